@@ -0,0 +1,200 @@
+//! Server socket selection.
+//!
+//! [`bind`] produces a [`BoundListener`] from the server configuration, in
+//! order of preference:
+//!
+//! 1. A socket inherited through systemd socket activation (`LISTEN_FDS`),
+//!    which may be either a TCP or a Unix domain stream socket.
+//! 2. A Unix domain socket at [`ServerConfig::unix_socket_path`], created
+//!    with the configured permissions.
+//! 3. A TCP socket at `hostname:port`.
+//!
+//! TCP sockets are wrapped in a [`TlsListener`] when TLS is configured; TLS
+//! is never served on a Unix socket. Unix sockets are only available on Unix
+//! platforms.
+
+#[cfg(unix)]
+use std::{
+    net::IpAddr,
+    os::unix::fs::{FileTypeExt, PermissionsExt},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+#[cfg(unix)]
+use axum::serve::Listener;
+#[cfg(unix)]
+use controller::rate_limit::PeerListener;
+use listenfd::ListenFd;
+use tokio::net::TcpListener;
+#[cfg(unix)]
+use tokio::net::UnixListener;
+
+use crate::{
+    config::{ServerConfig, TlsConfig},
+    tls::TlsListener,
+};
+
+/// A bound server socket, ready to be passed to `axum::serve`.
+pub enum BoundListener {
+    /// A plain-text TCP socket.
+    Tcp(TcpListener),
+    /// A TCP socket with in-process TLS termination.
+    Tls(TlsListener),
+    /// A Unix domain stream socket.
+    #[cfg(unix)]
+    Unix(UnixSocket),
+}
+
+/// A Unix domain socket listener that removes its socket file when dropped,
+/// unless it was inherited from the service manager.
+#[cfg(unix)]
+pub struct UnixSocket {
+    /// The underlying listener.
+    listener: UnixListener,
+    /// The socket file to remove on drop, if this process created it.
+    owned_path: Option<PathBuf>,
+}
+
+#[cfg(unix)]
+impl Listener for UnixSocket {
+    type Addr = <UnixListener as Listener>::Addr;
+    type Io = <UnixListener as Listener>::Io;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        Listener::accept(&mut self.listener).await
+    }
+
+    fn local_addr(&self) -> std::io::Result<Self::Addr> {
+        Listener::local_addr(&self.listener)
+    }
+}
+
+#[cfg(unix)]
+impl PeerListener for UnixSocket {
+    fn peer_ip(_addr: &Self::Addr) -> Option<IpAddr> {
+        None
+    }
+}
+
+#[cfg(unix)]
+impl Drop for UnixSocket {
+    fn drop(&mut self) {
+        if let Some(path) = &self.owned_path
+            && let Err(err) = std::fs::remove_file(path)
+        {
+            tracing::warn!(path = %path.display(), error = %err, "Failed to remove Unix socket file");
+        }
+    }
+}
+
+/// Binds (or inherits) the server socket described by `server` and `tls`.
+///
+/// # Errors
+///
+/// Returns an error if an inherited file descriptor is not a stream socket,
+/// if TLS is configured for an inherited Unix socket, or if the socket
+/// cannot be bound or configured.
+pub async fn bind(server: &ServerConfig, tls: &TlsConfig) -> Result<BoundListener> {
+    if let Some(listener) = take_activated_socket(tls)? {
+        return Ok(listener);
+    }
+
+    #[cfg(unix)]
+    if let Some(path) = &server.unix_socket_path {
+        let listener = bind_unix(path, server.unix_socket_mode)?;
+        tracing::info!(path = %path.display(), mode = format!("{:o}", server.unix_socket_mode), "Server listening (Unix socket)");
+
+        return Ok(BoundListener::Unix(UnixSocket {
+            listener,
+            owned_path: Some(path.clone()),
+        }));
+    }
+
+    let address = format!("{}:{}", server.hostname, server.port);
+    let listener = TcpListener::bind(address.as_str())
+        .await
+        .with_context(|| format!("Failed to bind '{address}'"))?;
+
+    tcp_or_tls(listener, tls, &address)
+}
+
+/// Takes the first socket passed by systemd socket activation, if any.
+fn take_activated_socket(tls: &TlsConfig) -> Result<Option<BoundListener>> {
+    let mut fds = ListenFd::from_env();
+    if fds.len() == 0 {
+        return Ok(None);
+    }
+
+    if fds.len() > 1 {
+        tracing::warn!(count = fds.len(), "Multiple sockets passed via LISTEN_FDS, only the first is used");
+    }
+
+    if let Ok(Some(listener)) = fds.take_tcp_listener(0) {
+        listener.set_nonblocking(true)?;
+        let listener = TcpListener::from_std(listener)?;
+        let address = listener.local_addr()?.to_string();
+
+        return tcp_or_tls(listener, tls, &format!("{address} (socket activation)")).map(Some);
+    }
+
+    take_activated_unix_socket(&mut fds, tls).map(Some)
+}
+
+/// Takes the first socket passed by systemd socket activation as a Unix
+/// domain stream socket, which cannot serve TLS.
+#[cfg(unix)]
+fn take_activated_unix_socket(fds: &mut ListenFd, tls: &TlsConfig) -> Result<BoundListener> {
+    let listener = fds
+        .take_unix_listener(0)
+        .context("LISTEN_FDS socket is neither a TCP nor a Unix stream socket")?
+        .context("LISTEN_FDS socket was already taken")?;
+    if tls.is_enabled() {
+        anyhow::bail!("LISTEN_FDS socket is a Unix socket, which cannot be combined with TLS");
+    }
+
+    listener.set_nonblocking(true)?;
+    tracing::info!("Server listening (Unix socket, socket activation)");
+
+    Ok(BoundListener::Unix(UnixSocket {
+        listener: UnixListener::from_std(listener)?,
+        owned_path: None,
+    }))
+}
+
+/// Rejects a socket passed by systemd socket activation that is not a TCP
+/// stream socket, since Unix sockets are unavailable on this platform.
+#[cfg(not(unix))]
+fn take_activated_unix_socket(_fds: &mut ListenFd, _tls: &TlsConfig) -> Result<BoundListener> {
+    anyhow::bail!("LISTEN_FDS socket is not a TCP stream socket")
+}
+
+/// Wraps a bound TCP listener in TLS when configured and logs the address.
+fn tcp_or_tls(listener: TcpListener, tls: &TlsConfig, address: &str) -> Result<BoundListener> {
+    if tls.is_enabled() {
+        tracing::info!(address, mutual_tls = tls.client_ca_path.is_some(), "Server listening (HTTPS)");
+        return Ok(BoundListener::Tls(TlsListener::new(listener, tls)?));
+    }
+
+    tracing::info!(address, "Server listening");
+    Ok(BoundListener::Tcp(listener))
+}
+
+/// Binds a Unix domain socket at `path`, replacing a stale socket file left
+/// by a previous run, and applies the given permission bits.
+#[cfg(unix)]
+fn bind_unix(path: &Path, mode: u32) -> Result<UnixListener> {
+    if let Ok(metadata) = std::fs::symlink_metadata(path) {
+        if !metadata.file_type().is_socket() {
+            anyhow::bail!("'{}' exists and is not a socket", path.display());
+        }
+
+        std::fs::remove_file(path).with_context(|| format!("Failed to remove stale socket '{}'", path.display()))?;
+    }
+
+    let listener = UnixListener::bind(path).with_context(|| format!("Failed to bind Unix socket '{}'", path.display()))?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))
+        .with_context(|| format!("Failed to set permissions on '{}'", path.display()))?;
+
+    Ok(listener)
+}
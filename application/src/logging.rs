@@ -1,19 +1,79 @@
 //! Application-wide logging initialisation.
 //!
 //! Configures a [`tracing_subscriber`] layer that outputs log events to the
-//! console using the default formatter. The log level can be controlled via
-//! the `RUST_LOG` environment variable (e.g. `RUST_LOG=warn`); if unset, it
-//! defaults to `debug`.
+//! console, either with the default human-readable formatter or as one JSON
+//! object per line, as selected by [`LoggingConfig::format`]. The log level
+//! can be controlled via the `RUST_LOG` environment variable (e.g.
+//! `RUST_LOG=warn`); if unset, [`LoggingConfig::level`] is used. When an
+//! OTLP endpoint is configured (see [`telemetry`](crate::telemetry)), spans
+//! are additionally exported through an OpenTelemetry layer.
 
+use anyhow::Result;
+use clap::ValueEnum;
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use serde::{Deserialize, Serialize};
 use tracing_subscriber::{EnvFilter, fmt, layer::SubscriberExt, util::SubscriberInitExt};
 
-/// Initialises the global tracing subscriber with the default formatter.
+use crate::{config::LoggingConfig, telemetry};
+
+/// The output format of console log lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable, coloured single-line output.
+    #[default]
+    Text,
+    /// One JSON object per line, including the fields of every enclosing
+    /// span (e.g. `request_id`).
+    Json,
+}
+
+/// Keeps the tracer provider alive for the lifetime of the application so
+/// that buffered spans can be flushed on shutdown.
+pub struct LoggingGuard {
+    /// The OpenTelemetry tracer provider, if export is enabled.
+    tracer_provider: Option<SdkTracerProvider>,
+}
+
+impl LoggingGuard {
+    /// Flushes any pending spans and shuts down the exporter. A failure is
+    /// logged through the console layers, which remain installed.
+    pub fn shutdown(self) {
+        if let Some(provider) = self.tracer_provider
+            && let Err(err) = provider.shutdown()
+        {
+            tracing::warn!(error = %err, "Failed to shut down tracer provider");
+        }
+    }
+}
+
+/// Initialises the global tracing subscriber with the configured console
+/// format and, when configured, an OpenTelemetry export layer.
 ///
 /// The log level can be controlled via the `RUST_LOG` environment variable
-/// (e.g. `RUST_LOG=warn`). If unset, it defaults to `debug`.
-pub fn init() {
+/// (e.g. `RUST_LOG=warn`). If unset, the configured level is used.
+///
+/// # Errors
+///
+/// Returns an error if the OTLP exporter cannot be configured.
+pub fn init(config: &LoggingConfig) -> Result<LoggingGuard> {
+    let format = config.format;
+
+    let tracer_provider = telemetry::init_tracer_provider()?;
+    let otel_layer = tracer_provider
+        .as_ref()
+        .map(|provider| tracing_opentelemetry::layer().with_tracer(provider.tracer(env!("CARGO_PKG_NAME"))));
+
+    let text_layer = (format == LogFormat::Text).then(fmt::layer);
+    let json_layer = (format == LogFormat::Json).then(|| fmt::layer().json().with_current_span(true).with_span_list(true));
+
     tracing_subscriber::registry()
-        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("debug")))
-        .with(fmt::layer())
+        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(&config.level)))
+        .with(text_layer)
+        .with(json_layer)
+        .with(otel_layer)
         .init();
+
+    Ok(LoggingGuard { tracer_provider })
 }
@@ -1,62 +1,167 @@
 //! Application entry point for the notes REST API.
 //!
-//! Reads configuration from environment variables, initialises the database
-//! connection, runs pending migrations, and starts the Axum HTTP server.
-
+//! Loads the layered [`AppConfig`] (TOML file, environment variables, and
+//! command-line flags), initialises the database connection, runs pending
+//! migrations, and starts the Axum HTTP server on a TCP socket, a Unix domain
+//! socket, or a systemd-activated socket (see [`listener`]), optionally
+//! terminating TLS in-process (see [`tls`]). API requests are rate limited
+//! per client unless disabled in the configuration. Due note reminders are
+//! fired by a background scheduler (see [`reminders`]). On SIGTERM or SIGINT
+//! the server fails its readiness probe for the configured pre-drain delay,
+//! then stops accepting connections and the scheduler, drains in-flight
+//! requests for up to the configured timeout, and closes the database pool.
+//! Tracing export to an OTLP collector is enabled via the standard
+//! `OTEL_EXPORTER_OTLP_*` environment variables.
+
+mod config;
+mod listener;
 mod logging;
+mod reminders;
+mod shutdown;
+mod telemetry;
+mod tls;
+
+use std::{fmt::Debug, future::IntoFuture, time::Duration};
 
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use axum::Router;
-use controller::AppRouter;
+use clap::Parser;
+use controller::{
+    AppRouter,
+    health::Readiness,
+    rate_limit::{ClientAddr, PeerListener},
+};
 use migration::MigratorTrait;
-use repository::database::DatabaseManager;
-use repository::note::NoteRepositoryImpl;
-use service::note::NoteServiceImpl;
-use tokio::net::TcpListener;
-
-/// Environment variable key for the database connection URL.
-const ENV_DATABASE_URL: &str = "DATABASE_URL";
-
-/// Environment variable key for the server bind hostname.
-const ENV_SERVER_HOSTNAME: &str = "SERVER_HOSTNAME";
-
-/// Environment variable key for the server bind port.
-const ENV_SERVER_PORT: &str = "SERVER_PORT";
-
-/// Fallback database URL when `DATABASE_URL` is not set (in-memory SQLite).
-const DEFAULT_DATABASE_URL: &str = "sqlite::memory:";
-
-/// Fallback hostname when `SERVER_HOSTNAME` is not set.
-const DEFAULT_SERVER_HOSTNAME: &str = "localhost";
-
-/// Fallback port when `SERVER_PORT` is not set.
-const DEFAULT_SERVER_PORT: &str = "8080";
+use repository::{
+    database::DatabaseManager, note::NoteRepositoryImpl, property_schema::PropertySchemaRepositoryImpl, reminder::ReminderRepositoryImpl,
+    saved_search::SavedSearchRepositoryImpl, task::TaskRepositoryImpl, template::TemplateRepositoryImpl,
+};
+use service::{
+    note::NoteServiceImpl, property_schema::PropertySchemaServiceImpl, reminder::ReminderServiceImpl, saved_search::SavedSearchServiceImpl,
+    task::TaskServiceImpl, template::TemplateServiceImpl,
+};
+
+use crate::{
+    config::{AppConfig, Cli, redact_url},
+    listener::BoundListener,
+    reminders::ReminderScheduler,
+    shutdown::Shutdown,
+};
+
+/// Maximum time to wait for the database pool to close on shutdown.
+const POOL_CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Bootstraps the database, runs migrations, wires all layers together, and
-/// starts serving HTTP requests.
+/// serves HTTP requests until a termination signal is received.
+///
+/// With `--print-config`, prints the effective configuration and exits
+/// without starting the server.
 #[tokio::main]
 async fn main() -> Result<()> {
-    logging::init();
+    let cli = Cli::parse();
+    let config = AppConfig::load(&cli)?;
+
+    if cli.print_config {
+        print!("{}", config.to_toml()?);
+        return Ok(());
+    }
 
-    let database_url: String = std::env::var(ENV_DATABASE_URL).unwrap_or_else(|_| DEFAULT_DATABASE_URL.into());
+    let logging_guard = logging::init(&config.logging)?;
 
-    tracing::info!(url = %database_url, "Connecting to database");
-    let database_manager = DatabaseManager::new(&database_url).await?;
+    tracing::info!(url = %redact_url(&config.database.url, false), "Connecting to database");
+    let database_manager = DatabaseManager::new(&config.database.url, config.database.pool_options()).await?;
 
     tracing::info!("Running database migrations");
     migration::Migrator::up(database_manager.connection(), None).await?;
 
-    let repository = NoteRepositoryImpl::new(database_manager.into_connection());
-    let service = NoteServiceImpl::new(repository);
-    let router: Router = AppRouter::new(service).into();
-
-    let server_hostname: String = std::env::var(ENV_SERVER_HOSTNAME).unwrap_or_else(|_| DEFAULT_SERVER_HOSTNAME.into());
-    let server_port: String = std::env::var(ENV_SERVER_PORT).unwrap_or_else(|_| DEFAULT_SERVER_PORT.into());
-    let server_url: String = format!("{server_hostname}:{server_port}");
-
-    let listener = TcpListener::bind(server_url.as_str()).await?;
-    tracing::info!(address = %server_url, "Server listening");
-    axum::serve(listener, router).await?;
+    let readiness = Readiness::new();
+    let shutdown = Shutdown::new(readiness.clone()).pre_drain_delay(config.server.pre_drain_delay());
+
+    let date_display = config.dates.display().map_err(|errors| anyhow!(errors.join("; ")))?;
+    let connection = database_manager.connection();
+    let note_limits = config.notes.limits();
+    let property_schemas = PropertySchemaRepositoryImpl::new(connection.clone());
+    let notes = NoteServiceImpl::new(NoteRepositoryImpl::new(connection.clone()), property_schemas.clone(), note_limits)
+        .idempotency_ttl(config.notes.idempotency_ttl());
+    let reminders = ReminderServiceImpl::new(ReminderRepositoryImpl::new(connection.clone()), note_limits).time_zone(date_display.time_zone);
+    let tasks = TaskServiceImpl::new(TaskRepositoryImpl::new(connection.clone()), note_limits);
+    let saved_searches = SavedSearchServiceImpl::new(SavedSearchRepositoryImpl::new(connection.clone()), notes.clone(), note_limits);
+    let templates = TemplateServiceImpl::new(TemplateRepositoryImpl::new(connection.clone()), notes.clone());
+    let property_schemas = PropertySchemaServiceImpl::new(property_schemas);
+    let scheduler = config
+        .reminders
+        .enabled
+        .then(|| ReminderScheduler::new(reminders.clone(), &config.reminders))
+        .transpose()?;
+    let mut app = AppRouter::new(notes, reminders, tasks, saved_searches, templates, property_schemas)
+        .readiness(readiness)
+        .limits(config.limits.request_limits())
+        .compression(config.compression.compression())
+        .date_display(date_display);
+    if let Some(policy) = config.cors.policy().map_err(|errors| anyhow!(errors.join("; ")))? {
+        app = app.cors(policy);
+    }
+    if let Some(rate_limiter) = config.rate_limit.rate_limiter() {
+        if config.server.unix_socket_path.is_some() && !config.rate_limit.trust_forwarded_for {
+            tracing::warn!("Rate limiting on a Unix socket without trusting X-Forwarded-For; all clients share one budget");
+        }
+        app = app.rate_limiter(rate_limiter);
+    }
+    if let Some(scheduler) = &scheduler {
+        app = app.reminder_events(scheduler.events());
+    }
+    let router: Router = app.into();
+    let scheduler = scheduler.map(|scheduler| tokio::spawn(scheduler.run(shutdown.clone())));
+
+    let drain_timeout = config.server.drain_timeout();
+
+    let drained = match listener::bind(&config.server, &config.tls).await? {
+        BoundListener::Tcp(listener) => serve(listener, router, &shutdown, drain_timeout).await?,
+        BoundListener::Tls(listener) => serve(listener, router, &shutdown, drain_timeout).await?,
+        #[cfg(unix)]
+        BoundListener::Unix(listener) => serve(listener, router, &shutdown, drain_timeout).await?,
+    };
+
+    if let Some(scheduler) = scheduler {
+        if drained {
+            scheduler.await?;
+        } else {
+            scheduler.abort();
+        }
+    }
+
+    // Abandoned requests may still hold pooled connections, so closing the
+    // pool is bounded rather than awaited indefinitely.
+    match tokio::time::timeout(POOL_CLOSE_TIMEOUT, database_manager.close()).await {
+        Ok(result) => result?,
+        Err(_) => tracing::warn!(timeout = ?POOL_CLOSE_TIMEOUT, "Timed out closing the database pool"),
+    }
+
+    logging_guard.shutdown();
 
     Ok(())
 }
+
+/// Serves `router` on `listener` until a termination signal is received,
+/// then drains in-flight requests for at most `drain_timeout`.
+///
+/// Returns `true` if every request completed before the deadline.
+async fn serve<L>(listener: L, router: Router, shutdown: &Shutdown, drain_timeout: Duration) -> Result<bool>
+where
+    L: PeerListener,
+    L::Addr: Debug,
+{
+    let server = axum::serve(listener, router.into_make_service_with_connect_info::<ClientAddr>()).with_graceful_shutdown(shutdown.clone().signal());
+
+    tokio::select! {
+        result = server.into_future() => {
+            result?;
+            tracing::info!("All in-flight requests drained");
+            Ok(true)
+        },
+        () = shutdown.drain_deadline(drain_timeout) => {
+            tracing::warn!(?drain_timeout, "Drain timeout elapsed, abandoning in-flight requests");
+            Ok(false)
+        },
+    }
+}
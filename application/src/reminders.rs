@@ -0,0 +1,157 @@
+//! Background firing of note reminders.
+//!
+//! [`ReminderScheduler`] asks the [`ReminderService`] for due reminders at a
+//! fixed interval until shutdown starts. Every fired reminder is logged,
+//! published to the `/api/reminders/events` stream, and, when a webhook is
+//! configured, posted to it as JSON. Webhook deliveries run in the
+//! background and are not retried; the scheduler waits for those still in
+//! flight, each bounded by the webhook timeout, before it stops.
+//!
+//! The event stream is per instance: when several instances share a
+//! database, a reminder fires on exactly one of them, and only clients
+//! streaming from that instance receive it. Consumers that need every
+//! reminder should use the webhook instead.
+
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use model::dto::reminder::ReminderEvent;
+use rustls::crypto::ring;
+use service::reminder::ReminderService;
+use tokio::{sync::broadcast, task::JoinSet, time::MissedTickBehavior};
+
+use crate::{config::RemindersConfig, shutdown::Shutdown};
+
+/// Number of fired reminders buffered for each event stream; slower clients
+/// skip the oldest.
+const EVENT_CAPACITY: usize = 256;
+
+/// Periodically fires due reminders and publishes them.
+pub struct ReminderScheduler<Service: ReminderService> {
+    /// The service that fires the reminders.
+    service: Service,
+    /// The channel fired reminders are published on. Dropping it ends every
+    /// open event stream.
+    events: broadcast::Sender<ReminderEvent>,
+    /// The webhook fired reminders are posted to, if any.
+    webhook: Option<Webhook>,
+    /// The interval between checks for due reminders.
+    poll_interval: Duration,
+}
+
+impl<Service: ReminderService> ReminderScheduler<Service> {
+    /// Creates a scheduler for the given service and settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the webhook client cannot be built.
+    pub fn new(service: Service, config: &RemindersConfig) -> Result<Self> {
+        let webhook = config
+            .webhook_url
+            .as_deref()
+            .map(|url| Webhook::new(url, config.webhook_timeout()))
+            .transpose()?;
+
+        Ok(Self {
+            service,
+            events: broadcast::Sender::new(EVENT_CAPACITY),
+            webhook,
+            poll_interval: config.poll_interval(),
+        })
+    }
+
+    /// Returns a weak handle to the event channel for the router, so that
+    /// the channel closes when the scheduler stops.
+    pub fn events(&self) -> broadcast::WeakSender<ReminderEvent> {
+        self.events.downgrade()
+    }
+
+    /// Fires due reminders every poll interval until shutdown starts, then
+    /// waits for pending webhook deliveries.
+    pub async fn run(self, shutdown: Shutdown) {
+        let mut deliveries = JoinSet::new();
+        let mut ticker = tokio::time::interval(self.poll_interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        tracing::info!(poll_interval = ?self.poll_interval, webhook = self.webhook.is_some(), "Reminder scheduler started");
+
+        loop {
+            tokio::select! {
+                () = shutdown.started() => break,
+                _ = ticker.tick() => {},
+            }
+
+            while deliveries.try_join_next().is_some() {}
+
+            match self.service.fire_due_reminders(Utc::now()).await {
+                Ok(events) => events.into_iter().for_each(|event| self.publish(event, &mut deliveries)),
+                Err(err) => tracing::warn!(error = %err, "Failed to fire due reminders"),
+            }
+        }
+
+        if !deliveries.is_empty() {
+            tracing::info!(pending = deliveries.len(), "Waiting for reminder webhook deliveries");
+            deliveries.join_all().await;
+        }
+
+        tracing::info!("Reminder scheduler stopped");
+    }
+
+    /// Logs a fired reminder and hands it to the event streams and the
+    /// webhook, tracking the delivery in `deliveries`.
+    fn publish(&self, event: ReminderEvent, deliveries: &mut JoinSet<()>) {
+        tracing::info!(
+            note_id = event.note_id,
+            title = %event.title,
+            remind_at = %event.remind_at,
+            next_remind_at = ?event.next_remind_at,
+            "Reminder fired"
+        );
+
+        // Sending only fails when no client is streaming events.
+        let _ = self.events.send(event.clone());
+
+        if let Some(webhook) = &self.webhook {
+            deliveries.spawn(webhook.deliver(event));
+        }
+    }
+}
+
+/// An HTTP endpoint that fired reminders are posted to.
+struct Webhook {
+    /// The client used for every delivery.
+    client: reqwest::Client,
+    /// The URL deliveries are posted to.
+    url: reqwest::Url,
+}
+
+impl Webhook {
+    /// Builds the client for the given URL, bounding each delivery by
+    /// `timeout`.
+    fn new(url: &str, timeout: Duration) -> Result<Self> {
+        // The TLS listener passes its provider explicitly, so the process
+        // default is only claimed here; a provider installed earlier wins.
+        let _ = ring::default_provider().install_default();
+
+        let url = reqwest::Url::parse(url).with_context(|| format!("Invalid reminder webhook URL '{url}'"))?;
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .context("Failed to build the reminder webhook client")?;
+
+        Ok(Self { client, url })
+    }
+
+    /// Returns a future that posts the event, logging the outcome.
+    fn deliver(&self, event: ReminderEvent) -> impl Future<Output = ()> + Send + 'static {
+        let request = self.client.post(self.url.clone()).json(&event);
+
+        async move {
+            match request.send().await.and_then(reqwest::Response::error_for_status) {
+                Ok(response) => tracing::debug!(note_id = event.note_id, status = %response.status(), "Reminder webhook delivered"),
+                Err(err) => tracing::warn!(note_id = event.note_id, error = %err, "Reminder webhook delivery failed"),
+            }
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! Graceful shutdown coordination.
+//!
+//! On SIGTERM or SIGINT, [`Shutdown`] flips the shared [`Readiness`] to
+//! failing, keeps serving for the pre-drain delay so that probes observe the
+//! failure and load balancers stop routing to the instance, and then
+//! resolves its [`signal`](Shutdown::signal) future and starts the drain
+//! deadline tracked by [`drain_deadline`](Shutdown::drain_deadline). A second
+//! signal skips the rest of the delay.
+//! Background tasks stop once [`started`](Shutdown::started) resolves.
+
+use std::time::Duration;
+
+use controller::health::Readiness;
+use tokio::sync::watch;
+
+/// Shared handle that turns an OS termination signal into a graceful
+/// shutdown with a bounded drain period.
+#[derive(Clone)]
+pub struct Shutdown {
+    /// Readiness flag flipped as soon as shutdown starts.
+    readiness: Readiness,
+    /// Time between failing readiness and closing the listener.
+    pre_drain_delay: Duration,
+    /// Broadcasts `true` once a termination signal has been received.
+    started: watch::Sender<bool>,
+}
+
+impl Shutdown {
+    /// Creates a new coordinator that will flip the given readiness handle.
+    pub fn new(readiness: Readiness) -> Self {
+        Self {
+            readiness,
+            pre_drain_delay: Duration::ZERO,
+            started: watch::Sender::new(false),
+        }
+    }
+
+    /// Sets the time between failing readiness and closing the listener.
+    pub fn pre_drain_delay(mut self, delay: Duration) -> Self {
+        self.pre_drain_delay = delay;
+        self
+    }
+
+    /// Resolves the pre-drain delay after SIGTERM or SIGINT is received,
+    /// having marked the instance as not ready, and starts the drain
+    /// deadline.
+    ///
+    /// Intended to be passed to `with_graceful_shutdown`.
+    pub async fn signal(self) {
+        let signal = wait_for_signal().await;
+        self.readiness.mark_not_ready();
+
+        if !self.pre_drain_delay.is_zero() {
+            tracing::info!(signal, delay = ?self.pre_drain_delay, "Shutdown signal received, failing readiness before draining");
+            tokio::select! {
+                () = tokio::time::sleep(self.pre_drain_delay) => {},
+                signal = wait_for_signal() => tracing::info!(signal, "Second shutdown signal received, skipping the pre-drain delay"),
+            }
+        }
+
+        tracing::info!(signal, "Draining in-flight requests");
+        self.started.send_replace(true);
+    }
+
+    /// Resolves once shutdown has started; never resolves if no signal is
+    /// received.
+    pub async fn started(&self) {
+        let mut started = self.started.subscribe();
+        if started.wait_for(|started| *started).await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    }
+
+    /// Resolves `drain_timeout` after shutdown has started; never resolves
+    /// if no signal is received.
+    pub async fn drain_deadline(&self, drain_timeout: Duration) {
+        self.started().await;
+        tokio::time::sleep(drain_timeout).await;
+    }
+}
+
+/// Waits for the first termination signal and returns its name.
+#[cfg(unix)]
+async fn wait_for_signal() -> &'static str {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut terminate = signal(SignalKind::terminate()).expect("failed to install SIGTERM handler");
+    let mut interrupt = signal(SignalKind::interrupt()).expect("failed to install SIGINT handler");
+
+    tokio::select! {
+        _ = terminate.recv() => "SIGTERM",
+        _ = interrupt.recv() => "SIGINT",
+    }
+}
+
+/// Waits for Ctrl+C and returns its name.
+#[cfg(not(unix))]
+async fn wait_for_signal() -> &'static str {
+    tokio::signal::ctrl_c().await.expect("failed to install Ctrl+C handler");
+    "Ctrl+C"
+}
@@ -0,0 +1,60 @@
+//! OpenTelemetry trace export.
+//!
+//! When `OTEL_EXPORTER_OTLP_ENDPOINT` is set, spans are batched and exported
+//! to an OTLP collector over gRPC (default) or HTTP/protobuf, selected via
+//! `OTEL_EXPORTER_OTLP_PROTOCOL`. The W3C trace-context propagator is always
+//! installed so that incoming `traceparent` headers are honoured.
+
+use anyhow::{Result, bail};
+use opentelemetry::global;
+use opentelemetry_otlp::{Protocol, SpanExporter, WithExportConfig};
+use opentelemetry_sdk::{Resource, propagation::TraceContextPropagator, trace::SdkTracerProvider};
+
+/// Environment variable key for the OTLP collector endpoint.
+const ENV_OTLP_ENDPOINT: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+
+/// Environment variable key for the OTLP transport protocol.
+const ENV_OTLP_PROTOCOL: &str = "OTEL_EXPORTER_OTLP_PROTOCOL";
+
+/// Environment variable key for the service name reported to the collector.
+const ENV_SERVICE_NAME: &str = "OTEL_SERVICE_NAME";
+
+/// Fallback service name when `OTEL_SERVICE_NAME` is not set.
+const DEFAULT_SERVICE_NAME: &str = env!("CARGO_PKG_NAME");
+
+/// Installs the W3C trace-context propagator and, if an OTLP endpoint is
+/// configured, builds a tracer provider that exports spans to it.
+///
+/// Returns `Ok(None)` when export is disabled.
+///
+/// # Errors
+///
+/// Returns an error if the protocol is unrecognised or the exporter cannot
+/// be built.
+pub fn init_tracer_provider() -> Result<Option<SdkTracerProvider>> {
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let Ok(endpoint) = std::env::var(ENV_OTLP_ENDPOINT) else {
+        return Ok(None);
+    };
+
+    let protocol = std::env::var(ENV_OTLP_PROTOCOL).unwrap_or_else(|_| "grpc".into());
+    let exporter = match protocol.as_str() {
+        "grpc" => SpanExporter::builder().with_tonic().with_endpoint(endpoint).build()?,
+        "http/protobuf" => SpanExporter::builder()
+            .with_http()
+            .with_protocol(Protocol::HttpBinary)
+            .with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+            .build()?,
+        other => bail!("Unsupported {ENV_OTLP_PROTOCOL} '{other}', expected 'grpc' or 'http/protobuf'"),
+    };
+
+    let service_name = std::env::var(ENV_SERVICE_NAME).unwrap_or_else(|_| DEFAULT_SERVICE_NAME.into());
+    let resource = Resource::builder().with_service_name(service_name).build();
+
+    let provider = SdkTracerProvider::builder().with_resource(resource).with_batch_exporter(exporter).build();
+
+    global::set_tracer_provider(provider.clone());
+
+    Ok(Some(provider))
+}
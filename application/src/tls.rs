@@ -0,0 +1,305 @@
+//! In-process TLS termination.
+//!
+//! [`TlsListener`] wraps a [`TcpListener`] and performs a bounded number of
+//! rustls handshakes concurrently in background tasks, so a slow client
+//! cannot stall the accept loop, nor a flood of them exhaust memory. It
+//! implements Axum's [`Listener`] trait and can be passed straight to
+//! [`axum::serve`]. HTTP/2 and HTTP/1.1 are offered via ALPN.
+//!
+//! The certificate chain and private key are re-read whenever their
+//! modification times change, so renewed certificates are picked up without
+//! a restart. When a client CA bundle is configured, client certificates are
+//! verified against it (mutual TLS).
+
+use std::{
+    fmt,
+    net::{IpAddr, SocketAddr},
+    path::Path,
+    sync::{Arc, RwLock, Weak},
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{Context, Result, anyhow};
+use axum::serve::Listener;
+use controller::rate_limit::PeerListener;
+use rustls::{
+    RootCertStore, ServerConfig,
+    crypto::{CryptoProvider, ring},
+    pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject},
+    server::{ClientHello, ResolvesServerCert, WebPkiClientVerifier},
+    sign::CertifiedKey,
+};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::{Semaphore, mpsc},
+};
+use tokio_rustls::{TlsAcceptor, server::TlsStream};
+
+use crate::config::TlsConfig;
+
+/// Number of completed handshakes that may be buffered before the accept
+/// loop applies back-pressure.
+const ACCEPT_BACKLOG: usize = 128;
+
+/// Maximum number of TLS handshakes in progress at once; further TCP
+/// connections wait in the kernel backlog until a handshake finishes.
+const MAX_PENDING_HANDSHAKES: usize = 512;
+
+/// ALPN protocol identifiers offered to clients, in order of preference.
+const ALPN_PROTOCOLS: &[&[u8]] = &[b"h2", b"http/1.1"];
+
+/// A [`Listener`] that yields TLS-wrapped TCP connections.
+pub struct TlsListener {
+    /// The address the underlying TCP socket is bound to.
+    local_addr: SocketAddr,
+    /// Connections whose TLS handshake has completed successfully.
+    connections: mpsc::Receiver<(TlsStream<TcpStream>, SocketAddr)>,
+}
+
+impl TlsListener {
+    /// Starts accepting TLS connections on an already-bound TCP `listener`
+    /// using the certificates and client-authentication settings in
+    /// `config`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the certificate, key, or client CA files cannot
+    /// be loaded.
+    pub fn new(listener: TcpListener, config: &TlsConfig) -> Result<Self> {
+        let acceptor = TlsAcceptor::from(Arc::new(build_server_config(config)?));
+        let local_addr = listener.local_addr()?;
+        let (sender, connections) = mpsc::channel(ACCEPT_BACKLOG);
+
+        tokio::spawn(accept_loop(listener, acceptor, sender, config.handshake_timeout()));
+
+        Ok(Self { local_addr, connections })
+    }
+}
+
+impl Listener for TlsListener {
+    type Addr = SocketAddr;
+    type Io = TlsStream<TcpStream>;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        match self.connections.recv().await {
+            Some(connection) => connection,
+            None => std::future::pending().await,
+        }
+    }
+
+    fn local_addr(&self) -> std::io::Result<Self::Addr> {
+        Ok(self.local_addr)
+    }
+}
+
+impl PeerListener for TlsListener {
+    fn peer_ip(addr: &SocketAddr) -> Option<IpAddr> {
+        Some(addr.ip())
+    }
+}
+
+/// Accepts TCP connections and completes each TLS handshake in its own task
+/// until the [`TlsListener`] is dropped. At most [`MAX_PENDING_HANDSHAKES`]
+/// handshakes run at once.
+async fn accept_loop(
+    listener: TcpListener,
+    acceptor: TlsAcceptor,
+    sender: mpsc::Sender<(TlsStream<TcpStream>, SocketAddr)>,
+    handshake_timeout: Duration,
+) {
+    let handshakes = Arc::new(Semaphore::new(MAX_PENDING_HANDSHAKES));
+
+    loop {
+        let permit = tokio::select! {
+            () = sender.closed() => return,
+            permit = handshakes.clone().acquire_owned() => match permit {
+                Ok(permit) => permit,
+                Err(_) => return,
+            },
+        };
+
+        let (stream, peer) = tokio::select! {
+            () = sender.closed() => return,
+            accepted = listener.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(err) => {
+                    tracing::warn!(error = %err, "Failed to accept TCP connection");
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    continue;
+                },
+            },
+        };
+
+        let acceptor = acceptor.clone();
+        let sender = sender.clone();
+
+        tokio::spawn(async move {
+            let handshake = tokio::time::timeout(handshake_timeout, acceptor.accept(stream)).await;
+            drop(permit);
+
+            match handshake {
+                Ok(Ok(tls_stream)) => {
+                    let _ = sender.send((tls_stream, peer)).await;
+                },
+                Ok(Err(err)) => tracing::debug!(%peer, error = %err, "TLS handshake failed"),
+                Err(_) => tracing::debug!(%peer, "TLS handshake timed out"),
+            }
+        });
+    }
+}
+
+/// Builds the rustls server configuration, spawning the certificate reload
+/// task as a side effect.
+fn build_server_config(config: &TlsConfig) -> Result<ServerConfig> {
+    let provider = Arc::new(ring::default_provider());
+    let resolver = Arc::new(ReloadingCertResolver::load(config, provider.clone())?);
+    tokio::spawn(watch_certificates(Arc::downgrade(&resolver), config.reload_interval()));
+
+    let builder = ServerConfig::builder_with_provider(provider.clone()).with_safe_default_protocol_versions()?;
+
+    let builder = match &config.client_ca_path {
+        Some(path) => {
+            let mut roots = RootCertStore::empty();
+            for certificate in read_certificates(path)? {
+                roots.add(certificate)?;
+            }
+
+            let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider);
+            let verifier = if config.client_auth_optional {
+                verifier.allow_unauthenticated()
+            } else {
+                verifier
+            };
+
+            builder.with_client_cert_verifier(verifier.build()?)
+        },
+        None => builder.with_no_client_auth(),
+    };
+
+    let mut server_config = builder.with_cert_resolver(resolver);
+    server_config.alpn_protocols = ALPN_PROTOCOLS.iter().map(|protocol| protocol.to_vec()).collect();
+
+    Ok(server_config)
+}
+
+/// Periodically reloads the certificate while the resolver is still in use.
+async fn watch_certificates(resolver: Weak<ReloadingCertResolver>, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.tick().await;
+
+    loop {
+        ticker.tick().await;
+
+        let Some(resolver) = resolver.upgrade() else {
+            return;
+        };
+
+        resolver.reload_if_changed();
+    }
+}
+
+/// Serves the current certificate and swaps it for a fresh one whenever the
+/// files on disk change.
+struct ReloadingCertResolver {
+    /// Path to the PEM-encoded certificate chain.
+    cert_path: Box<Path>,
+    /// Path to the PEM-encoded private key.
+    key_path: Box<Path>,
+    /// Crypto provider used to parse the private key.
+    provider: Arc<CryptoProvider>,
+    /// The active certificate.
+    current: RwLock<LoadedCertificate>,
+}
+
+/// A certificate together with the file modification times it was loaded
+/// from.
+struct LoadedCertificate {
+    /// The parsed certificate chain and signing key.
+    certified_key: Arc<CertifiedKey>,
+    /// Modification times of the certificate and key files at load time.
+    modified: (Option<SystemTime>, Option<SystemTime>),
+}
+
+impl ReloadingCertResolver {
+    /// Loads the initial certificate chain and key.
+    fn load(config: &TlsConfig, provider: Arc<CryptoProvider>) -> Result<Self> {
+        let cert_path = config.cert_path.clone().ok_or_else(|| anyhow!("tls.cert_path is not set"))?;
+        let key_path = config.key_path.clone().ok_or_else(|| anyhow!("tls.key_path is not set"))?;
+        let current = LoadedCertificate {
+            certified_key: Arc::new(load_certified_key(&cert_path, &key_path, &provider)?),
+            modified: (modified(&cert_path), modified(&key_path)),
+        };
+
+        Ok(Self {
+            cert_path: cert_path.into(),
+            key_path: key_path.into(),
+            provider,
+            current: RwLock::new(current),
+        })
+    }
+
+    /// Reloads the certificate if either file's modification time changed.
+    ///
+    /// A failed reload is logged and the previous certificate kept, so a
+    /// half-written renewal never takes the listener down.
+    fn reload_if_changed(&self) {
+        let stamps = (modified(&self.cert_path), modified(&self.key_path));
+        if self.current.read().unwrap_or_else(|poisoned| poisoned.into_inner()).modified == stamps {
+            return;
+        }
+
+        match load_certified_key(&self.cert_path, &self.key_path, &self.provider) {
+            Ok(certified_key) => {
+                *self.current.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = LoadedCertificate {
+                    certified_key: Arc::new(certified_key),
+                    modified: stamps,
+                };
+                tracing::info!(cert = %self.cert_path.display(), "TLS certificate reloaded");
+            },
+            Err(err) => tracing::error!(cert = %self.cert_path.display(), error = %err, "Failed to reload TLS certificate"),
+        }
+    }
+}
+
+impl ResolvesServerCert for ReloadingCertResolver {
+    fn resolve(&self, _client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        Some(self.current.read().unwrap_or_else(|poisoned| poisoned.into_inner()).certified_key.clone())
+    }
+}
+
+impl fmt::Debug for ReloadingCertResolver {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ReloadingCertResolver")
+            .field("cert_path", &self.cert_path)
+            .field("key_path", &self.key_path)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Parses the certificate chain and private key into a [`CertifiedKey`],
+/// checking that they match.
+fn load_certified_key(cert_path: &Path, key_path: &Path, provider: &CryptoProvider) -> Result<CertifiedKey> {
+    let certificates = read_certificates(cert_path)?;
+    let key = PrivateKeyDer::from_pem_file(key_path).with_context(|| format!("Failed to read private key '{}'", key_path.display()))?;
+
+    CertifiedKey::from_der(certificates, key, provider).with_context(|| format!("Invalid certificate/key pair '{}'", cert_path.display()))
+}
+
+/// Reads every PEM-encoded certificate in the given file.
+fn read_certificates(path: &Path) -> Result<Vec<CertificateDer<'static>>> {
+    let certificates = CertificateDer::pem_file_iter(path)
+        .and_then(Iterator::collect::<Result<Vec<_>, _>>)
+        .with_context(|| format!("Failed to read certificates from '{}'", path.display()))?;
+
+    if certificates.is_empty() {
+        return Err(anyhow!("No certificates found in '{}'", path.display()));
+    }
+
+    Ok(certificates)
+}
+
+/// Returns the file's modification time, or [`None`] if it is unavailable.
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
@@ -0,0 +1,113 @@
+//! HTTP body compression.
+//!
+//! Responses are compressed with gzip, brotli, or zstd as negotiated by the
+//! client's `Accept-Encoding` header, provided they are at least
+//! [`Compression::min_size`] bytes long. Request bodies sent with
+//! `Content-Encoding: gzip` are decompressed transparently; the decompressed
+//! body may not exceed [`Compression::max_decompressed_size`] bytes, which
+//! guards against compression bombs. Any other request encoding is rejected
+//! with `415 Unsupported Media Type` and an `Accept-Encoding` header listing
+//! the supported ones.
+
+use axum::{
+    Router,
+    extract::{DefaultBodyLimit, Request},
+    http::{HeaderValue, header},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+};
+use tower_http::{
+    compression::{
+        CompressionLayer,
+        predicate::{NotForContentType, Predicate, SizeAbove},
+    },
+    decompression::RequestDecompressionLayer,
+};
+
+use crate::error::AppError;
+
+/// Default minimum response size, in bytes, before compression is applied.
+pub const DEFAULT_MIN_SIZE: u16 = 1024;
+
+/// Default maximum size of a decompressed request body, in bytes.
+pub const DEFAULT_MAX_DECOMPRESSED_SIZE: usize = 2 * 1024 * 1024;
+
+/// The request content codings the server decodes, as advertised in the
+/// `Accept-Encoding` header of a `415` response.
+const ACCEPTED_REQUEST_ENCODINGS: &str = "gzip, identity";
+
+/// Compression settings applied to the API routes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Compression {
+    /// Whether responses are compressed.
+    pub responses: bool,
+    /// Minimum response size, in bytes, before compression is applied.
+    pub min_size: u16,
+    /// Maximum size of a (decompressed) request body, in bytes.
+    pub max_decompressed_size: usize,
+}
+
+impl Default for Compression {
+    fn default() -> Self {
+        Self {
+            responses: true,
+            min_size: DEFAULT_MIN_SIZE,
+            max_decompressed_size: DEFAULT_MAX_DECOMPRESSED_SIZE,
+        }
+    }
+}
+
+impl Compression {
+    /// Wraps `router` with gzip request decompression and the decompressed
+    /// body limit, rejecting unsupported request encodings first.
+    pub(crate) fn decompress_requests<State>(&self, router: Router<State>) -> Router<State>
+    where
+        State: Clone + Send + Sync + 'static,
+    {
+        router
+            .layer(DefaultBodyLimit::max(self.max_decompressed_size))
+            .layer(RequestDecompressionLayer::new().gzip(true))
+            .layer(middleware::from_fn(reject_unsupported_encoding))
+    }
+
+    /// Wraps `router` with response compression, if enabled.
+    pub(crate) fn compress_responses<State>(&self, router: Router<State>) -> Router<State>
+    where
+        State: Clone + Send + Sync + 'static,
+    {
+        if !self.responses {
+            return router;
+        }
+
+        let predicate = SizeAbove::new(self.min_size)
+            .and(NotForContentType::GRPC)
+            .and(NotForContentType::IMAGES)
+            .and(NotForContentType::SSE);
+
+        router.layer(CompressionLayer::new().gzip(true).br(true).zstd(true).compress_when(predicate))
+    }
+}
+
+/// Axum middleware that rejects request bodies whose `Content-Encoding` is
+/// not among [`ACCEPTED_REQUEST_ENCODINGS`].
+async fn reject_unsupported_encoding(request: Request, next: Next) -> Response {
+    let encoding = request
+        .headers()
+        .get(header::CONTENT_ENCODING)
+        .map(|value| value.to_str().unwrap_or_default().trim());
+
+    if let Some(encoding) = encoding
+        && !ACCEPTED_REQUEST_ENCODINGS
+            .split(", ")
+            .any(|accepted| accepted.eq_ignore_ascii_case(encoding))
+    {
+        tracing::warn!(encoding, "Unsupported request content encoding");
+        let mut response = AppError::UnsupportedMediaType(format!("Content-Encoding '{encoding}' is not supported")).into_response();
+        response
+            .headers_mut()
+            .insert(header::ACCEPT_ENCODING, HeaderValue::from_static(ACCEPTED_REQUEST_ENCODINGS));
+        return response;
+    }
+
+    next.run(request).await
+}
@@ -0,0 +1,80 @@
+//! Cross-origin resource sharing (CORS) for browser clients.
+//!
+//! [`CorsPolicy`] describes which origins may call the API and what they may
+//! send and read. It is converted into a `tower-http` [`CorsLayer`], which
+//! answers preflight `OPTIONS` requests for every API route and decorates
+//! all other responses, including errors, with the matching
+//! `Access-Control-*` headers.
+
+use std::time::Duration;
+
+use axum::http::{HeaderName, HeaderValue, Method};
+use regex::Regex;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+/// The set of origins allowed to make cross-origin requests.
+#[derive(Debug, Clone)]
+pub enum AllowedOrigins {
+    /// Any origin (`Access-Control-Allow-Origin: *`); incompatible with
+    /// credentials.
+    Any,
+    /// Origins that exactly match one of the listed values or fully match
+    /// one of the patterns.
+    Matching {
+        /// Exact origins, e.g. `https://notes.example.com`.
+        exact: Vec<HeaderValue>,
+        /// Origin patterns, e.g. `^https://[a-z0-9-]+\.example\.com$`.
+        patterns: Vec<Regex>,
+    },
+}
+
+/// A CORS policy for the API routes.
+#[derive(Debug, Clone)]
+pub struct CorsPolicy {
+    /// Origins allowed to make cross-origin requests.
+    pub origins: AllowedOrigins,
+    /// Methods allowed in cross-origin requests.
+    pub methods: Vec<Method>,
+    /// Request headers allowed in cross-origin requests.
+    pub headers: Vec<HeaderName>,
+    /// Response headers readable by cross-origin scripts.
+    pub exposed_headers: Vec<HeaderName>,
+    /// Whether cookies and other credentials may be included.
+    pub allow_credentials: bool,
+    /// How long browsers may cache preflight responses.
+    pub max_age: Duration,
+}
+
+impl CorsPolicy {
+    /// Builds the `tower-http` layer enforcing this policy.
+    ///
+    /// # Panics
+    ///
+    /// Panics if credentials are allowed together with
+    /// [`AllowedOrigins::Any`], which browsers reject.
+    pub(crate) fn layer(&self) -> CorsLayer {
+        let origins = match &self.origins {
+            AllowedOrigins::Any => AllowOrigin::any(),
+            AllowedOrigins::Matching { exact, patterns } if patterns.is_empty() => AllowOrigin::list(exact.clone()),
+            AllowedOrigins::Matching { exact, patterns } => {
+                let exact = exact.clone();
+                let patterns = patterns.clone();
+
+                AllowOrigin::predicate(move |origin, _| {
+                    exact.contains(origin)
+                        || origin
+                            .to_str()
+                            .is_ok_and(|origin| patterns.iter().any(|pattern| pattern.is_match(origin)))
+                })
+            },
+        };
+
+        CorsLayer::new()
+            .allow_origin(origins)
+            .allow_methods(self.methods.clone())
+            .allow_headers(self.headers.clone())
+            .expose_headers(self.exposed_headers.clone())
+            .allow_credentials(self.allow_credentials)
+            .max_age(self.max_age)
+    }
+}
@@ -10,9 +10,12 @@ use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
 };
+use http_body_util::LengthLimitError;
 use model::dto::pagination::SearchParams;
 use service::error::ServiceError;
 
+use crate::request_id::RequestId;
+
 /// Unified application error that can originate from either the service
 /// layer or from Axum extraction failures.
 #[derive(Debug)]
@@ -22,6 +25,21 @@ pub enum AppError {
     /// A bad-request error caused by an invalid extractor input
     /// (query string, path parameter, or JSON body).
     BadRequest(String),
+    /// None of the representations the client accepts can be produced.
+    NotAcceptable(String),
+    /// The (decompressed) request body exceeds the configured size limit.
+    PayloadTooLarge(String),
+    /// The request body uses a content coding the server cannot decode.
+    UnsupportedMediaType(String),
+    /// The client has exhausted its rate-limit budget.
+    TooManyRequests(String),
+    /// The server is at its concurrency limit and shed the request.
+    ServiceUnavailable(String),
+    /// The request did not complete within its route's timeout.
+    GatewayTimeout(String),
+    /// An unexpected controller-level failure, e.g. while serialising a
+    /// response.
+    Internal(String),
 }
 
 impl From<ServiceError> for AppError {
@@ -30,23 +48,43 @@ impl From<ServiceError> for AppError {
     }
 }
 
-impl From<QueryRejection> for AppError {
-    fn from(rejection: QueryRejection) -> Self {
-        let body = rejection.body_text();
-        let prefix = if let Some(start) = body.find("unknown field `") {
-            let rest = &body[start + "unknown field `".len()..];
-            let field = rest.split('`').next().unwrap_or("unknown");
-            format!("Invalid query parameter '{field}'.")
-        } else {
-            "Invalid query parameters.".to_owned()
+impl AppError {
+    /// Builds the error for a rejected [`SearchParams`] query string, which
+    /// names the offending parameter, if known, and lists the valid ones.
+    pub fn search_params(rejection: QueryRejection) -> Self {
+        let prefix = match unknown_query_field(&rejection) {
+            Some(field) => format!("Invalid query parameter '{field}'."),
+            None => "Invalid query parameters.".to_owned(),
         };
 
         AppError::BadRequest(format!("{prefix} {}", SearchParams::params_hint()))
     }
 }
 
+impl From<QueryRejection> for AppError {
+    fn from(rejection: QueryRejection) -> Self {
+        match unknown_query_field(&rejection) {
+            Some(field) => AppError::BadRequest(format!("Invalid query parameter '{field}'.")),
+            None => AppError::BadRequest(rejection.body_text()),
+        }
+    }
+}
+
+/// Returns the name of the unknown parameter a query string was rejected
+/// for, if that was the cause.
+fn unknown_query_field(rejection: &QueryRejection) -> Option<String> {
+    let body = rejection.body_text();
+    let start = body.find("unknown field `")? + "unknown field `".len();
+
+    body[start..].split('`').next().map(str::to_owned)
+}
+
 impl From<JsonRejection> for AppError {
     fn from(rejection: JsonRejection) -> Self {
+        if rejection.status() == StatusCode::PAYLOAD_TOO_LARGE || exceeds_length_limit(&rejection) {
+            return AppError::PayloadTooLarge(rejection.body_text());
+        }
+
         AppError::BadRequest(rejection.body_text())
     }
 }
@@ -57,15 +95,44 @@ impl From<PathRejection> for AppError {
     }
 }
 
+/// Returns `true` if the rejection was caused by a body-size limit, even
+/// when the limit error is wrapped by an intermediate body (e.g. request
+/// decompression) and therefore not classified as `413` by Axum.
+fn exceeds_length_limit(rejection: &JsonRejection) -> bool {
+    let mut source: Option<&(dyn std::error::Error + 'static)> = Some(rejection);
+
+    while let Some(error) = source {
+        if error.is::<LengthLimitError>() {
+            return true;
+        }
+
+        source = error.source();
+    }
+
+    false
+}
+
 impl IntoResponse for AppError {
     /// Maps each [`AppError`] variant to an HTTP status code and a JSON
-    /// body of the form `{ "error": "<message>" }`.
+    /// body of the form `{ "error": "<message>", "requestId": "<id>" }`.
+    ///
+    /// `requestId` is omitted when the error is produced outside of a
+    /// request scope.
     fn into_response(self) -> Response {
         let (status, message) = match self {
             AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg),
+            AppError::NotAcceptable(msg) => (StatusCode::NOT_ACCEPTABLE, msg),
+            AppError::PayloadTooLarge(msg) => (StatusCode::PAYLOAD_TOO_LARGE, msg),
+            AppError::UnsupportedMediaType(msg) => (StatusCode::UNSUPPORTED_MEDIA_TYPE, msg),
+            AppError::TooManyRequests(msg) => (StatusCode::TOO_MANY_REQUESTS, msg),
+            AppError::ServiceUnavailable(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg),
+            AppError::GatewayTimeout(msg) => (StatusCode::GATEWAY_TIMEOUT, msg),
+            AppError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
             AppError::Service(service_error) => match service_error {
                 ServiceError::Validation(msg) => (StatusCode::BAD_REQUEST, msg),
                 ServiceError::NotFound { entity, id } => (StatusCode::NOT_FOUND, format!("{entity} with ID {id} not found")),
+                ServiceError::Conflict(msg) => (StatusCode::CONFLICT, msg),
+                ServiceError::Unprocessable(msg) => (StatusCode::UNPROCESSABLE_ENTITY, msg),
                 ServiceError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
             },
         };
@@ -76,7 +143,11 @@ impl IntoResponse for AppError {
             tracing::error!(status = %status, error = %message, "Server error");
         }
 
-        let body = Json(serde_json::json!({ "error": message }));
-        (status, body).into_response()
+        let mut body = serde_json::json!({ "error": message });
+        if let Some(request_id) = RequestId::current() {
+            body["requestId"] = request_id.as_str().into();
+        }
+
+        (status, Json(body)).into_response()
     }
 }
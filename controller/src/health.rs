@@ -0,0 +1,54 @@
+//! Liveness and readiness probe endpoints.
+//!
+//! [`Readiness`] is a shared flag that the application flips to "not ready"
+//! as soon as shutdown begins, so that load balancers stop routing new
+//! traffic to the instance whilst in-flight requests drain.
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
+
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+
+/// A cloneable handle to the instance's readiness state.
+#[derive(Debug, Clone)]
+pub struct Readiness(Arc<AtomicBool>);
+
+impl Readiness {
+    /// Creates a new handle in the ready state.
+    pub fn new() -> Self {
+        Self(Arc::new(AtomicBool::new(true)))
+    }
+
+    /// Returns `true` while the instance accepts new traffic.
+    pub fn is_ready(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+
+    /// Marks the instance as no longer accepting new traffic.
+    pub fn mark_not_ready(&self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+impl Default for Readiness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `GET /health/live` – always returns `200 OK` while the process is up.
+pub async fn live() -> impl IntoResponse {
+    Json(serde_json::json!({ "status": "up" }))
+}
+
+/// `GET /health/ready` – returns `200 OK` while ready, or
+/// `503 Service Unavailable` once shutdown has started.
+pub async fn ready(State(readiness): State<Readiness>) -> impl IntoResponse {
+    if readiness.is_ready() {
+        (StatusCode::OK, Json(serde_json::json!({ "status": "ready" })))
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({ "status": "shutting down" })))
+    }
+}
@@ -1,11 +1,24 @@
 //! HTTP controller layer for the notes application.
 //!
-//! This crate wires Axum route handlers to the [`NoteService`](service::note::NoteService)
-//! trait, translating HTTP requests into service calls and service errors into
-//! JSON error responses.
+//! This crate wires Axum route handlers to the service traits, such as
+//! [`NoteService`](service::note::NoteService), translating HTTP requests
+//! into service calls and service errors into JSON error responses.
 
+pub mod compression;
+pub mod cors;
 pub mod error;
+pub mod health;
+pub mod limits;
+pub mod negotiation;
 pub mod note;
+pub mod property_schema;
+pub mod rate_limit;
+pub mod reminder;
+pub mod request_id;
 pub mod router;
+pub mod saved_search;
+pub mod task;
+pub mod telemetry;
+pub mod template;
 
 pub use router::AppRouter;
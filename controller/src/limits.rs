@@ -0,0 +1,98 @@
+//! Request size, duration, and concurrency limits.
+//!
+//! * [`limit_body_size`] rejects request bodies larger than
+//!   [`RequestLimits::max_body_size`] bytes on the wire with
+//!   `413 Payload Too Large`.
+//! * [`shed_load`] caps the number of API requests processed at once and
+//!   answers the excess immediately with `503 Service Unavailable` instead
+//!   of queueing it behind the (possibly single) database connection.
+//! * [`enforce_timeout`] aborts a handler that exceeds its route's timeout
+//!   with `504 Gateway Timeout`, releasing any database connection it held.
+
+use std::{sync::Arc, time::Duration};
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{HeaderValue, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use http_body_util::Limited;
+use tokio::sync::Semaphore;
+
+use crate::error::AppError;
+
+/// Size, duration, and concurrency limits applied to the API routes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestLimits {
+    /// Maximum request body size on the wire, in bytes.
+    pub max_body_size: usize,
+    /// Maximum number of API requests processed concurrently.
+    pub max_concurrent_requests: usize,
+    /// Timeout for reading a single note or the saved searches.
+    pub read_timeout: Duration,
+    /// Timeout for listing notes, including executing a saved search.
+    pub list_timeout: Duration,
+    /// Timeout for requests that modify notes or saved searches.
+    pub write_timeout: Duration,
+}
+
+impl Default for RequestLimits {
+    fn default() -> Self {
+        Self {
+            max_body_size: 1024 * 1024,
+            max_concurrent_requests: 512,
+            read_timeout: Duration::from_secs(5),
+            list_timeout: Duration::from_secs(10),
+            write_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Axum middleware that rejects request bodies larger than `max_body_size`.
+///
+/// Bodies with a declared `Content-Length` are rejected before being read;
+/// streamed bodies are cut off once the limit is reached, which surfaces as
+/// a `413` when the handler buffers them.
+pub async fn limit_body_size(State(max_body_size): State<usize>, request: Request, next: Next) -> Response {
+    let declared = request
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+
+    if let Some(length) = declared
+        && length > max_body_size as u64
+    {
+        tracing::warn!(length, max = max_body_size, "Request body too large");
+        return AppError::PayloadTooLarge(format!("Request body must not exceed {max_body_size} bytes")).into_response();
+    }
+
+    let request = request.map(|body| Body::new(Limited::new(body, max_body_size)));
+
+    next.run(request).await
+}
+
+/// Axum middleware that sheds load once all concurrency permits are taken.
+pub async fn shed_load(State(permits): State<Arc<Semaphore>>, request: Request, next: Next) -> Response {
+    let Ok(_permit) = permits.try_acquire() else {
+        tracing::warn!("Concurrency limit reached, shedding request");
+        let mut response = AppError::ServiceUnavailable("Server is overloaded, please retry later".into()).into_response();
+        response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from_static("1"));
+        return response;
+    };
+
+    next.run(request).await
+}
+
+/// Axum middleware that aborts the request once `timeout` has elapsed.
+pub async fn enforce_timeout(State(timeout): State<Duration>, request: Request, next: Next) -> Response {
+    match tokio::time::timeout(timeout, next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => {
+            tracing::warn!(?timeout, "Request timed out");
+            AppError::GatewayTimeout(format!("Request did not complete within {} ms", timeout.as_millis())).into_response()
+        },
+    }
+}
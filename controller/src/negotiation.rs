@@ -0,0 +1,501 @@
+//! `Accept`-based content negotiation for note responses.
+//!
+//! [`NoteFormat`] lists the representations of a single note and
+//! [`NoteListFormat`] those of a page of notes. Both are chosen from the
+//! request's `Accept` header by [`negotiate`], honouring quality values and
+//! preferring more specific media ranges on ties; a missing header selects
+//! JSON, and a header that matches nothing yields `406 Not Acceptable`.
+//!
+//! Independently of the media type, [`negotiate_date_display`] selects the
+//! [`DateDisplay`] of every timestamp in the response: its [`DateFormat`]
+//! from the `dateFormat` query parameter or a `Prefer: date-format=...`
+//! header, its time zone from `tz`, and its [`DateLocale`] from `locale` or
+//! `Accept-Language`. These parameters are removed from the query string
+//! before routing, so handlers never see them.
+
+use axum::{
+    Json,
+    extract::{Query, Request, State},
+    http::{HeaderMap, HeaderName, HeaderValue, StatusCode, Uri, header, uri::PathAndQuery},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use model::dto::{
+    datetime::{DateDisplay, DateFormat, DateLocale, FormattedDateTime, parse_time_zone},
+    note::NoteResponse,
+    pagination::PaginatedResponse,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::error::AppError;
+
+/// A response representation that can be selected through `Accept`.
+pub trait Negotiable: Copy + 'static {
+    /// Every supported representation, in order of server preference.
+    const ALL: &'static [Self];
+
+    /// The media types identifying this representation; the first is the
+    /// canonical `Content-Type`.
+    fn media_types(self) -> &'static [&'static str];
+}
+
+/// The representations of a single note.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoteFormat {
+    /// `application/json`.
+    Json,
+    /// `text/markdown`: the content preceded by YAML front-matter holding
+    /// the remaining fields.
+    Markdown,
+    /// `text/plain`: the raw content only.
+    PlainText,
+    /// `application/yaml`.
+    Yaml,
+    /// `application/msgpack`.
+    MessagePack,
+}
+
+impl Negotiable for NoteFormat {
+    const ALL: &'static [Self] = &[Self::Json, Self::Markdown, Self::PlainText, Self::Yaml, Self::MessagePack];
+
+    fn media_types(self) -> &'static [&'static str] {
+        match self {
+            Self::Json => &["application/json"],
+            Self::Markdown => &["text/markdown", "text/x-markdown"],
+            Self::PlainText => &["text/plain"],
+            Self::Yaml => &["application/yaml", "application/x-yaml", "text/yaml"],
+            Self::MessagePack => &["application/msgpack", "application/x-msgpack", "application/vnd.msgpack"],
+        }
+    }
+}
+
+/// The representations of a page of notes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoteListFormat {
+    /// `application/json`.
+    Json,
+    /// `text/csv`: one row per note, with pagination metadata in headers.
+    Csv,
+}
+
+impl Negotiable for NoteListFormat {
+    const ALL: &'static [Self] = &[Self::Json, Self::Csv];
+
+    fn media_types(self) -> &'static [&'static str] {
+        match self {
+            Self::Json => &["application/json"],
+            Self::Csv => &["text/csv"],
+        }
+    }
+}
+
+/// Selects the best representation for the request's `Accept` header.
+///
+/// Each candidate is scored by the quality value of the most specific media
+/// range that matches it (`type/subtype` over `type/*` over `*/*`); the
+/// highest-scoring candidate wins, with ties broken by server preference.
+///
+/// # Errors
+///
+/// Returns [`AppError::NotAcceptable`] when no supported representation is
+/// acceptable.
+pub fn negotiate<Format: Negotiable>(headers: &HeaderMap) -> Result<Format, AppError> {
+    let ranges: Vec<(String, f32)> = headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(parse_media_range)
+        .collect();
+
+    if ranges.is_empty() {
+        return Ok(Format::ALL[0]);
+    }
+
+    let mut best: Option<(Format, f32, u8)> = None;
+
+    for &format in Format::ALL {
+        let Some((quality, specificity)) = score(format, &ranges) else {
+            continue;
+        };
+
+        if quality > 0.0 && best.is_none_or(|(_, best_quality, best_specificity)| (quality, specificity) > (best_quality, best_specificity)) {
+            best = Some((format, quality, specificity));
+        }
+    }
+
+    best.map(|(format, ..)| format).ok_or_else(|| {
+        let supported: Vec<&str> = Format::ALL.iter().map(|format| format.media_types()[0]).collect();
+        tracing::warn!("No acceptable representation");
+        AppError::NotAcceptable(format!("Supported media types: {}", supported.join(", ")))
+    })
+}
+
+/// Parses one `Accept` element into a lower-cased media range and its
+/// quality value (default `1`).
+fn parse_media_range(element: &str) -> Option<(String, f32)> {
+    let mut parts = element.split(';').map(str::trim);
+    let range = parts.next().filter(|range| range.contains('/'))?.to_ascii_lowercase();
+
+    Some((range, quality_value(parts)))
+}
+
+/// Returns the `q` parameter among an element's parameters, defaulting to
+/// `1` and clamped to `0..=1`.
+fn quality_value<'a>(parameters: impl Iterator<Item = &'a str>) -> f32 {
+    parameters
+        .filter_map(|parameter| parameter.strip_prefix("q=").or_else(|| parameter.strip_prefix("Q=")))
+        .find_map(|value| value.parse::<f32>().ok())
+        .unwrap_or(1.0)
+        .clamp(0.0, 1.0)
+}
+
+/// Returns the quality and specificity of the most specific range matching
+/// any of the format's media types, or [`None`] if no range matches.
+fn score<Format: Negotiable>(format: Format, ranges: &[(String, f32)]) -> Option<(f32, u8)> {
+    let mut best: Option<(u8, f32)> = None;
+
+    for media_type in format.media_types() {
+        let main_type = media_type.split('/').next().unwrap_or_default();
+
+        for (range, quality) in ranges {
+            let specificity = if range == media_type {
+                2
+            } else if range.strip_suffix("/*") == Some(main_type) {
+                1
+            } else if range == "*/*" {
+                0
+            } else {
+                continue;
+            };
+
+            if best.is_none_or(|(best_specificity, _)| specificity > best_specificity) {
+                best = Some((specificity, *quality));
+            }
+        }
+    }
+
+    best.map(|(specificity, quality)| (quality, specificity))
+}
+
+/// The `Prefer` request header (RFC 7240).
+static PREFER_HEADER: HeaderName = HeaderName::from_static("prefer");
+
+/// The `Preference-Applied` response header (RFC 7240).
+static PREFERENCE_APPLIED_HEADER: HeaderName = HeaderName::from_static("preference-applied");
+
+/// The `Prefer` preference selecting the timestamp format.
+const DATE_FORMAT_PREFERENCE: &str = "date-format";
+
+/// The query parameters consumed by [`negotiate_date_display`].
+const DATE_DISPLAY_PARAMS: [&str; 3] = ["dateFormat", "tz", "locale"];
+
+/// The subset of the query string relevant to date-display negotiation.
+#[derive(Default, Deserialize)]
+struct DateDisplayQuery {
+    /// The requested timestamp format.
+    #[serde(rename = "dateFormat")]
+    date_format: Option<String>,
+    /// The requested IANA time zone.
+    tz: Option<String>,
+    /// The requested language tag.
+    locale: Option<String>,
+}
+
+/// Axum middleware that renders every timestamp in the response with the
+/// requested [`DateDisplay`], starting from the server's defaults.
+///
+/// * The format comes from the `dateFormat` query parameter or, failing
+///   that, a valid `Prefer: date-format=<format>` header, acknowledged
+///   through `Preference-Applied` and otherwise ignored as RFC 7240
+///   requires.
+/// * The time zone comes from the `tz` query parameter.
+/// * The locale comes from the `locale` query parameter or, failing that,
+///   the most preferred supported language in `Accept-Language`; unsupported
+///   languages fall back to the default.
+///
+/// Invalid query parameters are rejected with `400 Bad Request`; valid ones
+/// are removed from the request before it is routed, so that handlers need
+/// not accept them and saved searches do not store them. Successful
+/// responses that rendered human-readable timestamps state their language
+/// in `Content-Language`; error responses never do.
+pub async fn negotiate_date_display(State(defaults): State<DateDisplay>, mut request: Request, next: Next) -> Response {
+    let query = Query::<DateDisplayQuery>::try_from_uri(request.uri())
+        .map(|Query(query)| query)
+        .unwrap_or_default();
+    let mut display = defaults;
+    let mut preference_applied = false;
+
+    match query.date_format {
+        Some(raw) => match raw.parse::<DateFormat>() {
+            Ok(format) => display.format = format,
+            Err(err) => return invalid_parameter("dateFormat", &raw, err),
+        },
+        None => {
+            if let Some(format) = preferred_date_format(request.headers()) {
+                display.format = format;
+                preference_applied = true;
+            }
+        },
+    }
+
+    if let Some(raw) = query.tz {
+        match parse_time_zone(&raw) {
+            Ok(time_zone) => display.time_zone = time_zone,
+            Err(err) => return invalid_parameter("tz", &raw, err),
+        }
+    }
+
+    let locale_from_header = query.locale.is_none();
+    match query.locale {
+        Some(raw) => match raw.parse::<DateLocale>() {
+            Ok(locale) => display.locale = locale,
+            Err(err) => return invalid_parameter("locale", &raw, err),
+        },
+        None => {
+            if let Some(locale) = accepted_locale(request.headers()) {
+                display.locale = locale;
+            }
+        },
+    }
+
+    strip_date_display_params(&mut request);
+
+    let (mut response, rendered_human_dates) = display.scope_tracked(next.run(request)).await;
+    let localised = rendered_human_dates && response.status().is_success();
+    let headers = response.headers_mut();
+
+    if preference_applied && let Ok(applied) = HeaderValue::from_str(&format!("{DATE_FORMAT_PREFERENCE}={}", display.format)) {
+        headers.append(&PREFERENCE_APPLIED_HEADER, applied);
+    }
+
+    if localised {
+        if let Ok(language) = HeaderValue::from_str(&display.locale.to_string()) {
+            headers.insert(header::CONTENT_LANGUAGE, language);
+        }
+
+        if locale_from_header {
+            headers.append(header::VARY, HeaderValue::from_static("accept-language"));
+        }
+    }
+
+    response
+}
+
+/// Removes the [`DATE_DISPLAY_PARAMS`] from the request's query string,
+/// leaving the other parameters exactly as sent.
+fn strip_date_display_params(request: &mut Request) {
+    let Some(query) = request.uri().query() else {
+        return;
+    };
+
+    let is_date_display = |pair: &&str| {
+        form_urlencoded::parse(pair.as_bytes())
+            .next()
+            .is_some_and(|(key, _)| DATE_DISPLAY_PARAMS.contains(&&*key))
+    };
+    if !query.split('&').any(|pair| is_date_display(&pair)) {
+        return;
+    }
+
+    let kept: Vec<&str> = query.split('&').filter(|pair| !is_date_display(pair)).collect();
+    let path_and_query = match kept.as_slice() {
+        [] => request.uri().path().to_owned(),
+        _ => format!("{}?{}", request.uri().path(), kept.join("&")),
+    };
+
+    let Ok(path_and_query) = PathAndQuery::try_from(path_and_query) else {
+        return;
+    };
+
+    let mut parts = request.uri().clone().into_parts();
+    parts.path_and_query = Some(path_and_query);
+    if let Ok(uri) = Uri::from_parts(parts) {
+        *request.uri_mut() = uri;
+    }
+}
+
+/// Logs and rejects an invalid date-display query parameter.
+fn invalid_parameter(name: &str, value: &str, message: String) -> Response {
+    tracing::warn!(parameter = name, value, "Invalid date display parameter");
+    AppError::BadRequest(message).into_response()
+}
+
+/// Extracts a valid `date-format` preference from the `Prefer` headers.
+fn preferred_date_format(headers: &HeaderMap) -> Option<DateFormat> {
+    headers
+        .get_all(&PREFER_HEADER)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|preference| {
+            let (name, value) = preference.split(';').next()?.split_once('=')?;
+            name.trim()
+                .eq_ignore_ascii_case(DATE_FORMAT_PREFERENCE)
+                .then(|| value.trim().trim_matches('"').to_ascii_lowercase())
+        })
+        .find_map(|value| value.parse().ok())
+}
+
+/// Selects the supported locale with the highest quality value in the
+/// `Accept-Language` headers, preferring earlier entries on ties.
+fn accepted_locale(headers: &HeaderMap) -> Option<DateLocale> {
+    let mut best: Option<(DateLocale, f32)> = None;
+
+    let candidates = headers
+        .get_all(header::ACCEPT_LANGUAGE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|element| {
+            let mut parts = element.split(';').map(str::trim);
+            let locale = parts.next()?.parse::<DateLocale>().ok()?;
+            Some((locale, quality_value(parts)))
+        });
+
+    for (locale, quality) in candidates {
+        if quality > 0.0 && best.is_none_or(|(_, best_quality)| quality > best_quality) {
+            best = Some((locale, quality));
+        }
+    }
+
+    best.map(|(locale, _)| locale)
+}
+
+/// YAML front-matter of a Markdown note: every field except the content.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FrontMatter<'a> {
+    /// The unique identifier of the note.
+    id: i64,
+    /// The title of the note.
+    title: &'a str,
+    /// The creation timestamp.
+    created_at: &'a FormattedDateTime,
+    /// The last-updated timestamp.
+    updated_at: &'a FormattedDateTime,
+    /// Whether the note is pinned.
+    pinned: bool,
+    /// Whether the note is starred.
+    starred: bool,
+    /// Whether the note is archived.
+    archived: bool,
+    /// When the next reminder is due, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remind_at: Option<&'a FormattedDateTime>,
+    /// The recurrence rule of the reminder, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    recurrence: Option<&'a str>,
+    /// The custom properties, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    properties: Option<&'a Map<String, Value>>,
+}
+
+/// Renders a single note in the negotiated representation.
+///
+/// # Errors
+///
+/// Returns [`AppError::Internal`] if serialisation fails.
+pub fn render_note(note: NoteResponse, format: NoteFormat) -> Result<Response, AppError> {
+    let body = match format {
+        NoteFormat::Json => return Ok(with_vary(Json(note).into_response())),
+        NoteFormat::Markdown => {
+            let front_matter = FrontMatter {
+                id: note.id,
+                title: &note.title,
+                created_at: &note.created_at,
+                updated_at: &note.updated_at,
+                pinned: note.pinned,
+                starred: note.starred,
+                archived: note.archived,
+                remind_at: note.remind_at.as_ref(),
+                recurrence: note.recurrence.as_deref(),
+                properties: (!note.properties.is_empty()).then_some(&note.properties),
+            };
+            let yaml = serde_norway::to_string(&front_matter).map_err(|err| AppError::Internal(err.to_string()))?;
+
+            format!("---\n{yaml}---\n\n{}", note.content).into_bytes()
+        },
+        NoteFormat::PlainText => note.content.into_bytes(),
+        NoteFormat::Yaml => serde_norway::to_string(&note)
+            .map_err(|err| AppError::Internal(err.to_string()))?
+            .into_bytes(),
+        NoteFormat::MessagePack => rmp_serde::to_vec_named(&note).map_err(|err| AppError::Internal(err.to_string()))?,
+    };
+
+    Ok(with_content_type(body, format))
+}
+
+/// Renders a page of notes in the negotiated representation.
+///
+/// CSV rows carry RFC 3339 timestamps; pagination metadata is returned in
+/// the `X-Total-Count` and `X-Total-Pages` headers.
+pub fn render_note_list(page: PaginatedResponse<NoteResponse>, format: NoteListFormat) -> Response {
+    match format {
+        NoteListFormat::Json => with_vary(Json(page).into_response()),
+        NoteListFormat::Csv => {
+            let mut csv = String::from("id,title,content,createdAt,updatedAt,pinned,starred,archived,remindAt,recurrence,properties\r\n");
+
+            for note in &page.notes {
+                let fields = [
+                    note.id.to_string(),
+                    note.title.clone(),
+                    note.content.clone(),
+                    note.created_at.to_rfc3339(),
+                    note.updated_at.to_rfc3339(),
+                    note.pinned.to_string(),
+                    note.starred.to_string(),
+                    note.archived.to_string(),
+                    note.remind_at.as_ref().map(|remind_at| remind_at.to_rfc3339()).unwrap_or_default(),
+                    note.recurrence.clone().unwrap_or_default(),
+                    if note.properties.is_empty() {
+                        String::new()
+                    } else {
+                        Value::Object(note.properties.clone()).to_string()
+                    },
+                ];
+                let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+                csv.push_str(&row.join(","));
+                csv.push_str("\r\n");
+            }
+
+            let mut response = with_content_type(csv.into_bytes(), format);
+            let headers = response.headers_mut();
+            headers.insert("x-total-count", HeaderValue::from(page.page.total_elements));
+            headers.insert("x-total-pages", HeaderValue::from(page.page.total_pages));
+
+            response
+        },
+    }
+}
+
+/// Quotes a CSV field if it contains a delimiter, quote, or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+/// Builds a `200 OK` response with the format's canonical content type.
+fn with_content_type<Format: Negotiable>(body: Vec<u8>, format: Format) -> Response {
+    let content_type = match format.media_types()[0] {
+        media_type if media_type.starts_with("text/") => format!("{media_type}; charset=utf-8"),
+        media_type => media_type.to_owned(),
+    };
+
+    let mut response = (StatusCode::OK, body).into_response();
+    if let Ok(value) = HeaderValue::from_str(&content_type) {
+        response.headers_mut().insert(header::CONTENT_TYPE, value);
+    }
+
+    with_vary(response)
+}
+
+/// Marks the response as varying by `Accept` for downstream caches.
+fn with_vary(mut response: Response) -> Response {
+    response.headers_mut().append(header::VARY, HeaderValue::from_static("accept"));
+    response
+}
@@ -4,7 +4,8 @@
 //! parameters, query strings, and JSON bodies), delegates to the
 //! [`NoteService`], and returns a typed Axum response. Extraction failures
 //! are propagated as [`AppError::BadRequest`] via the `From` impls on
-//! [`AppError`].
+//! [`AppError`]. Read endpoints render the representation negotiated from
+//! the `Accept` header (see [`negotiation`](crate::negotiation)).
 
 use axum::{
     Json,
@@ -12,54 +13,118 @@ use axum::{
         Path, Query, State,
         rejection::{JsonRejection, PathRejection, QueryRejection},
     },
-    http::StatusCode,
-    response::IntoResponse,
+    http::{HeaderMap, HeaderName, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
 };
 use model::dto::{
-    note::{CreateNoteRequest, UpdateNoteRequest},
+    idempotency::IdempotentOutcome,
+    note::{CreateNoteRequest, MergeNotesRequest, NoteFlag, NoteResponse, SuggestQuery, UpdateNoteRequest},
     pagination::SearchParams,
 };
 use service::note::NoteService;
 
-use crate::error::AppError;
+use crate::{
+    error::AppError,
+    negotiation::{NoteFormat, NoteListFormat, negotiate, render_note, render_note_list},
+};
+
+/// The request header carrying a client-chosen idempotency key.
+pub static IDEMPOTENCY_KEY_HEADER: HeaderName = HeaderName::from_static("idempotency-key");
+
+/// The response header set when a stored response is replayed.
+pub static IDEMPOTENT_REPLAYED_HEADER: HeaderName = HeaderName::from_static("idempotent-replayed");
 
 /// `POST /api/notes` – creates a new note and returns it with `201 Created`.
+///
+/// When an `Idempotency-Key` header is present, retries with the same key
+/// and body replay the original response (marked with
+/// `Idempotent-Replayed: true`) instead of creating a duplicate note. The
+/// replayed note is rendered with the retry's date display, not that of the
+/// original request.
 #[tracing::instrument(skip_all)]
 pub async fn create_note<Service: NoteService>(
     State(service): State<Service>,
+    headers: HeaderMap,
     body: Result<Json<CreateNoteRequest>, JsonRejection>,
-) -> Result<impl IntoResponse, AppError> {
+) -> Result<Response, AppError> {
     let Json(req) = body?;
-    tracing::info!("Creating note");
-    let note = service.create(req).await.map_err(AppError::from)?;
 
-    Ok((StatusCode::CREATED, Json(note)))
+    let Some(key) = headers.get(&IDEMPOTENCY_KEY_HEADER) else {
+        tracing::info!("Creating note");
+        let note = service.create(req).await.map_err(AppError::from)?;
+
+        return Ok((StatusCode::CREATED, Json(note)).into_response());
+    };
+
+    let key = key
+        .to_str()
+        .map_err(|_| AppError::BadRequest("Header 'Idempotency-Key' must be visible ASCII".into()))?
+        .to_owned();
+
+    tracing::info!("Creating note with idempotency key");
+
+    match service.create_idempotent(req, key).await.map_err(AppError::from)? {
+        IdempotentOutcome::Created(note) => Ok((StatusCode::CREATED, Json(note)).into_response()),
+        IdempotentOutcome::Replayed(stored) => {
+            tracing::info!(status = stored.status_code, "Replaying stored response");
+            let status = StatusCode::from_u16(stored.status_code).unwrap_or(StatusCode::OK);
+            let note: NoteResponse =
+                serde_json::from_str(&stored.body).map_err(|err| AppError::Internal(format!("Invalid stored response: {err}")))?;
+
+            Ok((
+                status,
+                [(IDEMPOTENT_REPLAYED_HEADER.clone(), HeaderValue::from_static("true"))],
+                Json(note),
+            )
+                .into_response())
+        },
+    }
 }
 
-/// `GET /api/notes/{id}` – retrieves a single note by its primary key.
+/// `GET /api/notes/{id}` – retrieves a single note by its primary key as
+/// JSON, Markdown, plain text, YAML, or MessagePack.
 #[tracing::instrument(skip_all)]
 pub async fn get_note<Service: NoteService>(
     State(service): State<Service>,
+    headers: HeaderMap,
     path: Result<Path<i64>, PathRejection>,
-) -> Result<impl IntoResponse, AppError> {
+) -> Result<Response, AppError> {
     let Path(id) = path?;
-    tracing::info!(id, "Fetching note");
+    let format: NoteFormat = negotiate(&headers)?;
+    tracing::info!(id, ?format, "Fetching note");
     let note = service.find_by_id(id).await.map_err(AppError::from)?;
 
-    Ok(Json(note))
+    render_note(note, format)
 }
 
-/// `GET /api/notes` – returns a paginated, optionally filtered list of notes.
+/// `GET /api/notes` – returns a paginated, optionally filtered list of notes
+/// as JSON or CSV.
 #[tracing::instrument(skip_all)]
 pub async fn list_notes<Service: NoteService>(
     State(service): State<Service>,
+    headers: HeaderMap,
     query: Result<Query<SearchParams>, QueryRejection>,
-) -> Result<impl IntoResponse, AppError> {
-    let Query(params) = query?;
-    tracing::info!("Listing notes");
+) -> Result<Response, AppError> {
+    let Query(params) = query.map_err(AppError::search_params)?;
+    let format: NoteListFormat = negotiate(&headers)?;
+    tracing::info!(?format, "Listing notes");
     let result = service.find_all(params).await.map_err(AppError::from)?;
 
-    Ok(Json(result))
+    Ok(render_note_list(result, format))
+}
+
+/// `GET /api/notes/suggest` – returns up to `limit` note titles starting
+/// with `prefix`, or containing a word that does, for autocompletion.
+#[tracing::instrument(skip_all)]
+pub async fn suggest_notes<Service: NoteService>(
+    State(service): State<Service>,
+    query: Result<Query<SuggestQuery>, QueryRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    let Query(query) = query?;
+    tracing::info!("Suggesting note titles");
+    let suggestions = service.suggest(query).await.map_err(AppError::from)?;
+
+    Ok(Json(suggestions))
 }
 
 /// `PUT /api/notes/{id}` – partially updates an existing note.
@@ -77,6 +142,62 @@ pub async fn update_note<Service: NoteService>(
     Ok(Json(note))
 }
 
+/// `PUT /api/notes/{id}/{flag}` – pins, stars, or archives a note and
+/// returns it.
+#[tracing::instrument(skip_all)]
+pub async fn set_note_flag<Service: NoteService>(
+    State(service): State<Service>,
+    path: Result<Path<(i64, NoteFlag)>, PathRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    let Path((id, flag)) = path?;
+    tracing::info!(id, %flag, "Setting note flag");
+    let note = service.set_flag(id, flag, true).await.map_err(AppError::from)?;
+
+    Ok(Json(note))
+}
+
+/// `DELETE /api/notes/{id}/{flag}` – unpins, unstars, or unarchives a note
+/// and returns it.
+#[tracing::instrument(skip_all)]
+pub async fn clear_note_flag<Service: NoteService>(
+    State(service): State<Service>,
+    path: Result<Path<(i64, NoteFlag)>, PathRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    let Path((id, flag)) = path?;
+    tracing::info!(id, %flag, "Clearing note flag");
+    let note = service.set_flag(id, flag, false).await.map_err(AppError::from)?;
+
+    Ok(Json(note))
+}
+
+/// `POST /api/notes/{id}/duplicate` – copies a note under a suffixed title
+/// and returns the copy with `201 Created`.
+#[tracing::instrument(skip_all)]
+pub async fn duplicate_note<Service: NoteService>(
+    State(service): State<Service>,
+    path: Result<Path<i64>, PathRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    let Path(id) = path?;
+    tracing::info!(id, "Duplicating note");
+    let note = service.duplicate(id).await.map_err(AppError::from)?;
+
+    Ok((StatusCode::CREATED, Json(note)))
+}
+
+/// `POST /api/notes/merge` – appends the content of the source notes to the
+/// target note, archives or deletes the sources, and returns the target.
+#[tracing::instrument(skip_all)]
+pub async fn merge_notes<Service: NoteService>(
+    State(service): State<Service>,
+    body: Result<Json<MergeNotesRequest>, JsonRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    let Json(req) = body?;
+    tracing::info!(target_id = req.target_id, sources = req.source_ids.len(), "Merging notes");
+    let note = service.merge(req).await.map_err(AppError::from)?;
+
+    Ok(Json(note))
+}
+
 /// `DELETE /api/notes/{id}` – deletes a note and returns `204 No Content`.
 #[tracing::instrument(skip_all)]
 pub async fn delete_note<Service: NoteService>(
@@ -0,0 +1,58 @@
+//! Axum handler functions for property schema endpoints.
+//!
+//! A property schema fixes the value type of one custom note property key,
+//! e.g. `priority` as a `number`. Notes are checked against the schemas when
+//! created or updated, and `prop.*` search filters read their values as the
+//! declared type.
+
+use axum::{
+    Json,
+    extract::{
+        Path, State,
+        rejection::{JsonRejection, PathRejection},
+    },
+    http::StatusCode,
+    response::IntoResponse,
+};
+use model::dto::property::CreatePropertySchemaRequest;
+use service::property_schema::PropertySchemaService;
+
+use crate::error::AppError;
+
+/// `POST /api/property-schemas` – declares the value type of a property key
+/// and returns the schema with `201 Created`.
+#[tracing::instrument(skip_all)]
+pub async fn create_property_schema<Service: PropertySchemaService>(
+    State(service): State<Service>,
+    body: Result<Json<CreatePropertySchemaRequest>, JsonRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    let Json(req) = body?;
+    tracing::info!("Creating property schema");
+    let schema = service.create(req).await.map_err(AppError::from)?;
+
+    Ok((StatusCode::CREATED, Json(schema)))
+}
+
+/// `GET /api/property-schemas` – lists every property schema, ordered by
+/// key.
+#[tracing::instrument(skip_all)]
+pub async fn list_property_schemas<Service: PropertySchemaService>(State(service): State<Service>) -> Result<impl IntoResponse, AppError> {
+    tracing::info!("Listing property schemas");
+    let schemas = service.find_all().await.map_err(AppError::from)?;
+
+    Ok(Json(schemas))
+}
+
+/// `DELETE /api/property-schemas/{id}` – deletes a property schema and
+/// returns `204 No Content`.
+#[tracing::instrument(skip_all)]
+pub async fn delete_property_schema<Service: PropertySchemaService>(
+    State(service): State<Service>,
+    path: Result<Path<i64>, PathRejection>,
+) -> Result<StatusCode, AppError> {
+    let Path(id) = path?;
+    tracing::info!(id, "Deleting property schema");
+    service.delete(id).await.map_err(AppError::from)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,324 @@
+//! Per-client token-bucket rate limiting.
+//!
+//! [`enforce_rate_limit`] charges every API request against a token bucket
+//! keyed by the client (an authenticated [`ClientIdentity`] if one was
+//! attached upstream, otherwise the client IP) and by request class: reads
+//! (`GET`/`HEAD`, i.e. `get_note` and `list_notes`) and writes draw from
+//! separate budgets. Every response carries `RateLimit-Limit`,
+//! `RateLimit-Remaining`, `RateLimit-Reset`, and `RateLimit-Policy`
+//! headers; rejected requests receive `429 Too Many Requests` with
+//! `Retry-After`.
+//!
+//! Peers of a Unix domain socket have no IP address, so unless clients are
+//! identified upstream or by a trusted `X-Forwarded-For` header, they all
+//! share one bucket and the limit is effectively global.
+//!
+//! Buckets live in a [`RateLimitStore`]; [`InMemoryRateLimitStore`] keeps
+//! them in-process, and other backends (e.g. a shared cache) can be plugged
+//! in by implementing the trait. The in-memory store is bounded: once full,
+//! it evicts the least recently used bucket, and it periodically drops
+//! buckets that have fully refilled.
+
+use std::{
+    future::Future,
+    net::{IpAddr, SocketAddr},
+    num::NonZeroUsize,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::{ConnectInfo, Request, State, connect_info::Connected},
+    http::{HeaderMap, HeaderName, HeaderValue, Method},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    serve::{IncomingStream, Listener},
+};
+use lru::LruCache;
+use tokio::net::TcpListener;
+
+use crate::error::AppError;
+
+/// Upper bound on the number of buckets the in-memory store keeps; beyond
+/// it, the least recently used bucket is evicted.
+const MAX_TRACKED_BUCKETS: NonZeroUsize = NonZeroUsize::new(100_000).unwrap();
+
+/// Minimum time between sweeps of fully refilled buckets.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A boxed, sendable future, used to keep [`RateLimitStore`] object-safe.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// The budget of a single token bucket.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quota {
+    /// Maximum number of tokens, i.e. the largest permitted burst.
+    pub burst: u32,
+    /// Number of tokens added back per second.
+    pub per_second: f64,
+}
+
+impl Quota {
+    /// Returns the time needed to refill the bucket from empty.
+    fn window(&self) -> Duration {
+        Duration::from_secs_f64(f64::from(self.burst) / self.per_second)
+    }
+}
+
+/// The outcome of charging a request against a bucket.
+#[derive(Debug, Clone, Copy)]
+pub struct Decision {
+    /// Whether the request may proceed.
+    pub allowed: bool,
+    /// Whole tokens left in the bucket after this request.
+    pub remaining: u32,
+    /// Time until the bucket is full again.
+    pub reset_after: Duration,
+    /// Time until the next token becomes available, when rejected.
+    pub retry_after: Duration,
+}
+
+/// Storage backend for token buckets.
+pub trait RateLimitStore: Send + Sync + 'static {
+    /// Atomically refills the bucket identified by `key` according to
+    /// `quota` and attempts to take one token from it.
+    fn acquire<'a>(&'a self, key: &'a str, quota: Quota) -> BoxFuture<'a, Decision>;
+}
+
+/// The state of a single in-memory token bucket.
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    /// Tokens currently available (fractional while refilling).
+    tokens: f64,
+    /// When `tokens` was last brought up to date.
+    updated_at: Instant,
+    /// The budget the bucket was last charged against.
+    quota: Quota,
+}
+
+impl Bucket {
+    /// Returns whether the bucket has refilled completely by `now`, so that
+    /// dropping it is indistinguishable from keeping it.
+    fn is_full(&self, now: Instant) -> bool {
+        self.tokens + now.duration_since(self.updated_at).as_secs_f64() * self.quota.per_second >= f64::from(self.quota.burst)
+    }
+}
+
+/// The buckets of an [`InMemoryRateLimitStore`].
+#[derive(Debug)]
+struct Buckets {
+    /// Buckets keyed by client and request class, least recently used
+    /// first.
+    entries: LruCache<String, Bucket>,
+    /// When fully refilled buckets were last dropped.
+    swept_at: Instant,
+}
+
+/// A [`RateLimitStore`] that keeps buckets in process memory.
+#[derive(Debug)]
+pub struct InMemoryRateLimitStore {
+    /// The buckets, behind a lock shared by all requests.
+    buckets: Mutex<Buckets>,
+}
+
+impl InMemoryRateLimitStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self {
+            buckets: Mutex::new(Buckets {
+                entries: LruCache::new(MAX_TRACKED_BUCKETS),
+                swept_at: Instant::now(),
+            }),
+        }
+    }
+}
+
+impl Default for InMemoryRateLimitStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RateLimitStore for InMemoryRateLimitStore {
+    fn acquire<'a>(&'a self, key: &'a str, quota: Quota) -> BoxFuture<'a, Decision> {
+        Box::pin(async move {
+            let now = Instant::now();
+            let capacity = f64::from(quota.burst);
+            let mut buckets = self.buckets.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+            if now.duration_since(buckets.swept_at) >= SWEEP_INTERVAL {
+                let full: Vec<String> = buckets
+                    .entries
+                    .iter()
+                    .filter(|(_, bucket)| bucket.is_full(now))
+                    .map(|(key, _)| key.clone())
+                    .collect();
+                for key in &full {
+                    buckets.entries.pop(key);
+                }
+                buckets.swept_at = now;
+            }
+
+            let bucket = buckets.entries.get_or_insert_mut(key.to_owned(), || Bucket {
+                tokens: capacity,
+                updated_at: now,
+                quota,
+            });
+
+            bucket.quota = quota;
+            let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * quota.per_second).min(capacity);
+            bucket.updated_at = now;
+
+            let allowed = bucket.tokens >= 1.0;
+            if allowed {
+                bucket.tokens -= 1.0;
+            }
+
+            Decision {
+                allowed,
+                remaining: bucket.tokens.floor() as u32,
+                reset_after: Duration::from_secs_f64((capacity - bucket.tokens) / quota.per_second),
+                retry_after: Duration::from_secs_f64(((1.0 - bucket.tokens) / quota.per_second).max(0.0)),
+            }
+        })
+    }
+}
+
+/// An authenticated caller identity. When present in the request extensions
+/// (inserted by an authentication layer), it is used as the rate-limit key
+/// instead of the client IP.
+#[derive(Debug, Clone)]
+pub struct ClientIdentity(pub String);
+
+/// The peer IP address of a connection, or [`None`] for transports without
+/// one (e.g. Unix domain sockets).
+#[derive(Debug, Clone, Copy)]
+pub struct ClientAddr(pub Option<IpAddr>);
+
+/// Listeners whose peer addresses may carry a client IP address, allowing
+/// [`ClientAddr`] to be used as connect info when serving them.
+pub trait PeerListener: Listener {
+    /// Returns the peer IP address, if the transport has one.
+    fn peer_ip(addr: &Self::Addr) -> Option<IpAddr>;
+}
+
+impl PeerListener for TcpListener {
+    fn peer_ip(addr: &SocketAddr) -> Option<IpAddr> {
+        Some(addr.ip())
+    }
+}
+
+#[cfg(unix)]
+impl PeerListener for tokio::net::UnixListener {
+    fn peer_ip(_addr: &tokio::net::unix::SocketAddr) -> Option<IpAddr> {
+        None
+    }
+}
+
+impl<L: PeerListener> Connected<IncomingStream<'_, L>> for ClientAddr {
+    fn connect_info(stream: IncomingStream<'_, L>) -> Self {
+        Self(L::peer_ip(stream.remote_addr()))
+    }
+}
+
+/// Rate-limiting policy shared by all requests.
+pub struct RateLimiter {
+    /// Budget for read requests.
+    read: Quota,
+    /// Budget for write requests.
+    write: Quota,
+    /// Whether to key anonymous clients by the first `X-Forwarded-For`
+    /// address (only safe behind a trusted reverse proxy).
+    trust_forwarded_for: bool,
+    /// Where bucket state is kept.
+    store: Arc<dyn RateLimitStore>,
+}
+
+impl RateLimiter {
+    /// Creates a rate limiter with separate read and write budgets, backed by
+    /// an [`InMemoryRateLimitStore`].
+    pub fn new(read: Quota, write: Quota) -> Self {
+        Self {
+            read,
+            write,
+            trust_forwarded_for: false,
+            store: Arc::new(InMemoryRateLimitStore::new()),
+        }
+    }
+
+    /// Replaces the bucket storage backend.
+    pub fn store(mut self, store: Arc<dyn RateLimitStore>) -> Self {
+        self.store = store;
+        self
+    }
+
+    /// Keys anonymous clients by the first `X-Forwarded-For` address instead
+    /// of the socket peer address.
+    pub fn trust_forwarded_for(mut self, trust: bool) -> Self {
+        self.trust_forwarded_for = trust;
+        self
+    }
+
+    /// Derives the bucket key for the client that sent `request`; clients
+    /// without an identity, a trusted forwarded address, or a peer IP share
+    /// the `anonymous` bucket.
+    fn client_key(&self, request: &Request) -> String {
+        if let Some(ClientIdentity(identity)) = request.extensions().get::<ClientIdentity>() {
+            return format!("id:{identity}");
+        }
+
+        if self.trust_forwarded_for
+            && let Some(forwarded) = request.headers().get("x-forwarded-for").and_then(|value| value.to_str().ok())
+            && let Some(ip) = forwarded.split(',').next().and_then(|first| first.trim().parse::<IpAddr>().ok())
+        {
+            return format!("ip:{ip}");
+        }
+
+        match request.extensions().get::<ConnectInfo<ClientAddr>>() {
+            Some(ConnectInfo(ClientAddr(Some(ip)))) => format!("ip:{ip}"),
+            _ => "anonymous".into(),
+        }
+    }
+}
+
+/// Axum middleware that enforces the [`RateLimiter`], if one is configured.
+pub async fn enforce_rate_limit(State(limiter): State<Option<Arc<RateLimiter>>>, request: Request, next: Next) -> Response {
+    let Some(limiter) = limiter else {
+        return next.run(request).await;
+    };
+
+    let (class, quota) = if matches!(*request.method(), Method::GET | Method::HEAD) {
+        ("read", limiter.read)
+    } else {
+        ("write", limiter.write)
+    };
+
+    let client = limiter.client_key(&request);
+    let decision = limiter.store.acquire(&format!("{class}:{client}"), quota).await;
+
+    let mut response = if decision.allowed {
+        next.run(request).await
+    } else {
+        tracing::warn!(client = %client, class, "Rate limit exceeded");
+        let mut response = AppError::TooManyRequests(format!("Rate limit exceeded for {class} requests")).into_response();
+        insert_seconds(response.headers_mut(), HeaderName::from_static("retry-after"), decision.retry_after);
+        response
+    };
+
+    let headers = response.headers_mut();
+    headers.insert(HeaderName::from_static("ratelimit-limit"), HeaderValue::from(quota.burst));
+    headers.insert(HeaderName::from_static("ratelimit-remaining"), HeaderValue::from(decision.remaining));
+    insert_seconds(headers, HeaderName::from_static("ratelimit-reset"), decision.reset_after);
+    if let Ok(policy) = HeaderValue::from_str(&format!("{};w={}", quota.burst, quota.window().as_secs().max(1))) {
+        headers.insert(HeaderName::from_static("ratelimit-policy"), policy);
+    }
+
+    response
+}
+
+/// Inserts a duration header rounded up to whole seconds.
+fn insert_seconds(headers: &mut HeaderMap, name: HeaderName, duration: Duration) {
+    headers.insert(name, HeaderValue::from(duration.as_secs_f64().ceil() as u64));
+}
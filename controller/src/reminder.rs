@@ -0,0 +1,102 @@
+//! Axum handler functions for note reminder endpoints.
+//!
+//! A reminder is set on a note through `/api/notes/{id}/reminder` and fired
+//! by the application's scheduler once due. Fired reminders are streamed to
+//! clients of `/api/reminders/events` as server-sent events.
+
+use std::convert::Infallible;
+
+use axum::{
+    Json,
+    extract::{
+        Path, Query, State,
+        rejection::{JsonRejection, PathRejection, QueryRejection},
+    },
+    response::{
+        IntoResponse,
+        sse::{Event, KeepAlive, Sse},
+    },
+};
+use model::dto::reminder::{ReminderEvent, ReminderQuery, SetReminderRequest};
+use service::reminder::ReminderService;
+use tokio::sync::broadcast;
+use tokio_stream::{StreamExt, wrappers::BroadcastStream};
+
+use crate::error::AppError;
+
+/// The SSE event name of a fired reminder.
+const REMINDER_EVENT: &str = "reminder";
+
+/// `PUT /api/notes/{id}/reminder` – sets the reminder of a note, replacing
+/// any previous one, and returns the note.
+#[tracing::instrument(skip_all)]
+pub async fn set_note_reminder<Service: ReminderService>(
+    State(service): State<Service>,
+    path: Result<Path<i64>, PathRejection>,
+    body: Result<Json<SetReminderRequest>, JsonRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    let Path(id) = path?;
+    let Json(req) = body?;
+    tracing::info!(id, "Setting note reminder");
+    let note = service.set_reminder(id, req).await.map_err(AppError::from)?;
+
+    Ok(Json(note))
+}
+
+/// `DELETE /api/notes/{id}/reminder` – removes the reminder of a note and
+/// returns the note.
+#[tracing::instrument(skip_all)]
+pub async fn clear_note_reminder<Service: ReminderService>(
+    State(service): State<Service>,
+    path: Result<Path<i64>, PathRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    let Path(id) = path?;
+    tracing::info!(id, "Clearing note reminder");
+    let note = service.clear_reminder(id).await.map_err(AppError::from)?;
+
+    Ok(Json(note))
+}
+
+/// `GET /api/reminders` – lists up to `limit` pending reminders, soonest
+/// first, optionally only those due before `before`.
+#[tracing::instrument(skip_all)]
+pub async fn list_reminders<Service: ReminderService>(
+    State(service): State<Service>,
+    query: Result<Query<ReminderQuery>, QueryRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    let Query(query) = query.map_err(|rejection| AppError::BadRequest(rejection.body_text()))?;
+    tracing::info!("Listing reminders");
+    let reminders = service.find_reminders(query).await.map_err(AppError::from)?;
+
+    Ok(Json(reminders))
+}
+
+/// `GET /api/reminders/events` – streams fired reminders as `reminder`
+/// server-sent events with a JSON [`ReminderEvent`] payload.
+///
+/// The handler only holds a weak reference to the scheduler's channel, so
+/// every stream ends once the scheduler stops. Events a slow client misses
+/// are skipped.
+#[tracing::instrument(skip_all)]
+pub async fn stream_reminder_events(State(sender): State<broadcast::WeakSender<ReminderEvent>>) -> Result<impl IntoResponse, AppError> {
+    let receiver = sender
+        .upgrade()
+        .ok_or_else(|| AppError::ServiceUnavailable("Reminder events are no longer published".into()))?
+        .subscribe();
+    tracing::info!("Streaming reminder events");
+
+    let events = BroadcastStream::new(receiver).filter_map(|received| match received {
+        Ok(event) => Event::default()
+            .event(REMINDER_EVENT)
+            .json_data(&event)
+            .inspect_err(|err| tracing::warn!(%err, "Failed to encode reminder event"))
+            .ok()
+            .map(Ok::<_, Infallible>),
+        Err(err) => {
+            tracing::warn!(%err, "Reminder event stream lagged");
+            None
+        },
+    });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
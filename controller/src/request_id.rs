@@ -0,0 +1,88 @@
+//! Per-request correlation identifiers.
+//!
+//! [`assign_request_id`] reuses a well-formed `X-Request-Id` header supplied
+//! by the caller or generates a fresh UUID, exposes it to downstream
+//! middleware as a [`RequestId`] extension, echoes it in the response, and
+//! scopes it so that [`AppError`](crate::error::AppError) can include it in
+//! error bodies.
+
+use std::fmt;
+
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use uuid::Uuid;
+
+/// The header used to carry the request identifier in both directions.
+pub static REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Maximum accepted length of a caller-supplied request identifier.
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    /// The identifier of the request currently being handled.
+    static CURRENT_REQUEST_ID: RequestId;
+}
+
+/// An opaque identifier correlating all log lines, spans, and responses
+/// belonging to a single HTTP request.
+#[derive(Debug, Clone)]
+pub struct RequestId(String);
+
+impl RequestId {
+    /// Returns the identifier of the request being handled on the current
+    /// task, if any.
+    pub fn current() -> Option<RequestId> {
+        CURRENT_REQUEST_ID.try_with(RequestId::clone).ok()
+    }
+
+    /// Returns the identifier as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Accepts a caller-supplied identifier if it is non-empty, reasonably
+    /// short, and made up of visible ASCII characters only.
+    fn from_header(value: &HeaderValue) -> Option<Self> {
+        let value = value.to_str().ok()?;
+        let valid = !value.is_empty() && value.len() <= MAX_REQUEST_ID_LEN && value.bytes().all(|byte| byte.is_ascii_graphic());
+
+        valid.then(|| Self(value.to_owned()))
+    }
+
+    /// Generates a fresh random identifier.
+    fn generate() -> Self {
+        Self(Uuid::new_v4().to_string())
+    }
+}
+
+impl fmt::Display for RequestId {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(&self.0)
+    }
+}
+
+/// Axum middleware that assigns a [`RequestId`] to every request.
+///
+/// Must be installed outside of the tracing middleware so that the request
+/// span can record the identifier.
+pub async fn assign_request_id(mut request: Request, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get(&REQUEST_ID_HEADER)
+        .and_then(RequestId::from_header)
+        .unwrap_or_else(RequestId::generate);
+
+    request.extensions_mut().insert(request_id.clone());
+
+    let mut response = CURRENT_REQUEST_ID.scope(request_id.clone(), next.run(request)).await;
+
+    if let Ok(value) = HeaderValue::from_str(request_id.as_str()) {
+        response.headers_mut().insert(REQUEST_ID_HEADER.clone(), value);
+    }
+
+    response
+}
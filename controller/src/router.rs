@@ -1,37 +1,322 @@
 //! Application router construction.
 //!
-//! [`AppRouter`] provides a typed builder that converts a [`NoteService`] into
-//! a fully configured Axum [`Router`] via the [`From`] trait.
+//! [`AppRouter`] provides a typed builder that converts the services of each
+//! resource, such as the [`NoteService`], into a fully configured Axum
+//! [`Router`] via the [`From`] trait. Health probes are mounted under `/health`
+//! and report the shared [`Readiness`] state. An optional [`RateLimiter`] is
+//! enforced on every API route, API bodies are compressed according to the
+//! configured [`Compression`], and the [`RequestLimits`] bound body sizes,
+//! per-route durations, and concurrency. An optional [`CorsPolicy`] answers
+//! preflight requests and grants cross-origin access to browser clients.
+//! Timestamps are rendered with the server's default [`DateDisplay`] unless the
+//! request selects another. Saved searches are mounted under `/api/searches`
+//! and executed through `/api/searches/{id}/notes`. Reminders are set through
+//! `/api/notes/{id}/reminder` and listed under `/api/reminders`; when a channel
+//! of fired reminders is supplied, they are streamed from
+//! `/api/reminders/events`. Task list items are read and ticked under
+//! `/api/notes/{id}/tasks` and listed across notes under `/api/tasks`. Note
+//! templates are managed under `/api/templates` and rendered into new notes
+//! through `/api/notes/from-template/{id}`. The value types of custom note
+//! properties are declared under `/api/property-schemas`. Notes are copied
+//! through `/api/notes/{id}/duplicate` and combined through `/api/notes/merge`.
 
-use axum::{routing::get, Router};
-use service::note::NoteService;
+use std::{sync::Arc, time::Duration};
 
-use crate::note::{create_note, delete_note, get_note, list_notes, update_note};
+use axum::{
+    Router,
+    handler::Handler,
+    middleware,
+    routing::{delete, get, patch, post, put},
+};
+use model::dto::{datetime::DateDisplay, reminder::ReminderEvent};
+use service::{
+    note::NoteService, property_schema::PropertySchemaService, reminder::ReminderService, saved_search::SavedSearchService, task::TaskService,
+    template::TemplateService,
+};
+use tokio::sync::{Semaphore, broadcast};
 
-/// A typed router builder that converts a [`NoteService`] into an Axum
-/// [`Router`].
-pub struct AppRouter<Service: NoteService> {
-    /// The service instance that will be installed as Axum shared state.
-    service: Service,
+use crate::{
+    compression::Compression,
+    cors::CorsPolicy,
+    health::{Readiness, live, ready},
+    limits::{RequestLimits, enforce_timeout, limit_body_size, shed_load},
+    negotiation::negotiate_date_display,
+    note::{clear_note_flag, create_note, delete_note, duplicate_note, get_note, list_notes, merge_notes, set_note_flag, suggest_notes, update_note},
+    property_schema::{create_property_schema, delete_property_schema, list_property_schemas},
+    rate_limit::{RateLimiter, enforce_rate_limit},
+    reminder::{clear_note_reminder, list_reminders, set_note_reminder, stream_reminder_events},
+    request_id::assign_request_id,
+    saved_search::{create_saved_search, delete_saved_search, get_saved_search, list_saved_search_notes, list_saved_searches},
+    task::{list_note_tasks, list_tasks, update_note_task},
+    telemetry::{log_access, propagate_trace_context},
+    template::{create_note_from_template, create_template, delete_template, get_template, list_templates, update_template},
+};
+
+/// A typed router builder that converts the service of each resource into
+/// an Axum [`Router`].
+pub struct AppRouter<Notes, Reminders, Tasks, Searches, Templates, Schemas>
+where
+    Notes: NoteService,
+    Reminders: ReminderService,
+    Tasks: TaskService,
+    Searches: SavedSearchService,
+    Templates: TemplateService,
+    Schemas: PropertySchemaService,
+{
+    /// The service installed as shared state of the note routes.
+    notes: Notes,
+    /// The service installed as shared state of the reminder routes.
+    reminders: Reminders,
+    /// The service installed as shared state of the task routes.
+    tasks: Tasks,
+    /// The service installed as shared state of the saved search routes.
+    saved_searches: Searches,
+    /// The service installed as shared state of the template routes.
+    templates: Templates,
+    /// The service installed as shared state of the property schema routes.
+    property_schemas: Schemas,
+    /// The readiness flag reported by `/health/ready`.
+    readiness: Readiness,
+    /// The rate limiter applied to API routes, if any.
+    rate_limiter: Option<Arc<RateLimiter>>,
+    /// The request/response compression settings for API routes.
+    compression: Compression,
+    /// The size, timeout, and concurrency limits for API routes.
+    limits: RequestLimits,
+    /// The CORS policy for API routes, if cross-origin access is allowed.
+    cors: Option<CorsPolicy>,
+    /// The timestamp rendering used when a request does not select one.
+    date_display: DateDisplay,
+    /// The channel of fired reminders streamed to clients, if any.
+    reminder_events: Option<broadcast::WeakSender<ReminderEvent>>,
 }
 
-impl<Service: NoteService> AppRouter<Service> {
-    /// Creates a new [`AppRouter`] wrapping the given service.
-    pub fn new(service: Service) -> Self {
-        Self { service }
+impl<Notes, Reminders, Tasks, Searches, Templates, Schemas> AppRouter<Notes, Reminders, Tasks, Searches, Templates, Schemas>
+where
+    Notes: NoteService,
+    Reminders: ReminderService,
+    Tasks: TaskService,
+    Searches: SavedSearchService,
+    Templates: TemplateService,
+    Schemas: PropertySchemaService,
+{
+    /// Creates a new [`AppRouter`] wrapping the given services.
+    pub fn new(notes: Notes, reminders: Reminders, tasks: Tasks, saved_searches: Searches, templates: Templates, property_schemas: Schemas) -> Self {
+        Self {
+            notes,
+            reminders,
+            tasks,
+            saved_searches,
+            templates,
+            property_schemas,
+            readiness: Readiness::new(),
+            rate_limiter: None,
+            compression: Compression::default(),
+            limits: RequestLimits::default(),
+            cors: None,
+            date_display: DateDisplay::default(),
+            reminder_events: None,
+        }
+    }
+
+    /// Streams the reminders published on the given channel from
+    /// `/api/reminders/events`. The router holds a weak reference only, so
+    /// open streams end once every sender is dropped.
+    pub fn reminder_events(mut self, sender: broadcast::WeakSender<ReminderEvent>) -> Self {
+        self.reminder_events = Some(sender);
+        self
+    }
+
+    /// Overrides the default timestamp format, time zone, and locale.
+    pub fn date_display(mut self, date_display: DateDisplay) -> Self {
+        self.date_display = date_display;
+        self
+    }
+
+    /// Allows cross-origin requests to the API routes under the given
+    /// policy.
+    pub fn cors(mut self, policy: CorsPolicy) -> Self {
+        self.cors = Some(policy);
+        self
+    }
+
+    /// Overrides the default body-size, timeout, and concurrency limits.
+    pub fn limits(mut self, limits: RequestLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Overrides the default request/response compression settings.
+    pub fn compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Enforces the given per-client rate limits on all API routes.
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(Arc::new(rate_limiter));
+        self
+    }
+
+    /// Reports the given readiness handle from `/health/ready`, allowing the
+    /// caller to flip it when shutdown begins.
+    pub fn readiness(mut self, readiness: Readiness) -> Self {
+        self.readiness = readiness;
+        self
     }
 }
 
-impl<Service: NoteService> From<AppRouter<Service>> for Router {
-    /// Builds the full Axum [`Router`] with all note endpoints registered
-    /// and the service installed as shared state.
-    fn from(app: AppRouter<Service>) -> Self {
-        Router::new()
-            .route("/api/notes", get(list_notes::<Service>).post(create_note::<Service>))
+impl<Notes, Reminders, Tasks, Searches, Templates, Schemas> From<AppRouter<Notes, Reminders, Tasks, Searches, Templates, Schemas>> for Router
+where
+    Notes: NoteService,
+    Reminders: ReminderService,
+    Tasks: TaskService,
+    Searches: SavedSearchService,
+    Templates: TemplateService,
+    Schemas: PropertySchemaService,
+{
+    /// Builds the full Axum [`Router`] with all endpoints registered, the
+    /// service of each resource installed as the shared state of its routes,
+    /// and request-ID assignment, load shedding, trace-context propagation,
+    /// access logging, rate limiting, date-display negotiation, and body-size
+    /// limiting applied to every request (outermost first). Each route
+    /// additionally enforces its own timeout, except for the long-lived
+    /// reminder event stream. Response compression wraps all of these so that
+    /// the access log reports uncompressed sizes, whilst request decompression
+    /// runs just before the handlers. CORS, when configured, wraps everything
+    /// so that preflight requests are answered immediately and error responses
+    /// remain readable cross-origin. Health probes bypass these middleware so
+    /// that they do not flood the access log.
+    fn from(app: AppRouter<Notes, Reminders, Tasks, Searches, Templates, Schemas>) -> Self {
+        let health = Router::new()
+            .route("/health/live", get(live))
+            .route("/health/ready", get(ready))
+            .with_state(app.readiness);
+
+        let limits = app.limits;
+        let timeout = |duration: Duration| middleware::from_fn_with_state(duration, enforce_timeout);
+
+        let notes = Router::new()
+            .route(
+                "/api/notes",
+                get(list_notes::<Notes>.layer(timeout(limits.list_timeout))).post(create_note::<Notes>.layer(timeout(limits.write_timeout))),
+            )
+            .route("/api/notes/suggest", get(suggest_notes::<Notes>.layer(timeout(limits.list_timeout))))
+            .route("/api/notes/merge", post(merge_notes::<Notes>.layer(timeout(limits.write_timeout))))
             .route(
                 "/api/notes/{id}",
-                get(get_note::<Service>).put(update_note::<Service>).delete(delete_note::<Service>),
+                get(get_note::<Notes>.layer(timeout(limits.read_timeout)))
+                    .put(update_note::<Notes>.layer(timeout(limits.write_timeout)))
+                    .delete(delete_note::<Notes>.layer(timeout(limits.write_timeout))),
+            )
+            .route(
+                "/api/notes/{id}/duplicate",
+                post(duplicate_note::<Notes>.layer(timeout(limits.write_timeout))),
             )
-            .with_state(app.service)
+            .route(
+                "/api/notes/{id}/{flag}",
+                put(set_note_flag::<Notes>.layer(timeout(limits.write_timeout)))
+                    .delete(clear_note_flag::<Notes>.layer(timeout(limits.write_timeout))),
+            )
+            .with_state(app.notes);
+
+        let reminders = Router::new()
+            .route(
+                "/api/notes/{id}/reminder",
+                put(set_note_reminder::<Reminders>.layer(timeout(limits.write_timeout)))
+                    .delete(clear_note_reminder::<Reminders>.layer(timeout(limits.write_timeout))),
+            )
+            .route("/api/reminders", get(list_reminders::<Reminders>.layer(timeout(limits.list_timeout))))
+            .with_state(app.reminders);
+
+        let tasks = Router::new()
+            .route("/api/notes/{id}/tasks", get(list_note_tasks::<Tasks>.layer(timeout(limits.read_timeout))))
+            .route(
+                "/api/notes/{id}/tasks/{index}",
+                patch(update_note_task::<Tasks>.layer(timeout(limits.write_timeout))),
+            )
+            .route("/api/tasks", get(list_tasks::<Tasks>.layer(timeout(limits.list_timeout))))
+            .with_state(app.tasks);
+
+        let saved_searches = Router::new()
+            .route(
+                "/api/searches",
+                get(list_saved_searches::<Searches>.layer(timeout(limits.read_timeout)))
+                    .post(create_saved_search::<Searches>.layer(timeout(limits.write_timeout))),
+            )
+            .route(
+                "/api/searches/{id}",
+                get(get_saved_search::<Searches>.layer(timeout(limits.read_timeout)))
+                    .delete(delete_saved_search::<Searches>.layer(timeout(limits.write_timeout))),
+            )
+            .route(
+                "/api/searches/{id}/notes",
+                get(list_saved_search_notes::<Searches>.layer(timeout(limits.list_timeout))),
+            )
+            .with_state(app.saved_searches);
+
+        let templates = Router::new()
+            .route(
+                "/api/notes/from-template/{id}",
+                post(create_note_from_template::<Templates>.layer(timeout(limits.write_timeout))),
+            )
+            .route(
+                "/api/templates",
+                get(list_templates::<Templates>.layer(timeout(limits.read_timeout)))
+                    .post(create_template::<Templates>.layer(timeout(limits.write_timeout))),
+            )
+            .route(
+                "/api/templates/{id}",
+                get(get_template::<Templates>.layer(timeout(limits.read_timeout)))
+                    .put(update_template::<Templates>.layer(timeout(limits.write_timeout)))
+                    .delete(delete_template::<Templates>.layer(timeout(limits.write_timeout))),
+            )
+            .with_state(app.templates);
+
+        let property_schemas = Router::new()
+            .route(
+                "/api/property-schemas",
+                get(list_property_schemas::<Schemas>.layer(timeout(limits.read_timeout)))
+                    .post(create_property_schema::<Schemas>.layer(timeout(limits.write_timeout))),
+            )
+            .route(
+                "/api/property-schemas/{id}",
+                delete(delete_property_schema::<Schemas>.layer(timeout(limits.write_timeout))),
+            )
+            .with_state(app.property_schemas);
+
+        let api = notes
+            .merge(reminders)
+            .merge(tasks)
+            .merge(saved_searches)
+            .merge(templates)
+            .merge(property_schemas);
+
+        let api = match app.reminder_events {
+            Some(sender) => api.route("/api/reminders/events", get(stream_reminder_events).with_state(sender)),
+            None => api,
+        };
+
+        let api = app
+            .compression
+            .decompress_requests(api)
+            .layer(middleware::from_fn_with_state(limits.max_body_size, limit_body_size))
+            .layer(middleware::from_fn_with_state(app.date_display, negotiate_date_display))
+            .layer(middleware::from_fn_with_state(app.rate_limiter, enforce_rate_limit))
+            .layer(middleware::from_fn(log_access))
+            .layer(middleware::from_fn(propagate_trace_context))
+            .layer(middleware::from_fn_with_state(
+                Arc::new(Semaphore::new(limits.max_concurrent_requests)),
+                shed_load,
+            ))
+            .layer(middleware::from_fn(assign_request_id));
+
+        let api = app.compression.compress_responses(api);
+        let api = match &app.cors {
+            Some(policy) => api.layer(policy.layer()),
+            None => api,
+        };
+
+        api.merge(health)
     }
 }
@@ -0,0 +1,90 @@
+//! Axum handler functions for saved-search endpoints.
+//!
+//! A saved search stores the raw `GET /api/notes` query parameters under a
+//! unique name. Executing it runs the stored parameters through the same
+//! validation and query path as an ad-hoc listing.
+
+use axum::{
+    Json,
+    extract::{
+        Path, Query, State,
+        rejection::{JsonRejection, PathRejection, QueryRejection},
+    },
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use model::dto::saved_search::{CreateSavedSearchRequest, SavedSearchQuery};
+use service::saved_search::SavedSearchService;
+
+use crate::{
+    error::AppError,
+    negotiation::{NoteListFormat, negotiate, render_note_list},
+};
+
+/// `POST /api/searches` – saves a named search and returns it with
+/// `201 Created`.
+#[tracing::instrument(skip_all)]
+pub async fn create_saved_search<Service: SavedSearchService>(
+    State(service): State<Service>,
+    body: Result<Json<CreateSavedSearchRequest>, JsonRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    let Json(req) = body?;
+    tracing::info!("Saving search");
+    let search = service.create(req).await.map_err(AppError::from)?;
+
+    Ok((StatusCode::CREATED, Json(search)))
+}
+
+/// `GET /api/searches` – lists every saved search, ordered by name.
+#[tracing::instrument(skip_all)]
+pub async fn list_saved_searches<Service: SavedSearchService>(State(service): State<Service>) -> Result<impl IntoResponse, AppError> {
+    tracing::info!("Listing saved searches");
+    let searches = service.find_all().await.map_err(AppError::from)?;
+
+    Ok(Json(searches))
+}
+
+/// `GET /api/searches/{id}` – retrieves a single saved search.
+#[tracing::instrument(skip_all)]
+pub async fn get_saved_search<Service: SavedSearchService>(
+    State(service): State<Service>,
+    path: Result<Path<i64>, PathRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    let Path(id) = path?;
+    tracing::info!(id, "Fetching saved search");
+    let search = service.find_by_id(id).await.map_err(AppError::from)?;
+
+    Ok(Json(search))
+}
+
+/// `DELETE /api/searches/{id}` – deletes a saved search and returns
+/// `204 No Content`.
+#[tracing::instrument(skip_all)]
+pub async fn delete_saved_search<Service: SavedSearchService>(
+    State(service): State<Service>,
+    path: Result<Path<i64>, PathRejection>,
+) -> Result<StatusCode, AppError> {
+    let Path(id) = path?;
+    tracing::info!(id, "Deleting saved search");
+    service.delete(id).await.map_err(AppError::from)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `GET /api/searches/{id}/notes` – executes a saved search and returns the
+/// matching notes as JSON or CSV, optionally on another `page`.
+#[tracing::instrument(skip_all)]
+pub async fn list_saved_search_notes<Service: SavedSearchService>(
+    State(service): State<Service>,
+    headers: HeaderMap,
+    path: Result<Path<i64>, PathRejection>,
+    query: Result<Query<SavedSearchQuery>, QueryRejection>,
+) -> Result<Response, AppError> {
+    let Path(id) = path?;
+    let Query(query) = query.map_err(|rejection| AppError::BadRequest(rejection.body_text()))?;
+    let format: NoteListFormat = negotiate(&headers)?;
+    tracing::info!(id, ?format, "Executing saved search");
+    let result = service.find_notes(id, query.page).await.map_err(AppError::from)?;
+
+    Ok(render_note_list(result, format))
+}
@@ -0,0 +1,61 @@
+//! Axum handler functions for the task list items of notes.
+//!
+//! Task list items (`- [ ]` and `- [x]`) are parsed from note content on
+//! every request; ticking one rewrites the Markdown of its note.
+
+use axum::{
+    Json,
+    extract::{
+        Path, Query, State,
+        rejection::{JsonRejection, PathRejection, QueryRejection},
+    },
+    response::IntoResponse,
+};
+use model::dto::task::{TaskQuery, UpdateTaskRequest};
+use service::task::TaskService;
+
+use crate::error::AppError;
+
+/// `GET /api/notes/{id}/tasks` – lists the task list items of a note in
+/// document order.
+#[tracing::instrument(skip_all)]
+pub async fn list_note_tasks<Service: TaskService>(
+    State(service): State<Service>,
+    path: Result<Path<i64>, PathRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    let Path(id) = path?;
+    tracing::info!(id, "Listing note tasks");
+    let tasks = service.find_note_tasks(id).await.map_err(AppError::from)?;
+
+    Ok(Json(tasks))
+}
+
+/// `PATCH /api/notes/{id}/tasks/{index}` – ticks or clears a task list item,
+/// or toggles it when the body omits `done`, and returns the item.
+#[tracing::instrument(skip_all)]
+pub async fn update_note_task<Service: TaskService>(
+    State(service): State<Service>,
+    path: Result<Path<(i64, usize)>, PathRejection>,
+    body: Result<Json<UpdateTaskRequest>, JsonRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    let Path((id, index)) = path?;
+    let Json(req) = body?;
+    tracing::info!(id, index, "Updating note task");
+    let task = service.update_task(id, index, req).await.map_err(AppError::from)?;
+
+    Ok(Json(task))
+}
+
+/// `GET /api/tasks` – lists task list items across unarchived notes,
+/// optionally only open (`done=false`) or ticked (`done=true`) ones.
+#[tracing::instrument(skip_all)]
+pub async fn list_tasks<Service: TaskService>(
+    State(service): State<Service>,
+    query: Result<Query<TaskQuery>, QueryRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    let Query(query) = query.map_err(|rejection| AppError::BadRequest(rejection.body_text()))?;
+    tracing::info!("Listing tasks");
+    let tasks = service.find_tasks(query).await.map_err(AppError::from)?;
+
+    Ok(Json(tasks))
+}
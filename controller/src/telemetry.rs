@@ -0,0 +1,138 @@
+//! Request tracing and access logging middleware.
+//!
+//! [`propagate_trace_context`] wraps every request in a server span whose
+//! parent is extracted from the incoming `traceparent`/`tracestate` headers,
+//! so that traces started by an upstream gateway continue into this service.
+//! The span's context is injected back into the response headers.
+//!
+//! [`log_access`] emits a single access-log event per request once the
+//! response body has been sent.
+
+use std::time::{Duration, Instant};
+
+use axum::{
+    body::Body,
+    extract::{MatchedPath, Request},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+use http_body_util::BodyExt;
+use opentelemetry::global;
+use opentelemetry_http::{HeaderExtractor, HeaderInjector};
+use tracing::Instrument;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::request_id::RequestId;
+
+/// Axum middleware that continues the caller's distributed trace.
+///
+/// Creates an `http_request` span parented to the extracted remote context
+/// and tagged with the [`RequestId`], runs the rest of the stack inside it,
+/// and records the response status. The span is named after the matched
+/// route template (e.g. `GET /api/notes/{id}`), or the method alone when no
+/// route matched, so that span names do not grow with every note ID.
+pub async fn propagate_trace_context(request: Request, next: Next) -> Response {
+    let parent_context = global::get_text_map_propagator(|propagator| propagator.extract(&HeaderExtractor(request.headers())));
+    let request_id = request.extensions().get::<RequestId>().map(RequestId::to_string).unwrap_or_default();
+    let route = request.extensions().get::<MatchedPath>().map(MatchedPath::as_str);
+    let name = match route {
+        Some(route) => format!("{} {route}", request.method()),
+        None => request.method().to_string(),
+    };
+
+    let span = tracing::info_span!(
+        "http_request",
+        otel.name = %name,
+        otel.kind = "server",
+        http.request.method = %request.method(),
+        http.route = route,
+        url.path = %request.uri().path(),
+        request_id = %request_id,
+        http.response.status_code = tracing::field::Empty,
+    );
+    let _ = span.set_parent(parent_context);
+
+    let mut response = next.run(request).instrument(span.clone()).await;
+    span.record("http.response.status_code", response.status().as_u16());
+
+    global::get_text_map_propagator(|propagator| propagator.inject_context(&span.context(), &mut HeaderInjector(response.headers_mut())));
+
+    response
+}
+
+/// Axum middleware that logs one `access_log` event per request with the
+/// method, matched route, status, latency, and response body size.
+///
+/// The latency is measured until the response head is ready, whilst the
+/// size counts the body bytes actually sent, before compression; the event
+/// is therefore emitted once the body has been sent or abandoned, which for
+/// event streams is when the client disconnects.
+pub async fn log_access(request: Request, next: Next) -> Response {
+    let method = request.method().clone();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or_else(|| request.uri().path().to_owned(), |path| path.as_str().to_owned());
+    let start = Instant::now();
+
+    let response = next.run(request).await;
+
+    let mut entry = AccessLogEntry {
+        span: tracing::Span::current(),
+        method,
+        route,
+        status: response.status().as_u16(),
+        latency: start.elapsed(),
+        bytes: 0,
+    };
+
+    response.map(|body| {
+        Body::new(body.map_frame(move |frame| {
+            if let Some(data) = frame.data_ref() {
+                entry.count(data.len());
+            }
+            frame
+        }))
+    })
+}
+
+/// The access-log event of a request, emitted when the response body is
+/// dropped, i.e. once it has been sent in full or the client went away.
+struct AccessLogEntry {
+    /// The request's span, re-entered so that the event keeps its fields.
+    span: tracing::Span,
+    /// The request method.
+    method: Method,
+    /// The matched route template, or the path when no route matched.
+    route: String,
+    /// The response status code.
+    status: u16,
+    /// The time taken to produce the response head.
+    latency: Duration,
+    /// The number of body bytes sent so far.
+    bytes: u64,
+}
+
+impl AccessLogEntry {
+    /// Adds a sent chunk of the body to the size.
+    fn count(&mut self, len: usize) {
+        self.bytes += len as u64;
+    }
+}
+
+impl Drop for AccessLogEntry {
+    fn drop(&mut self) {
+        let _entered = self.span.enter();
+
+        tracing::info!(
+            target: "access_log",
+            method = %self.method,
+            route = %self.route,
+            status = self.status,
+            latency_ms = self.latency.as_secs_f64() * 1000.0,
+            bytes = self.bytes,
+            "Request completed"
+        );
+    }
+}
@@ -0,0 +1,104 @@
+//! Axum handler functions for note template endpoints.
+//!
+//! A template stores title and content patterns with `{{variable}}`
+//! placeholders. Creating a note from a template renders the patterns and
+//! submits the result exactly like `POST /api/notes`.
+
+use axum::{
+    Json,
+    extract::{
+        Path, State,
+        rejection::{JsonRejection, PathRejection},
+    },
+    http::StatusCode,
+    response::IntoResponse,
+};
+use model::dto::template::{CreateTemplateRequest, RenderTemplateRequest, UpdateTemplateRequest};
+use service::template::TemplateService;
+
+use crate::error::AppError;
+
+/// `POST /api/templates` – creates a note template and returns it with
+/// `201 Created`.
+#[tracing::instrument(skip_all)]
+pub async fn create_template<Service: TemplateService>(
+    State(service): State<Service>,
+    body: Result<Json<CreateTemplateRequest>, JsonRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    let Json(req) = body?;
+    tracing::info!("Creating template");
+    let template = service.create(req).await.map_err(AppError::from)?;
+
+    Ok((StatusCode::CREATED, Json(template)))
+}
+
+/// `GET /api/templates` – lists every note template, ordered by name.
+#[tracing::instrument(skip_all)]
+pub async fn list_templates<Service: TemplateService>(State(service): State<Service>) -> Result<impl IntoResponse, AppError> {
+    tracing::info!("Listing templates");
+    let templates = service.find_all().await.map_err(AppError::from)?;
+
+    Ok(Json(templates))
+}
+
+/// `GET /api/templates/{id}` – retrieves a single note template.
+#[tracing::instrument(skip_all)]
+pub async fn get_template<Service: TemplateService>(
+    State(service): State<Service>,
+    path: Result<Path<i64>, PathRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    let Path(id) = path?;
+    tracing::info!(id, "Fetching template");
+    let template = service.find_by_id(id).await.map_err(AppError::from)?;
+
+    Ok(Json(template))
+}
+
+/// `PUT /api/templates/{id}` – partially updates a note template.
+#[tracing::instrument(skip_all)]
+pub async fn update_template<Service: TemplateService>(
+    State(service): State<Service>,
+    path: Result<Path<i64>, PathRejection>,
+    body: Result<Json<UpdateTemplateRequest>, JsonRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    let Path(id) = path?;
+    let Json(req) = body?;
+    tracing::info!(id, "Updating template");
+    let template = service.update(id, req).await.map_err(AppError::from)?;
+
+    Ok(Json(template))
+}
+
+/// `DELETE /api/templates/{id}` – deletes a note template and returns
+/// `204 No Content`.
+#[tracing::instrument(skip_all)]
+pub async fn delete_template<Service: TemplateService>(
+    State(service): State<Service>,
+    path: Result<Path<i64>, PathRejection>,
+) -> Result<StatusCode, AppError> {
+    let Path(id) = path?;
+    tracing::info!(id, "Deleting template");
+    service.delete(id).await.map_err(AppError::from)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `POST /api/notes/from-template/{id}` – renders a template into a new
+/// note and returns it with `201 Created`.
+///
+/// The body supplies the custom `variables` and the value of `{{user}}`,
+/// either as its `user` field or among the `variables`; it may be omitted
+/// when the template only uses `{{date}}`.
+#[tracing::instrument(skip_all)]
+pub async fn create_note_from_template<Service: TemplateService>(
+    State(service): State<Service>,
+    path: Result<Path<i64>, PathRejection>,
+    body: Result<Option<Json<RenderTemplateRequest>>, JsonRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    let Path(id) = path?;
+    let req = body?.map(|Json(req)| req).unwrap_or_default();
+    tracing::info!(id, "Creating note from template");
+    let note = service.create_note(id, req).await.map_err(AppError::from)?;
+
+    Ok((StatusCode::CREATED, Json(note)))
+}
@@ -0,0 +1,55 @@
+//! Migration that adds the `pinned`, `starred`, and `archived` flags to the
+//! `notes` table.
+
+use sea_orm_migration::prelude::*;
+
+use crate::create_notes_table;
+
+/// Column identifiers used by the migration DSL.
+#[derive(DeriveIden, Clone, Copy)]
+enum Notes {
+    /// Whether the note is listed before the others on request.
+    Pinned,
+    /// Whether the note is marked as a favourite.
+    Starred,
+    /// Whether the note is hidden from default listings.
+    Archived,
+}
+
+/// The flag columns, in the order they are added.
+const FLAGS: [Notes; 3] = [Notes::Pinned, Notes::Starred, Notes::Archived];
+
+/// Adds (and drops) the flag columns, each defaulting to `false`.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Applies the migration: adds each flag column. SQLite accepts only one
+    /// column per `ALTER TABLE`, so every column is added separately.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for flag in FLAGS {
+            let mut column = ColumnDef::new(flag);
+
+            let table_alter_statement: TableAlterStatement = Table::alter()
+                .table(create_notes_table::TABLE_NAME)
+                .add_column_if_not_exists(column.boolean().not_null().default(false))
+                .to_owned();
+
+            manager.alter_table(table_alter_statement).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Rolls back the migration: drops each flag column.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for flag in FLAGS {
+            let table_alter_statement: TableAlterStatement = Table::alter().table(create_notes_table::TABLE_NAME).drop_column(flag).to_owned();
+
+            manager.alter_table(table_alter_statement).await?;
+        }
+
+        Ok(())
+    }
+}
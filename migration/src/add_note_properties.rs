@@ -0,0 +1,51 @@
+//! Migration that adds the `properties` column to the `notes` table.
+//!
+//! The column holds a JSON object of custom note properties: `JSON` text on
+//! SQLite and `JSONB` on PostgreSQL, so that properties can be compared
+//! without parsing the document on every row.
+
+use sea_orm_migration::{prelude::*, sea_orm::DbBackend};
+
+use crate::create_notes_table;
+
+/// Column identifiers used by the migration DSL.
+#[derive(DeriveIden)]
+enum Notes {
+    /// The custom properties of the note.
+    Properties,
+}
+
+/// Adds (and drops) the `properties` column, defaulting to an empty object.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Applies the migration: adds the column with the JSON type native to
+    /// the backend.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let mut properties = ColumnDef::new(Notes::Properties);
+
+        match manager.get_database_backend() {
+            DbBackend::Postgres => properties.json_binary(),
+            _ => properties.json(),
+        };
+
+        let table_alter_statement: TableAlterStatement = Table::alter()
+            .table(create_notes_table::TABLE_NAME)
+            .add_column_if_not_exists(properties.not_null().default("{}"))
+            .to_owned();
+
+        manager.alter_table(table_alter_statement).await
+    }
+
+    /// Rolls back the migration: drops the column.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let table_alter_statement: TableAlterStatement = Table::alter()
+            .table(create_notes_table::TABLE_NAME)
+            .drop_column(Notes::Properties)
+            .to_owned();
+
+        manager.alter_table(table_alter_statement).await
+    }
+}
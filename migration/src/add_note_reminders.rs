@@ -0,0 +1,72 @@
+//! Migration that adds the `remind_at` and `recurrence` columns to the
+//! `notes` table.
+
+use sea_orm_migration::prelude::*;
+
+use crate::create_notes_table;
+
+/// The name of the index on `notes.remind_at`.
+const REMIND_AT_INDEX: &str = "notes_remind_at_idx";
+
+/// Column identifiers used by the migration DSL.
+#[derive(DeriveIden, Clone, Copy)]
+enum Notes {
+    /// When the next reminder for the note is due.
+    RemindAt,
+    /// The recurrence rule of the reminder.
+    Recurrence,
+}
+
+/// Adds (and drops) the nullable reminder columns.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Applies the migration: adds both columns and an index on `remind_at`
+    /// for the scheduler. SQLite accepts only one column per `ALTER TABLE`,
+    /// so every column is added separately.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let columns = [
+            ColumnDef::new(Notes::RemindAt).timestamp_with_time_zone().null().to_owned(),
+            ColumnDef::new(Notes::Recurrence).text().null().to_owned(),
+        ];
+
+        for mut column in columns {
+            let table_alter_statement: TableAlterStatement = Table::alter()
+                .table(create_notes_table::TABLE_NAME)
+                .add_column_if_not_exists(&mut column)
+                .to_owned();
+
+            manager.alter_table(table_alter_statement).await?;
+        }
+
+        let index_create_statement: IndexCreateStatement = Index::create()
+            .if_not_exists()
+            .name(REMIND_AT_INDEX)
+            .table(create_notes_table::TABLE_NAME)
+            .col(Notes::RemindAt)
+            .to_owned();
+
+        manager.create_index(index_create_statement).await
+    }
+
+    /// Rolls back the migration: drops the index and both columns.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let index_drop_statement: IndexDropStatement = Index::drop()
+            .if_exists()
+            .name(REMIND_AT_INDEX)
+            .table(create_notes_table::TABLE_NAME)
+            .to_owned();
+
+        manager.drop_index(index_drop_statement).await?;
+
+        for column in [Notes::RemindAt, Notes::Recurrence] {
+            let table_alter_statement: TableAlterStatement = Table::alter().table(create_notes_table::TABLE_NAME).drop_column(column).to_owned();
+
+            manager.alter_table(table_alter_statement).await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+//! Migration that creates the `idempotency_keys` table and its expiry index.
+
+use sea_orm_migration::prelude::*;
+
+/// The name of the table managed by this migration.
+pub const TABLE_NAME: &str = "idempotency_keys";
+
+/// The name of the index on `expires_at`, used when purging stale keys.
+const EXPIRES_AT_INDEX: &str = "idempotency_keys_expires_at_idx";
+
+/// Column identifiers used by the migration DSL.
+#[derive(DeriveIden)]
+enum IdempotencyKeys {
+    /// Primary-key column holding the client-supplied key.
+    Key,
+    /// Request fingerprint column.
+    Fingerprint,
+    /// Stored HTTP status code column.
+    StatusCode,
+    /// Stored JSON response body column.
+    ResponseBody,
+    /// Row creation timestamp column.
+    CreatedAt,
+    /// Expiry timestamp column.
+    ExpiresAt,
+}
+
+/// Creates (and drops) the `idempotency_keys` table together with an index
+/// on `expires_at`.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Applies the migration: creates the `idempotency_keys` table and the
+    /// `idempotency_keys_expires_at_idx` index if they do not already exist.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let mut key = ColumnDef::new(IdempotencyKeys::Key);
+        let mut fingerprint = ColumnDef::new(IdempotencyKeys::Fingerprint);
+        let mut status_code = ColumnDef::new(IdempotencyKeys::StatusCode);
+        let mut response_body = ColumnDef::new(IdempotencyKeys::ResponseBody);
+        let mut created_at = ColumnDef::new(IdempotencyKeys::CreatedAt);
+        let mut expires_at = ColumnDef::new(IdempotencyKeys::ExpiresAt);
+
+        let table_create_statement: TableCreateStatement = Table::create()
+            .table(TABLE_NAME)
+            .if_not_exists()
+            .col(key.string().not_null().primary_key())
+            .col(fingerprint.string().not_null())
+            .col(status_code.small_integer().not_null())
+            .col(response_body.text().not_null())
+            .col(created_at.date_time().not_null().default(Expr::current_timestamp()))
+            .col(expires_at.date_time().not_null())
+            .to_owned();
+
+        let expires_at_index_create_statement: IndexCreateStatement = Index::create()
+            .if_not_exists()
+            .name(EXPIRES_AT_INDEX)
+            .table(TABLE_NAME)
+            .col(IdempotencyKeys::ExpiresAt)
+            .to_owned();
+
+        manager.create_table(table_create_statement).await?;
+        manager.create_index(expires_at_index_create_statement).await?;
+
+        Ok(())
+    }
+
+    /// Rolls back the migration: drops the `idempotency_keys_expires_at_idx`
+    /// index and then the `idempotency_keys` table.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let index_drop_statement: IndexDropStatement = Index::drop().name(EXPIRES_AT_INDEX).table(TABLE_NAME).to_owned();
+
+        let table_drop_statement: TableDropStatement = Table::drop().table(TABLE_NAME).to_owned();
+
+        manager.drop_index(index_drop_statement).await?;
+        manager.drop_table(table_drop_statement).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+//! Migration that creates the `property_schemas` table and its unique key
+//! index.
+
+use sea_orm_migration::prelude::*;
+
+/// The name of the table managed by this migration.
+pub const TABLE_NAME: &str = "property_schemas";
+
+/// The name of the unique index on `key`.
+const KEY_INDEX: &str = "property_schemas_key_idx";
+
+/// Column identifiers used by the migration DSL.
+#[derive(DeriveIden)]
+enum PropertySchemas {
+    /// Auto-incrementing primary-key column.
+    Id,
+    /// Unique property key column.
+    Key,
+    /// Property value type column.
+    Type,
+    /// Row creation timestamp column.
+    CreatedAt,
+}
+
+/// Creates (and drops) the `property_schemas` table together with a unique
+/// index on `key`.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Applies the migration: creates the `property_schemas` table and the
+    /// `property_schemas_key_idx` index if they do not already exist.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let mut id = ColumnDef::new(PropertySchemas::Id);
+        let mut key = ColumnDef::new(PropertySchemas::Key);
+        let mut kind = ColumnDef::new(PropertySchemas::Type);
+        let mut created_at = ColumnDef::new(PropertySchemas::CreatedAt);
+
+        let table_create_statement: TableCreateStatement = Table::create()
+            .table(TABLE_NAME)
+            .if_not_exists()
+            .col(id.integer().not_null().auto_increment().primary_key())
+            .col(key.string().not_null())
+            .col(kind.string().not_null())
+            .col(created_at.date_time().not_null().default(Expr::current_timestamp()))
+            .to_owned();
+
+        let key_index_create_statement: IndexCreateStatement = Index::create()
+            .if_not_exists()
+            .unique()
+            .name(KEY_INDEX)
+            .table(TABLE_NAME)
+            .col(PropertySchemas::Key)
+            .to_owned();
+
+        manager.create_table(table_create_statement).await?;
+        manager.create_index(key_index_create_statement).await?;
+
+        Ok(())
+    }
+
+    /// Rolls back the migration: drops the `property_schemas_key_idx` index
+    /// and then the `property_schemas` table.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let index_drop_statement: IndexDropStatement = Index::drop().name(KEY_INDEX).table(TABLE_NAME).to_owned();
+
+        let table_drop_statement: TableDropStatement = Table::drop().table(TABLE_NAME).to_owned();
+
+        manager.drop_index(index_drop_statement).await?;
+        manager.drop_table(table_drop_statement).await?;
+
+        Ok(())
+    }
+}
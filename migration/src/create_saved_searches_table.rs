@@ -0,0 +1,75 @@
+//! Migration that creates the `saved_searches` table and its unique name
+//! index.
+
+use sea_orm_migration::prelude::*;
+
+/// The name of the table managed by this migration.
+pub const TABLE_NAME: &str = "saved_searches";
+
+/// The name of the unique index on `name`.
+const NAME_INDEX: &str = "saved_searches_name_idx";
+
+/// Column identifiers used by the migration DSL.
+#[derive(DeriveIden)]
+enum SavedSearches {
+    /// Auto-incrementing primary-key column.
+    Id,
+    /// Unique display name column.
+    Name,
+    /// Serialised search parameters column (JSON).
+    Params,
+    /// Row creation timestamp column.
+    CreatedAt,
+}
+
+/// Creates (and drops) the `saved_searches` table together with a unique
+/// index on `name`.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Applies the migration: creates the `saved_searches` table and the
+    /// `saved_searches_name_idx` index if they do not already exist.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let mut id = ColumnDef::new(SavedSearches::Id);
+        let mut name = ColumnDef::new(SavedSearches::Name);
+        let mut params = ColumnDef::new(SavedSearches::Params);
+        let mut created_at = ColumnDef::new(SavedSearches::CreatedAt);
+
+        let table_create_statement: TableCreateStatement = Table::create()
+            .table(TABLE_NAME)
+            .if_not_exists()
+            .col(id.integer().not_null().auto_increment().primary_key())
+            .col(name.string().not_null())
+            .col(params.text().not_null())
+            .col(created_at.date_time().not_null().default(Expr::current_timestamp()))
+            .to_owned();
+
+        let name_index_create_statement: IndexCreateStatement = Index::create()
+            .if_not_exists()
+            .unique()
+            .name(NAME_INDEX)
+            .table(TABLE_NAME)
+            .col(SavedSearches::Name)
+            .to_owned();
+
+        manager.create_table(table_create_statement).await?;
+        manager.create_index(name_index_create_statement).await?;
+
+        Ok(())
+    }
+
+    /// Rolls back the migration: drops the `saved_searches_name_idx` index
+    /// and then the `saved_searches` table.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let index_drop_statement: IndexDropStatement = Index::drop().name(NAME_INDEX).table(TABLE_NAME).to_owned();
+
+        let table_drop_statement: TableDropStatement = Table::drop().table(TABLE_NAME).to_owned();
+
+        manager.drop_index(index_drop_statement).await?;
+        manager.drop_table(table_drop_statement).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+//! Migration that creates the `templates` table and its unique name index.
+
+use sea_orm_migration::prelude::*;
+
+/// The name of the table managed by this migration.
+pub const TABLE_NAME: &str = "templates";
+
+/// The name of the unique index on `name`.
+const NAME_INDEX: &str = "templates_name_idx";
+
+/// Column identifiers used by the migration DSL.
+#[derive(DeriveIden)]
+enum Templates {
+    /// Auto-incrementing primary-key column.
+    Id,
+    /// Unique display name column.
+    Name,
+    /// Title pattern column.
+    Title,
+    /// Content pattern column.
+    Content,
+    /// Row creation timestamp column.
+    CreatedAt,
+    /// Row last-updated timestamp column.
+    UpdatedAt,
+}
+
+/// Creates (and drops) the `templates` table together with a unique index
+/// on `name`.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Applies the migration: creates the `templates` table and the
+    /// `templates_name_idx` index if they do not already exist.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let mut id = ColumnDef::new(Templates::Id);
+        let mut name = ColumnDef::new(Templates::Name);
+        let mut title = ColumnDef::new(Templates::Title);
+        let mut content = ColumnDef::new(Templates::Content);
+        let mut created_at = ColumnDef::new(Templates::CreatedAt);
+        let mut updated_at = ColumnDef::new(Templates::UpdatedAt);
+
+        let table_create_statement: TableCreateStatement = Table::create()
+            .table(TABLE_NAME)
+            .if_not_exists()
+            .col(id.integer().not_null().auto_increment().primary_key())
+            .col(name.string().not_null())
+            .col(title.text().not_null())
+            .col(content.text().not_null())
+            .col(created_at.date_time().not_null().default(Expr::current_timestamp()))
+            .col(updated_at.date_time().not_null().default(Expr::current_timestamp()))
+            .to_owned();
+
+        let name_index_create_statement: IndexCreateStatement = Index::create()
+            .if_not_exists()
+            .unique()
+            .name(NAME_INDEX)
+            .table(TABLE_NAME)
+            .col(Templates::Name)
+            .to_owned();
+
+        manager.create_table(table_create_statement).await?;
+        manager.create_index(name_index_create_statement).await?;
+
+        Ok(())
+    }
+
+    /// Rolls back the migration: drops the `templates_name_idx` index and
+    /// then the `templates` table.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let index_drop_statement: IndexDropStatement = Index::drop().name(NAME_INDEX).table(TABLE_NAME).to_owned();
+
+        let table_drop_statement: TableDropStatement = Table::drop().table(TABLE_NAME).to_owned();
+
+        manager.drop_index(index_drop_statement).await?;
+        manager.drop_table(table_drop_statement).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+//! Migration that enables `pg_trgm` and indexes note titles by trigram on
+//! PostgreSQL, so that fuzzy title searches, which filter with the `%`
+//! operator, do not scan every note. Calls to `SIMILARITY()` cannot use the
+//! index and only rank the matches.
+//!
+//! Other backends have no trigram support; the repository ranks titles
+//! in-process there, and this migration does nothing.
+
+use sea_orm_migration::{prelude::*, sea_orm::DbBackend};
+
+use crate::create_notes_table;
+
+/// The name of the trigram index on `notes.title`.
+const TITLE_TRIGRAM_INDEX: &str = "notes_title_trgm_idx";
+
+/// Creates (and drops) the trigram index on note titles.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Applies the migration: on PostgreSQL, enables the `pg_trgm`
+    /// extension and creates a GIN index on `title` if it does not already
+    /// exist. The `gin_trgm_ops` operator class serves the `%` operator.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if manager.get_database_backend() != DbBackend::Postgres {
+            return Ok(());
+        }
+
+        let connection = manager.get_connection();
+        connection.execute_unprepared("CREATE EXTENSION IF NOT EXISTS pg_trgm").await?;
+        connection
+            .execute_unprepared(&format!(
+                "CREATE INDEX IF NOT EXISTS {TITLE_TRIGRAM_INDEX} ON {} USING GIN (title gin_trgm_ops)",
+                create_notes_table::TABLE_NAME
+            ))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Rolls back the migration: drops the trigram index on PostgreSQL. The
+    /// extension is left installed, as other schemas may rely on it.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if manager.get_database_backend() != DbBackend::Postgres {
+            return Ok(());
+        }
+
+        let index_drop_statement: IndexDropStatement = Index::drop().name(TITLE_TRIGRAM_INDEX).table(create_notes_table::TABLE_NAME).to_owned();
+
+        manager.drop_index(index_drop_statement).await?;
+
+        Ok(())
+    }
+}
@@ -6,13 +6,31 @@
 
 pub use sea_orm_migration::prelude::*;
 
+mod add_note_flags;
+mod add_note_properties;
+mod add_note_reminders;
+mod create_idempotency_keys_table;
 mod create_notes_table;
+mod create_property_schemas_table;
+mod create_saved_searches_table;
+mod create_templates_table;
+mod create_title_trigram_index;
 
 /// Top-level migrator that registers every migration in the correct order.
 pub struct Migrator;
 
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(create_notes_table::Migration)]
+        vec![
+            Box::new(create_notes_table::Migration),
+            Box::new(create_idempotency_keys_table::Migration),
+            Box::new(create_saved_searches_table::Migration),
+            Box::new(create_title_trigram_index::Migration),
+            Box::new(add_note_flags::Migration),
+            Box::new(add_note_reminders::Migration),
+            Box::new(create_templates_table::Migration),
+            Box::new(add_note_properties::Migration),
+            Box::new(create_property_schemas_table::Migration),
+        ]
     }
 }
@@ -1,14 +1,264 @@
-//! Custom date-time wrapper with human-readable serialisation.
+//! Custom date-time wrapper with selectable serialisation.
+//!
+//! How a [`FormattedDateTime`] is rendered is decided per request by a
+//! [`DateDisplay`]: the [`DateFormat`], the time zone the instant is shown
+//! in, and the [`DateLocale`] supplying weekday names, month names, and
+//! ordinal day numbers for the human-readable format.
 
-use chrono::{DateTime, Datelike, Timelike, Utc};
-use serde::{Serialize, Serializer};
-use std::ops::Deref;
+use chrono::{DateTime, Datelike, NaiveDateTime, SecondsFormat, Timelike, Utc};
+use chrono_tz::Tz;
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{self, Visitor},
+};
+use std::{cell::Cell, fmt, future::Future, ops::Deref, str::FromStr};
 
-/// A UTC timestamp that serialises as a human-readable string of the form
-/// `Friday, 3rd August 2034, 12:45:34 PM UTC`.
+/// The `chrono` format matching [`DateFormat::Human`] in English and UTC
+/// once the ordinal suffix has been removed from the day.
+const HUMAN_PARSE_FORMAT: &str = "%A, %d %B %Y, %I:%M:%S %p UTC";
+
+tokio::task_local! {
+    /// The date display settings selected for the request being handled.
+    static CURRENT_DATE_DISPLAY: DateDisplay;
+
+    /// Whether a timestamp was serialised in human-readable form within the
+    /// current [`DateDisplay::scope_tracked`].
+    static HUMAN_DATES_RENDERED: Cell<bool>;
+}
+
+/// How [`FormattedDateTime`] values are serialised.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DateFormat {
+    /// A human-readable string such as
+    /// `Friday, 3rd August 2034, 12:45:34 PM UTC`.
+    #[default]
+    Human,
+    /// An RFC 3339 / ISO 8601 string such as `2034-08-03T12:45:34Z`.
+    Iso,
+    /// Whole seconds since the Unix epoch, as an integer.
+    Unix,
+}
+
+impl DateFormat {
+    /// All variants of the enum, in declaration order.
+    pub const ALL: &[DateFormat] = &[Self::Human, Self::Iso, Self::Unix];
+
+    /// Returns a comma-separated list of all valid format names.
+    pub fn all_names() -> String {
+        Self::ALL.iter().map(|format| format.to_string()).collect::<Vec<_>>().join(", ")
+    }
+}
+
+impl fmt::Display for DateFormat {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Human => "human",
+            Self::Iso => "iso",
+            Self::Unix => "unix",
+        };
+        formatter.write_str(name)
+    }
+}
+
+impl FromStr for DateFormat {
+    type Err = String;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        match string {
+            "human" => Ok(Self::Human),
+            "iso" => Ok(Self::Iso),
+            "unix" => Ok(Self::Unix),
+            other => Err(format!("Unknown date format: '{other}'. Valid formats: {}", Self::all_names())),
+        }
+    }
+}
+
+/// The language of human-readable timestamps.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DateLocale {
+    /// English, e.g. `Friday, 3rd August 2034, 12:45:34 PM UTC`.
+    #[default]
+    English,
+    /// Romanian, e.g. `vineri, 3 august 2034, 15:45:34 EEST`.
+    Romanian,
+    /// German, e.g. `Freitag, 3. August 2034, 14:45:34 CEST`.
+    German,
+    /// French, e.g. `vendredi 1er août 2034, 14:45:34 CEST`.
+    French,
+    /// Spanish, e.g. `viernes, 3 de agosto de 2034, 14:45:34 CEST`.
+    Spanish,
+    /// Italian, e.g. `venerdì 1º agosto 2034, 14:45:34 CEST`.
+    Italian,
+}
+
+impl DateLocale {
+    /// All variants of the enum, in declaration order.
+    pub const ALL: &[DateLocale] = &[Self::English, Self::Romanian, Self::German, Self::French, Self::Spanish, Self::Italian];
+
+    /// Returns a comma-separated list of all supported language tags.
+    pub fn all_names() -> String {
+        Self::ALL.iter().map(|locale| locale.to_string()).collect::<Vec<_>>().join(", ")
+    }
+
+    /// Returns the localised names of the weekdays, starting with Monday.
+    fn weekdays(self) -> [&'static str; 7] {
+        match self {
+            Self::English => ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"],
+            Self::Romanian => ["luni", "marți", "miercuri", "joi", "vineri", "sâmbătă", "duminică"],
+            Self::German => ["Montag", "Dienstag", "Mittwoch", "Donnerstag", "Freitag", "Samstag", "Sonntag"],
+            Self::French => ["lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche"],
+            Self::Spanish => ["lunes", "martes", "miércoles", "jueves", "viernes", "sábado", "domingo"],
+            Self::Italian => ["lunedì", "martedì", "mercoledì", "giovedì", "venerdì", "sabato", "domenica"],
+        }
+    }
+
+    /// Returns the localised names of the months, starting with January.
+    fn months(self) -> [&'static str; 12] {
+        match self {
+            Self::English => [
+                "January", "February", "March", "April", "May", "June", "July", "August", "September", "October", "November", "December",
+            ],
+            Self::Romanian => [
+                "ianuarie", "februarie", "martie", "aprilie", "mai", "iunie", "iulie", "august", "septembrie", "octombrie", "noiembrie", "decembrie",
+            ],
+            Self::German => [
+                "Januar", "Februar", "März", "April", "Mai", "Juni", "Juli", "August", "September", "Oktober", "November", "Dezember",
+            ],
+            Self::French => [
+                "janvier", "février", "mars", "avril", "mai", "juin", "juillet", "août", "septembre", "octobre", "novembre", "décembre",
+            ],
+            Self::Spanish => [
+                "enero", "febrero", "marzo", "abril", "mayo", "junio", "julio", "agosto", "septiembre", "octubre", "noviembre", "diciembre",
+            ],
+            Self::Italian => [
+                "gennaio", "febbraio", "marzo", "aprile", "maggio", "giugno", "luglio", "agosto", "settembre", "ottobre", "novembre", "dicembre",
+            ],
+        }
+    }
+
+    /// Returns the day of the month as this language writes it in a date.
+    ///
+    /// English appends `st`, `nd`, `rd`, or `th`; German appends a full stop;
+    /// French and Italian mark only the first of the month (`1er`, `1º`);
+    /// Romanian and Spanish use the plain cardinal number.
+    fn ordinal_day(self, day: u32) -> String {
+        match self {
+            Self::English => format!("{day}{}", ordinal_suffix(day)),
+            Self::German => format!("{day}."),
+            Self::French if day == 1 => "1er".to_owned(),
+            Self::Italian if day == 1 => "1º".to_owned(),
+            Self::Romanian | Self::French | Self::Spanish | Self::Italian => day.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for DateLocale {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tag = match self {
+            Self::English => "en",
+            Self::Romanian => "ro",
+            Self::German => "de",
+            Self::French => "fr",
+            Self::Spanish => "es",
+            Self::Italian => "it",
+        };
+        formatter.write_str(tag)
+    }
+}
+
+impl FromStr for DateLocale {
+    type Err = String;
+
+    /// Parses a BCP 47 language tag such as `ro-RO`, `ro_RO`, or `ro`; only
+    /// the primary language subtag is significant.
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let language = string.split(['-', '_']).next().unwrap_or_default();
+
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|locale| language.eq_ignore_ascii_case(&locale.to_string()))
+            .ok_or_else(|| format!("Unsupported locale: '{string}'. Supported languages: {}", Self::all_names()))
+    }
+}
+
+/// Parses an IANA time zone name such as `Europe/Bucharest`.
+///
+/// # Errors
+///
+/// Returns a description of the problem if the zone is unknown.
+pub fn parse_time_zone(name: &str) -> Result<Tz, String> {
+    name.parse::<Tz>()
+        .map_err(|_| format!("Unknown time zone: '{name}'. Expected an IANA name such as 'Europe/Bucharest'"))
+}
+
+/// The per-request settings deciding how timestamps are rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateDisplay {
+    /// The serialisation format.
+    pub format: DateFormat,
+    /// The time zone human-readable and ISO timestamps are shown in.
+    pub time_zone: Tz,
+    /// The language of human-readable timestamps.
+    pub locale: DateLocale,
+}
+
+impl Default for DateDisplay {
+    fn default() -> Self {
+        Self {
+            format: DateFormat::default(),
+            time_zone: Tz::UTC,
+            locale: DateLocale::default(),
+        }
+    }
+}
+
+impl DateDisplay {
+    /// The settings used for timestamps that are stored rather than sent:
+    /// RFC 3339 in UTC, which deserialises back to the same instant.
+    pub const STORED: DateDisplay = DateDisplay {
+        format: DateFormat::Iso,
+        time_zone: Tz::UTC,
+        locale: DateLocale::English,
+    };
+
+    /// Returns the settings selected for the current task, or the defaults
+    /// outside of a [`scope`](Self::scope).
+    pub fn current() -> DateDisplay {
+        CURRENT_DATE_DISPLAY.try_with(|display| *display).unwrap_or_default()
+    }
+
+    /// Runs `future` with these settings applied to every
+    /// [`FormattedDateTime`] it serialises.
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT_DATE_DISPLAY.scope(self, future).await
+    }
+
+    /// Like [`scope`](Self::scope), but also reports whether any
+    /// [`FormattedDateTime`] was serialised in human-readable form, i.e. in
+    /// the settings' language.
+    pub async fn scope_tracked<F: Future>(self, future: F) -> (F::Output, bool) {
+        HUMAN_DATES_RENDERED
+            .scope(Cell::new(false), async {
+                let output = self.scope(future).await;
+                (output, HUMAN_DATES_RENDERED.with(Cell::get))
+            })
+            .await
+    }
+
+    /// Runs `f` with these settings applied to every [`FormattedDateTime`]
+    /// it serialises.
+    pub fn sync_scope<R>(self, f: impl FnOnce() -> R) -> R {
+        CURRENT_DATE_DISPLAY.sync_scope(self, f)
+    }
+}
+
+/// A UTC timestamp that serialises according to the [`DateDisplay`] selected
+/// for the current request (human-readable English in UTC by default).
 ///
 /// Implements [`Deref`] to [`DateTime<Utc>`] for transparent access to all
 /// chrono methods, and [`From<DateTime<Utc>>`] for convenient construction.
+/// Deserialises from any of the [`DateFormat`] representations.
 #[derive(Debug, Clone)]
 pub struct FormattedDateTime(DateTime<Utc>);
 
@@ -28,24 +278,88 @@ impl Deref for FormattedDateTime {
     }
 }
 
+impl FormattedDateTime {
+    /// Formats the timestamp as a human-readable string in the given zone
+    /// and language.
+    ///
+    /// English keeps the 12-hour clock; every other language uses the
+    /// 24-hour clock.
+    fn to_human(&self, time_zone: Tz, locale: DateLocale) -> String {
+        let local = self.with_timezone(&time_zone);
+        let weekday = locale.weekdays()[local.weekday().num_days_from_monday() as usize];
+        let month = locale.months()[local.month0() as usize];
+        let day = locale.ordinal_day(local.day());
+        let year = local.year();
+        let zone = local.format("%Z");
+        let time = format!("{:02}:{:02}:{:02}", local.hour(), local.minute(), local.second());
+
+        match locale {
+            DateLocale::English => format!("{weekday}, {day} {month} {year}, {} {zone}", local.format("%I:%M:%S %p")),
+            DateLocale::Romanian | DateLocale::German => format!("{weekday}, {day} {month} {year}, {time} {zone}"),
+            DateLocale::French | DateLocale::Italian => format!("{weekday} {day} {month} {year}, {time} {zone}"),
+            DateLocale::Spanish => format!("{weekday}, {day} de {month} de {year}, {time} {zone}"),
+        }
+    }
+
+    /// Parses an RFC 3339 or human-readable timestamp.
+    fn parse(value: &str) -> Option<Self> {
+        if let Ok(date_time) = DateTime::parse_from_rfc3339(value) {
+            return Some(Self(date_time.with_timezone(&Utc)));
+        }
+
+        NaiveDateTime::parse_from_str(&strip_ordinal_suffix(value), HUMAN_PARSE_FORMAT)
+            .ok()
+            .map(|naive| Self(naive.and_utc()))
+    }
+}
+
 impl Serialize for FormattedDateTime {
-    /// Serialises the timestamp as a human-readable string.
+    /// Serialises the timestamp with the current [`DateDisplay`].
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-        let day = self.day();
+        let display = DateDisplay::current();
 
-        let formatted = format!(
-            "{weekday}, {day}{suffix} {month} {year}, {hour}:{minute:02}:{second:02} {ampm} UTC",
-            weekday = self.format("%A"),
-            suffix = ordinal_suffix(day),
-            month = self.format("%B"),
-            year = self.year(),
-            hour = self.format("%I"),
-            minute = self.minute(),
-            second = self.second(),
-            ampm = self.format("%p"),
-        );
+        match display.format {
+            DateFormat::Human => {
+                let _ = HUMAN_DATES_RENDERED.try_with(|rendered| rendered.set(true));
+                serializer.serialize_str(&self.to_human(display.time_zone, display.locale))
+            },
+            DateFormat::Iso => serializer.serialize_str(&self.with_timezone(&display.time_zone).to_rfc3339_opts(SecondsFormat::AutoSi, true)),
+            DateFormat::Unix => serializer.serialize_i64(self.timestamp()),
+        }
+    }
+}
 
-        serializer.serialize_str(&formatted)
+impl<'de> Deserialize<'de> for FormattedDateTime {
+    /// Deserialises an RFC 3339 string, an English human-readable string in
+    /// UTC, or an integer number of seconds since the Unix epoch.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(FormattedDateTimeVisitor)
+    }
+}
+
+/// Visitor accepting every [`DateFormat`] representation.
+struct FormattedDateTimeVisitor;
+
+impl Visitor<'_> for FormattedDateTimeVisitor {
+    type Value = FormattedDateTime;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("an RFC 3339 timestamp, a human-readable timestamp, or Unix seconds")
+    }
+
+    fn visit_i64<E: de::Error>(self, seconds: i64) -> Result<Self::Value, E> {
+        DateTime::from_timestamp(seconds, 0)
+            .map(FormattedDateTime)
+            .ok_or_else(|| E::custom(format!("Unix timestamp {seconds} is out of range")))
+    }
+
+    fn visit_u64<E: de::Error>(self, seconds: u64) -> Result<Self::Value, E> {
+        let seconds = i64::try_from(seconds).map_err(|_| E::custom(format!("Unix timestamp {seconds} is out of range")))?;
+        self.visit_i64(seconds)
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        FormattedDateTime::parse(value).ok_or_else(|| E::custom(format!("Invalid timestamp: '{value}'")))
     }
 }
 
@@ -62,3 +376,22 @@ fn ordinal_suffix(day: u32) -> &'static str {
         _ => "th",
     }
 }
+
+/// Removes the ordinal suffix following the day number in a human-readable
+/// timestamp (e.g. `3rd` becomes `3`).
+fn strip_ordinal_suffix(value: &str) -> String {
+    value
+        .split(' ')
+        .map(|word| {
+            let digits = word.trim_end_matches(char::is_alphabetic);
+            let suffix = &word[digits.len()..];
+
+            if !digits.is_empty() && digits.chars().all(|character| character.is_ascii_digit()) && ["st", "nd", "rd", "th"].contains(&suffix) {
+                digits
+            } else {
+                word
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
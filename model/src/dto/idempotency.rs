@@ -0,0 +1,49 @@
+//! Types supporting `Idempotency-Key` handling for create requests.
+
+use chrono::{DateTime, Utc};
+
+use crate::entity::idempotency_key;
+
+/// A new idempotency record to be stored alongside the response it guards.
+#[derive(Debug, Clone)]
+pub struct IdempotencyRecord {
+    /// The client-supplied `Idempotency-Key` header value.
+    pub key: String,
+    /// Hex-encoded digest of the request body.
+    pub fingerprint: String,
+    /// HTTP status code of the response to store.
+    pub status_code: u16,
+    /// Timestamp after which the key may be reused.
+    pub expires_at: DateTime<Utc>,
+}
+
+/// A response previously stored under an idempotency key.
+#[derive(Debug, Clone)]
+pub struct StoredResponse {
+    /// Hex-encoded digest of the request that first used the key.
+    pub fingerprint: String,
+    /// HTTP status code of the original response.
+    pub status_code: u16,
+    /// JSON body of the original response, with timestamps serialised with
+    /// [`DateDisplay::STORED`](crate::dto::datetime::DateDisplay::STORED).
+    pub body: String,
+}
+
+impl From<idempotency_key::Model> for StoredResponse {
+    fn from(model: idempotency_key::Model) -> Self {
+        Self {
+            fingerprint: model.fingerprint,
+            status_code: u16::try_from(model.status_code).unwrap_or_default(),
+            body: model.response_body,
+        }
+    }
+}
+
+/// The outcome of an idempotent create operation.
+#[derive(Debug, Clone)]
+pub enum IdempotentOutcome<T> {
+    /// The request was processed for the first time.
+    Created(T),
+    /// The key had already been used; the original response is returned.
+    Replayed(StoredResponse),
+}
@@ -1,10 +1,23 @@
 //! Data Transfer Objects for request, response, and pagination payloads.
 //!
 //! * [`datetime`] – [`FormattedDateTime`](datetime::FormattedDateTime), a
-//!   UTC timestamp newtype with human-readable serialisation.
+//!   UTC timestamp newtype serialised with a per-request
+//!   [`DateDisplay`](datetime::DateDisplay) (format, time zone, and locale).
+//! * [`idempotency`] – Records and outcomes for `Idempotency-Key` handling.
 //! * [`note`] – Request and response DTOs for note operations.
 //! * [`pagination`] – Generic pagination request and response types.
+//! * [`property`] – Custom note properties and the DTOs of their schemas.
+//! * [`reminder`] – Request, response, and event DTOs for note reminders.
+//! * [`saved_search`] – Request and response DTOs for saved searches.
+//! * [`task`] – Request and response DTOs for the task list items of notes.
+//! * [`template`] – Request and response DTOs for note templates.
 
 pub mod datetime;
+pub mod idempotency;
 pub mod note;
 pub mod pagination;
+pub mod property;
+pub mod reminder;
+pub mod saved_search;
+pub mod task;
+pub mod template;
@@ -1,7 +1,10 @@
 //! Note-specific request and response DTOs.
 
+use std::fmt;
+
 use crate::dto::datetime::FormattedDateTime;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
 /// Request body for creating a new note.
 #[derive(Debug, Clone, Deserialize)]
@@ -10,6 +13,9 @@ pub struct CreateNoteRequest {
     pub title: String,
     /// The main body content of the note.
     pub content: String,
+    /// Custom properties of the note, e.g. `{"status": "open"}`.
+    #[serde(default)]
+    pub properties: Map<String, Value>,
 }
 
 /// Request body for partially updating an existing note.
@@ -22,10 +28,42 @@ pub struct UpdateNoteRequest {
     pub title: Option<String>,
     /// An optional new body content for the note.
     pub content: Option<String>,
+    /// Optional new custom properties, replacing all existing ones.
+    pub properties: Option<Map<String, Value>>,
+}
+
+/// Request body for merging notes into a target note.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct MergeNotesRequest {
+    /// The note that receives the merged content.
+    pub target_id: i64,
+    /// The notes whose content is appended to the target, in order.
+    pub source_ids: Vec<i64>,
+    /// The text inserted before each appended note (default a blank line).
+    pub separator: Option<String>,
+    /// What happens to the source notes afterwards (default `archive`).
+    #[serde(default)]
+    pub source_action: SourceAction,
+}
+
+/// What happens to the source notes of a merge.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceAction {
+    /// The sources are archived, hiding them from default listings whilst
+    /// keeping them recoverable.
+    #[default]
+    Archive,
+    /// The sources are deleted.
+    Delete,
 }
 
 /// Serialisable representation of a note returned to the client.
-#[derive(Debug, Clone, Serialize)]
+///
+/// Also deserialisable, so that a response stored for an idempotent replay
+/// can be rendered again for the replaying request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct NoteResponse {
     /// The unique identifier of the note.
@@ -40,4 +78,85 @@ pub struct NoteResponse {
     /// The timestamp at which the note was last updated (UTC),
     /// formatted as e.g. `Friday, 3rd August 2034, 12:45:34 PM UTC`.
     pub updated_at: FormattedDateTime,
+    /// Whether the note is pinned.
+    pub pinned: bool,
+    /// Whether the note is starred.
+    pub starred: bool,
+    /// Whether the note is archived and therefore hidden from default
+    /// listings.
+    pub archived: bool,
+    /// When the next reminder for the note is due; absent if none is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remind_at: Option<FormattedDateTime>,
+    /// The recurrence rule of the reminder, e.g. `FREQ=WEEKLY;BYDAY=MO`;
+    /// absent for one-off reminders.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recurrence: Option<String>,
+    /// The custom properties of the note; absent if it has none.
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub properties: Map<String, Value>,
+    /// Marked fragments explaining why the note matched a search; only
+    /// present in listings that request highlighting.
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub highlights: Option<NoteHighlights>,
+}
+
+/// A boolean state of a note that is switched on and off through its own
+/// endpoint, e.g. `PUT /api/notes/7/pinned`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NoteFlag {
+    /// The note is listed first when `pinnedFirst` is requested.
+    Pinned,
+    /// The note is marked as a favourite.
+    Starred,
+    /// The note is hidden from listings unless requested.
+    Archived,
+}
+
+impl fmt::Display for NoteFlag {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Pinned => "pinned",
+            Self::Starred => "starred",
+            Self::Archived => "archived",
+        };
+        formatter.write_str(name)
+    }
+}
+
+/// Query parameters for title autocompletion.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SuggestQuery {
+    /// The text a title, or one of its words, must start with. Required.
+    pub prefix: Option<String>,
+    /// The maximum number of suggestions, as a raw query-string value.
+    pub limit: Option<String>,
+    /// Validated suggestion count, populated by the service layer. Not
+    /// deserialised from the query string.
+    #[serde(skip)]
+    pub parsed_limit: u64,
+}
+
+/// A title suggested for autocompletion.
+#[derive(Debug, Clone, Serialize)]
+pub struct NoteSuggestion {
+    /// The unique identifier of the note.
+    pub id: i64,
+    /// The title of the note.
+    pub title: String,
+}
+
+/// Fragments of a listed note with the text filter matches marked.
+///
+/// The note text is HTML-escaped, so the markers are the only markup and the
+/// fragments can be rendered as HTML as they are.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct NoteHighlights {
+    /// The whole title with every match marked, or nothing if the title did
+    /// not match.
+    pub title: Vec<String>,
+    /// Fragments of the content around its matches, in document order.
+    pub content: Vec<String>,
 }
@@ -1,9 +1,19 @@
 //! Pagination and sorting primitives used across list endpoints.
 
+use std::collections::BTreeMap;
 use std::fmt;
 use std::str::FromStr;
 
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use serde::{
+    Deserialize, Deserializer, Serialize,
+    de::{self, MapAccess, Visitor},
+};
+
+use crate::dto::{
+    note::NoteFlag,
+    property::{PROPERTY_PREFIX, PropertyValue, is_property_key},
+};
 
 /// The direction to sort results in.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -15,7 +25,7 @@ pub enum SortDirection {
 }
 
 /// The field to sort notes by.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SortFieldName {
     /// Sort by note ID.
     Id,
@@ -27,16 +37,37 @@ pub enum SortFieldName {
     CreatedAt,
     /// Sort by last-updated timestamp.
     UpdatedAt,
+    /// Sort by the pinned flag; descending lists pinned notes first.
+    Pinned,
+    /// Sort by the starred flag.
+    Starred,
+    /// Sort by the archived flag.
+    Archived,
+    /// Sort by a custom property, e.g. `prop.priority`; notes without it
+    /// sort as `null`.
+    Property(String),
 }
 
 impl SortFieldName {
     /// All variants of the enum, in declaration order.
-    pub const ALL: &[SortFieldName] = &[Self::Id, Self::Title, Self::Content, Self::CreatedAt, Self::UpdatedAt];
+    pub const ALL: &[SortFieldName] = &[
+        Self::Id,
+        Self::Title,
+        Self::Content,
+        Self::CreatedAt,
+        Self::UpdatedAt,
+        Self::Pinned,
+        Self::Starred,
+        Self::Archived,
+    ];
 
     /// Returns a comma-separated list of all valid field names (e.g.
-    /// `"id, title, content, createdAt, updatedAt"`).
+    /// `"id, title, content, createdAt, updatedAt, pinned, starred,
+    /// archived, prop.<key>"`).
     pub fn all_names() -> String {
-        Self::ALL.iter().map(|field| field.to_string()).collect::<Vec<_>>().join(", ")
+        let mut names: Vec<String> = Self::ALL.iter().map(|field| field.to_string()).collect();
+        names.push(format!("{PROPERTY_PREFIX}<key>"));
+        names.join(", ")
     }
 }
 
@@ -48,6 +79,10 @@ impl fmt::Display for SortFieldName {
             Self::Content => "content",
             Self::CreatedAt => "createdAt",
             Self::UpdatedAt => "updatedAt",
+            Self::Pinned => "pinned",
+            Self::Starred => "starred",
+            Self::Archived => "archived",
+            Self::Property(key) => return write!(formatter, "{PROPERTY_PREFIX}{key}"),
         };
         formatter.write_str(name)
     }
@@ -63,13 +98,20 @@ impl FromStr for SortFieldName {
             "content" => Ok(Self::Content),
             "createdAt" => Ok(Self::CreatedAt),
             "updatedAt" => Ok(Self::UpdatedAt),
-            other => Err(format!("Unknown 'orderBy' field: '{other}'. Valid fields: {}", Self::all_names())),
+            "pinned" => Ok(Self::Pinned),
+            "starred" => Ok(Self::Starred),
+            "archived" => Ok(Self::Archived),
+            other => other
+                .strip_prefix(PROPERTY_PREFIX)
+                .filter(|key| is_property_key(key))
+                .map(|key| Self::Property(key.to_owned()))
+                .ok_or_else(|| format!("Unknown 'orderBy' field: '{other}'. Valid fields: {}", Self::all_names())),
         }
     }
 }
 
 /// A single parsed sort criterion, combining a field name with a direction.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct SortField {
     /// The field to sort by.
     pub name: SortFieldName,
@@ -85,72 +127,352 @@ pub struct QueryParamInfo {
     pub kind: &'static str,
 }
 
-/// Query parameters for paginated, optionally filtered, and sorted searches.
-#[derive(Debug, Clone, Default, Deserialize)]
-#[serde(deny_unknown_fields)]
-pub struct SearchParams {
+/// A note text column that can be matched by a filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextField {
+    /// The note title.
+    Title,
+    /// The note content.
+    Content,
+}
+
+/// How a [`TextFilter`] matches its column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextMatch {
+    /// The column contains the pattern anywhere.
+    Contains,
+    /// The column starts with the pattern.
+    Prefix,
+    /// The column equals the pattern.
+    Exact,
+}
+
+/// A single validated text filter.
+#[derive(Debug, Clone)]
+pub struct TextFilter {
+    /// The column to match.
+    pub field: TextField,
+    /// The literal text to look for; never interpreted as a wildcard.
+    pub pattern: String,
+    /// How the pattern is matched.
+    pub matching: TextMatch,
+    /// Whether notes that match are excluded instead of included.
+    pub negated: bool,
+}
+
+/// A comparison against an ordered column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    /// Equal to the value.
+    Equal,
+    /// Strictly greater than the value.
+    Greater,
+    /// Greater than or equal to the value.
+    GreaterOrEqual,
+    /// Strictly less than the value.
+    Less,
+    /// Less than or equal to the value.
+    LessOrEqual,
+}
+
+/// A single validated comparison against a custom property, e.g.
+/// `prop.priority>=2`. Notes without the property, or with a value of
+/// another type, never match.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertyFilter {
+    /// The property key.
+    pub key: String,
+    /// How the property is compared with the value.
+    pub comparison: Comparison,
+    /// The value as written in the query string.
+    pub text: String,
+    /// The typed value, following the key's schema if it has one.
+    pub value: PropertyValue,
+}
+
+/// A note timestamp column that can be compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampField {
+    /// The creation timestamp.
+    CreatedAt,
+    /// The last-updated timestamp.
+    UpdatedAt,
+}
+
+/// A validated boolean filter expression, built by the service layer from a
+/// parsed [`query::Expr`](crate::query::Expr).
+#[derive(Debug, Clone)]
+pub enum FilterExpr {
+    /// Every operand must match.
+    All(Vec<FilterExpr>),
+    /// At least one operand must match.
+    Any(Vec<FilterExpr>),
+    /// The operand must not match.
+    Not(Box<FilterExpr>),
+    /// A text match; its `negated` flag is always `false`.
+    Text(TextFilter),
+    /// A comparison against the note ID.
+    Id(Comparison, i64),
+    /// A comparison against a timestamp.
+    Timestamp(TimestampField, Comparison, DateTime<Utc>),
+    /// A required value of a flag.
+    Flag(NoteFlag, bool),
+}
+
+impl FilterExpr {
+    /// Returns whether the expression tests `flag` anywhere.
+    pub fn mentions(&self, flag: NoteFlag) -> bool {
+        match self {
+            Self::All(operands) | Self::Any(operands) => operands.iter().any(|operand| operand.mentions(flag)),
+            Self::Not(operand) => operand.mentions(flag),
+            Self::Flag(mentioned, _) => *mentioned == flag,
+            Self::Text(_) | Self::Id(..) | Self::Timestamp(..) => false,
+        }
+    }
+}
+
+/// Validated filters, populated by the service layer from the raw
+/// [`SearchParams`] fields. All filters must hold for a note to be listed.
+#[derive(Debug, Clone, Default)]
+pub struct NoteFilters {
+    /// Text filters on the title and content.
+    pub text: Vec<TextFilter>,
+    /// Whether text filters distinguish upper from lower case.
+    pub case_sensitive: bool,
+    /// Note IDs to restrict results to; empty means no restriction.
+    pub ids: Vec<i64>,
+    /// Required flag values; by default, archived notes are excluded.
+    pub flags: Vec<(NoteFlag, bool)>,
+    /// Exclusive lower bound on the creation timestamp.
+    pub created_after: Option<DateTime<Utc>>,
+    /// Exclusive upper bound on the creation timestamp.
+    pub created_before: Option<DateTime<Utc>>,
+    /// Exclusive lower bound on the last-updated timestamp.
+    pub updated_after: Option<DateTime<Utc>>,
+    /// Exclusive upper bound on the last-updated timestamp.
+    pub updated_before: Option<DateTime<Utc>>,
+    /// Comparisons against custom properties.
+    pub properties: Vec<PropertyFilter>,
+    /// The boolean `filter` expression, if any.
+    pub expression: Option<FilterExpr>,
+    /// A title to rank notes by trigram similarity to, replacing the `title`
+    /// substring filter when `fuzzy` is requested. Matching is always
+    /// case-insensitive.
+    pub fuzzy_title: Option<String>,
+}
+
+/// How matches are marked in search results, populated by the service layer
+/// when highlighting is requested.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HighlightOptions {
+    /// The approximate length of a content fragment, in characters.
+    pub fragment_size: usize,
+    /// The marker inserted before each match.
+    pub pre_tag: String,
+    /// The marker inserted after each match.
+    pub post_tag: String,
+}
+
+impl Default for HighlightOptions {
+    fn default() -> Self {
+        Self {
+            fragment_size: 150,
+            pre_tag: "<mark>".into(),
+            post_tag: "</mark>".into(),
+        }
+    }
+}
+
+/// The query-string key of the sort parameter, whose description in
+/// [`SearchParams::params_hint`] embeds the valid sort field names.
+const ORDER_BY_PARAM: &str = "orderBy";
+
+/// Declares [`SearchParams`] together with [`SearchParams::QUERY_PARAMS`]
+/// and its deserialiser, so that every raw query-string field is described
+/// exactly once and the parameter hint can never fall out of sync with what
+/// is accepted.
+macro_rules! search_params {
+    ($(
+        $(#[doc = $doc:literal])*
+        $field:ident: $name:literal => $kind:literal,
+    )*) => {
+        /// Query parameters for paginated, optionally filtered, and sorted
+        /// searches.
+        ///
+        /// Every raw field holds the query-string value verbatim; the service
+        /// layer validates them and populates the parsed fields. Only the raw
+        /// fields are serialised, which is how saved searches are stored.
+        /// Parameters other than the listed ones and `prop.*` property
+        /// filters are rejected.
+        #[derive(Debug, Clone, Default, Serialize)]
+        pub struct SearchParams {
+            $(
+                $(#[doc = $doc])*
+                #[serde(rename = $name, skip_serializing_if = "Option::is_none")]
+                pub $field: Option<String>,
+            )*
+            /// Raw property filters keyed by their query-string key, e.g.
+            /// `prop.status` → `open` for `prop.status=open`, or
+            /// `prop.priority>` → `2` for `prop.priority>=2`.
+            #[serde(flatten)]
+            pub properties: BTreeMap<String, String>,
+            /// Validated page number, populated by the service layer. Not
+            /// deserialised from the query string.
+            #[serde(skip)]
+            pub parsed_page: u64,
+            /// Validated page size, populated by the service layer. Not
+            /// deserialised from the query string.
+            #[serde(skip)]
+            pub parsed_size: u64,
+            /// Parsed sort fields, populated by the service layer after
+            /// validating [`order_by`](Self::order_by). Not deserialised from
+            /// the query string.
+            #[serde(skip)]
+            pub sort_fields: Vec<SortField>,
+            /// Validated filters, populated by the service layer. Not
+            /// deserialised from the query string.
+            #[serde(skip)]
+            pub filters: NoteFilters,
+            /// Validated highlighting options, populated by the service layer
+            /// when [`highlight`](Self::highlight) is `true`. Not deserialised
+            /// from the query string.
+            #[serde(skip)]
+            pub highlight_options: Option<HighlightOptions>,
+        }
+
+        impl SearchParams {
+            /// Metadata for every supported query parameter, in declaration
+            /// order.
+            ///
+            /// Use [`params_hint`](Self::params_hint) to obtain the full
+            /// human-readable string.
+            pub const QUERY_PARAMS: &'static [QueryParamInfo] = &[$(QueryParamInfo { name: $name, kind: $kind }),*];
+        }
+
+        impl<'de> Deserialize<'de> for SearchParams {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                /// Collects the raw fields and the `prop.*` parameters.
+                struct SearchParamsVisitor;
+
+                impl<'de> Visitor<'de> for SearchParamsVisitor {
+                    type Value = SearchParams;
+
+                    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                        formatter.write_str("search parameters")
+                    }
+
+                    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<SearchParams, A::Error> {
+                        const FIELDS: &[&str] = &[$($name),*];
+                        let mut params = SearchParams::default();
+
+                        while let Some(key) = map.next_key::<String>()? {
+                            match key.as_str() {
+                                $(
+                                    $name => {
+                                        if params.$field.is_some() {
+                                            return Err(de::Error::duplicate_field($name));
+                                        }
+                                        params.$field = map.next_value()?;
+                                    },
+                                )*
+                                _ if key.starts_with(PROPERTY_PREFIX) => {
+                                    let value = map.next_value()?;
+                                    if params.properties.insert(key.clone(), value).is_some() {
+                                        return Err(de::Error::custom(format_args!("duplicate parameter `{key}`")));
+                                    }
+                                },
+                                _ => return Err(de::Error::unknown_field(&key, FIELDS)),
+                            }
+                        }
+
+                        Ok(params)
+                    }
+                }
+
+                deserializer.deserialize_map(SearchParamsVisitor)
+            }
+        }
+    };
+}
+
+search_params! {
     /// An optional title substring to filter results by.
-    pub title: Option<String>,
+    title: "title" => "string",
+    /// Whether `title` matches by trigram similarity instead of as a
+    /// substring, tolerating typos and listing the closest titles first
+    /// (`true` or `false`, default `false`).
+    fuzzy: "fuzzy" => "boolean",
     /// An optional content substring to filter results by.
-    pub content: Option<String>,
+    content: "content" => "string",
+    /// An optional title substring excluding matching notes.
+    not_title: "-title" => "string",
+    /// An optional content substring excluding matching notes.
+    not_content: "-content" => "string",
+    /// An optional prefix the title must start with.
+    title_prefix: "titlePrefix" => "string",
+    /// Whether text filters are case-sensitive (`true` or `false`, default
+    /// `false`).
+    case_sensitive: "caseSensitive" => "boolean",
+    /// Comma-separated note IDs to restrict results to, e.g. `1,2,3`.
+    ids: "ids" => "comma-separated positive integers",
+    /// Only pinned (`true`) or unpinned (`false`) notes.
+    pinned: "pinned" => "boolean",
+    /// Only starred (`true`) or unstarred (`false`) notes.
+    starred: "starred" => "boolean",
+    /// Only archived (`true`), unarchived (`false`), or `any` notes.
+    ///
+    /// Archived notes are hidden by default, unless the `filter` expression
+    /// tests `archived` itself.
+    archived: "archived" => "true, false, or any",
+    /// Only notes created strictly after this instant.
+    created_after: "createdAfter" => "RFC 3339 timestamp or date",
+    /// Only notes created strictly before this instant.
+    created_before: "createdBefore" => "RFC 3339 timestamp or date",
+    /// Only notes last updated strictly after this instant.
+    updated_after: "updatedAfter" => "RFC 3339 timestamp or date",
+    /// Only notes last updated strictly before this instant.
+    updated_before: "updatedBefore" => "RFC 3339 timestamp or date",
+    /// A boolean filter expression, e.g.
+    /// `title:~"release" AND (created>2025-01-01 OR id:7)`; see
+    /// [`query`](crate::query) for the syntax.
+    filter: "filter" => "filter expression",
+    /// Whether each listed note carries `highlights` marking its text filter
+    /// matches (`true` or `false`, default `false`).
+    highlight: "highlight" => "boolean",
+    /// The approximate length of a highlighted content fragment, in
+    /// characters (default 150).
+    fragment_size: "fragmentSize" => "positive integer",
+    /// The marker inserted before each highlighted match (default `<mark>`).
+    pre_tag: "preTag" => "string",
+    /// The marker inserted after each highlighted match (default `</mark>`).
+    post_tag: "postTag" => "string",
     /// The one-based page number to retrieve, as a raw query-string value.
-    pub page: Option<String>,
+    page: "page" => "positive integer",
     /// The maximum number of items per page, as a raw query-string value.
-    pub size: Option<String>,
+    size: "size" => "positive integer",
     /// Comma-separated sort fields with an optional `+` (ascending) or `-`
     /// (descending) prefix, e.g. `title,-createdAt`.
     ///
     /// Defaults to ascending when no prefix is supplied. Unknown field names
     /// are rejected by the service layer with a validation error.
-    #[serde(rename = "orderBy")]
-    pub order_by: Option<String>,
-    /// Validated page number, populated by the service layer. Not
-    /// deserialised from the query string.
-    #[serde(skip)]
-    pub parsed_page: u64,
-    /// Validated page size, populated by the service layer. Not
-    /// deserialised from the query string.
-    #[serde(skip)]
-    pub parsed_size: u64,
-    /// Parsed sort fields, populated by the service layer after validating
-    /// [`order_by`](Self::order_by). Not deserialised from the query string.
-    #[serde(skip)]
-    pub sort_fields: Vec<SortField>,
+    order_by: "orderBy" => "comma-separated fields",
+    /// Whether pinned notes are listed before all others, ahead of `orderBy`
+    /// (`true` or `false`, default `false`).
+    pinned_first: "pinnedFirst" => "boolean",
 }
 
 impl SearchParams {
-    /// Metadata for every supported query parameter except `orderBy`, whose
-    /// description is dynamic (it embeds the set of valid sort fields).
-    ///
-    /// Use [`params_hint`](Self::params_hint) to obtain the full
-    /// human-readable string.
-    pub const QUERY_PARAMS: &'static [QueryParamInfo] = &[
-        QueryParamInfo {
-            name: "title",
-            kind: "string",
-        },
-        QueryParamInfo {
-            name: "content",
-            kind: "string",
-        },
-        QueryParamInfo {
-            name: "page",
-            kind: "positive integer",
-        },
-        QueryParamInfo {
-            name: "size",
-            kind: "positive integer",
-        },
-    ];
-
     /// Returns a human-readable description of every accepted query parameter,
     /// including the valid sort field names for `orderBy`.
     pub fn params_hint() -> String {
-        let mut parts: Vec<String> = Self::QUERY_PARAMS
+        let parts: Vec<String> = Self::QUERY_PARAMS
             .iter()
-            .map(|param| format!("{} ({})", param.name, param.kind))
+            .map(|param| match param.name {
+                ORDER_BY_PARAM => format!("{} ({}: {})", param.name, param.kind, SortFieldName::all_names()),
+                _ => format!("{} ({})", param.name, param.kind),
+            })
+            .chain([format!("{PROPERTY_PREFIX}<key> (property comparison with =, >, >=, <, or <=)")])
             .collect();
-        parts.push(format!("orderBy (comma-separated fields: {})", SortFieldName::all_names()));
         format!("Valid parameters: {}", parts.join(", "))
     }
 }
@@ -0,0 +1,145 @@
+//! Custom note properties and the DTOs of their schemas.
+//!
+//! A note's properties are a JSON object of string, number, or boolean
+//! values, e.g. `{"status": "open", "priority": 2}`. A property schema
+//! fixes the value type of one key for every note; keys without a schema
+//! accept any of the three types.
+
+use std::{fmt, str::FromStr};
+
+use crate::dto::datetime::FormattedDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The prefix of property filter parameters and sort fields, e.g.
+/// `prop.status=open` or `orderBy=-prop.priority`.
+pub const PROPERTY_PREFIX: &str = "prop.";
+
+/// Maximum length of a property key, in characters.
+pub const MAX_PROPERTY_KEY_LEN: usize = 64;
+
+/// The value type of a property.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PropertyType {
+    /// A JSON string.
+    String,
+    /// A JSON number.
+    Number,
+    /// `true` or `false`.
+    Boolean,
+}
+
+impl PropertyType {
+    /// Returns the type of a JSON value, or [`None`] for `null`, arrays, and
+    /// objects, which are not valid property values.
+    pub fn of(value: &Value) -> Option<Self> {
+        match value {
+            Value::String(_) => Some(Self::String),
+            Value::Number(_) => Some(Self::Number),
+            Value::Bool(_) => Some(Self::Boolean),
+            Value::Null | Value::Array(_) | Value::Object(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for PropertyType {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::String => "string",
+            Self::Number => "number",
+            Self::Boolean => "boolean",
+        };
+        formatter.write_str(name)
+    }
+}
+
+impl FromStr for PropertyType {
+    type Err = String;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        match string {
+            "string" => Ok(Self::String),
+            "number" => Ok(Self::Number),
+            "boolean" => Ok(Self::Boolean),
+            other => Err(format!("Unknown property type '{other}'. Valid types: string, number, boolean")),
+        }
+    }
+}
+
+/// A typed property value that notes are compared against.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyValue {
+    /// A string value.
+    String(String),
+    /// A numeric value.
+    Number(f64),
+    /// A boolean value.
+    Boolean(bool),
+}
+
+impl PropertyValue {
+    /// Reads `text` as a value of type `kind`, returning [`None`] if it is
+    /// not one.
+    pub fn parse_as(text: &str, kind: PropertyType) -> Option<Self> {
+        match kind {
+            PropertyType::String => Some(Self::String(text.to_owned())),
+            PropertyType::Number => text.parse().ok().filter(|number: &f64| number.is_finite()).map(Self::Number),
+            PropertyType::Boolean => text.parse().ok().map(Self::Boolean),
+        }
+    }
+
+    /// Reads `text` as a boolean or a number if it is one, and as a string
+    /// otherwise.
+    pub fn infer(text: &str) -> Self {
+        Self::parse_as(text, PropertyType::Boolean)
+            .or_else(|| Self::parse_as(text, PropertyType::Number))
+            .unwrap_or_else(|| Self::String(text.to_owned()))
+    }
+
+    /// Returns the type of the value.
+    pub fn kind(&self) -> PropertyType {
+        match self {
+            Self::String(_) => PropertyType::String,
+            Self::Number(_) => PropertyType::Number,
+            Self::Boolean(_) => PropertyType::Boolean,
+        }
+    }
+}
+
+/// Returns whether `key` is a valid property key: an ASCII letter or `_`
+/// followed by ASCII letters, digits, `_`, or `-`, at most
+/// [`MAX_PROPERTY_KEY_LEN`] characters long.
+pub fn is_property_key(key: &str) -> bool {
+    let mut characters = key.chars();
+
+    characters.next().is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && characters.all(|next| next.is_ascii_alphanumeric() || matches!(next, '_' | '-'))
+        && key.len() <= MAX_PROPERTY_KEY_LEN
+}
+
+/// Request body for declaring the value type of a property key.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreatePropertySchemaRequest {
+    /// The property key, e.g. `priority`.
+    pub key: String,
+    /// The value type every note must use for the key.
+    #[serde(rename = "type")]
+    pub kind: PropertyType,
+}
+
+/// Serialisable representation of a property schema returned to the
+/// client.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PropertySchemaResponse {
+    /// The unique identifier of the schema.
+    pub id: i64,
+    /// The property key.
+    pub key: String,
+    /// The value type of the property.
+    #[serde(rename = "type")]
+    pub kind: PropertyType,
+    /// The timestamp at which the schema was declared.
+    pub created_at: FormattedDateTime,
+}
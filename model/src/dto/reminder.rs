@@ -0,0 +1,70 @@
+//! Request, response, and event DTOs for note reminders.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::dto::datetime::FormattedDateTime;
+
+/// Request body for setting the reminder of a note.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SetReminderRequest {
+    /// When the reminder is first due, as RFC 3339, a human-readable UTC
+    /// timestamp, or Unix seconds.
+    pub remind_at: FormattedDateTime,
+    /// An optional recurrence rule, e.g. `FREQ=WEEKLY;BYDAY=MO,TH;COUNT=10`
+    /// (see [`Recurrence`](crate::rrule::Recurrence)). Omit it for a one-off
+    /// reminder.
+    pub recurrence: Option<String>,
+}
+
+/// Query parameters for listing upcoming reminders.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReminderQuery {
+    /// Only reminders due strictly before this timestamp are listed.
+    pub before: Option<String>,
+    /// The maximum number of reminders, as a raw query-string value.
+    pub limit: Option<String>,
+    /// Validated `before` bound, populated by the service layer. Not
+    /// deserialised from the query string.
+    #[serde(skip)]
+    pub parsed_before: Option<DateTime<Utc>>,
+    /// Validated reminder count, populated by the service layer. Not
+    /// deserialised from the query string.
+    #[serde(skip)]
+    pub parsed_limit: u64,
+}
+
+/// A pending reminder returned to the client, soonest first.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReminderResponse {
+    /// The identifier of the note the reminder belongs to.
+    pub note_id: i64,
+    /// The title of the note.
+    pub title: String,
+    /// When the reminder is next due.
+    pub remind_at: FormattedDateTime,
+    /// The recurrence rule of the reminder; absent for one-off reminders.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recurrence: Option<String>,
+}
+
+/// A reminder that has fired, as published to the log, the webhook, and
+/// the `GET /api/reminders/events` stream.
+///
+/// Timestamps are always RFC 3339, as the event is not tied to a request
+/// that could select another format.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReminderEvent {
+    /// The identifier of the note the reminder belongs to.
+    pub note_id: i64,
+    /// The title of the note.
+    pub title: String,
+    /// The occurrence that fell due.
+    pub remind_at: DateTime<Utc>,
+    /// The following occurrence, or [`None`] if the reminder has ended.
+    pub next_remind_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,37 @@
+//! Request and response DTOs for saved searches.
+
+use crate::dto::{datetime::FormattedDateTime, pagination::SearchParams};
+use serde::{Deserialize, Serialize};
+
+/// Request body for saving a named search.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateSavedSearchRequest {
+    /// The unique display name of the search.
+    pub name: String,
+    /// The raw search parameters, keyed exactly as in the `GET /api/notes`
+    /// query string, e.g. `{"filter": "title:~release", "size": "10"}`.
+    pub params: SearchParams,
+}
+
+/// Serialisable representation of a saved search returned to the client.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedSearchResponse {
+    /// The unique identifier of the saved search.
+    pub id: i64,
+    /// The unique display name of the search.
+    pub name: String,
+    /// The raw search parameters.
+    pub params: SearchParams,
+    /// The timestamp at which the search was saved.
+    pub created_at: FormattedDateTime,
+}
+
+/// Query parameters accepted when executing a saved search.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SavedSearchQuery {
+    /// The one-based page number, overriding any page stored with the
+    /// search.
+    pub page: Option<String>,
+}
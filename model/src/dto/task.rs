@@ -0,0 +1,75 @@
+//! Request and response DTOs for the task list items of notes.
+
+use serde::{Deserialize, Serialize};
+
+use crate::task::TaskItem;
+
+/// A task list item of a single note.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteTaskResponse {
+    /// The zero-based position of the item among the note's tasks, as used
+    /// in `PATCH /api/notes/{id}/tasks/{index}`.
+    pub index: usize,
+    /// The text after the checkbox.
+    pub text: String,
+    /// Whether the item is ticked.
+    pub done: bool,
+    /// The one-based line of the note content the item is on.
+    pub line: usize,
+}
+
+impl From<TaskItem> for NoteTaskResponse {
+    fn from(task: TaskItem) -> Self {
+        Self {
+            index: task.index,
+            text: task.text,
+            done: task.done,
+            line: task.line,
+        }
+    }
+}
+
+/// Request body for ticking or clearing a task list item.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateTaskRequest {
+    /// The new state of the item; omit it to toggle the current state.
+    pub done: Option<bool>,
+}
+
+/// Query parameters for listing task list items across notes.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TaskQuery {
+    /// Only list ticked (`true`) or open (`false`) items; both if absent.
+    pub done: Option<String>,
+    /// The maximum number of items, as a raw query-string value.
+    pub limit: Option<String>,
+    /// Validated `done` filter, populated by the service layer. Not
+    /// deserialised from the query string.
+    #[serde(skip)]
+    pub parsed_done: Option<bool>,
+    /// Validated item count, populated by the service layer. Not
+    /// deserialised from the query string.
+    #[serde(skip)]
+    pub parsed_limit: u64,
+}
+
+/// A task list item together with the note it belongs to.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskResponse {
+    /// The identifier of the note the item belongs to.
+    pub note_id: i64,
+    /// The title of the note.
+    pub note_title: String,
+    /// The zero-based position of the item among the note's tasks.
+    pub index: usize,
+    /// The text after the checkbox.
+    pub text: String,
+    /// Whether the item is ticked.
+    pub done: bool,
+    /// The one-based line of the note content the item is on.
+    pub line: usize,
+}
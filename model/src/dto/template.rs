@@ -0,0 +1,65 @@
+//! Request and response DTOs for note templates.
+
+use std::collections::BTreeMap;
+
+use crate::dto::datetime::FormattedDateTime;
+use serde::{Deserialize, Serialize};
+
+/// Request body for creating a note template.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateTemplateRequest {
+    /// The unique display name of the template.
+    pub name: String,
+    /// The title pattern, e.g. `Stand-up {{date}}`.
+    pub title: String,
+    /// The content pattern, e.g. `Notes by {{user}} for {{project}}`.
+    pub content: String,
+}
+
+/// Request body for partially updating a note template.
+///
+/// Only the fields that are [`Some`] will be applied; omitted fields remain
+/// unchanged.
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateTemplateRequest {
+    /// An optional new name for the template.
+    pub name: Option<String>,
+    /// An optional new title pattern.
+    pub title: Option<String>,
+    /// An optional new content pattern.
+    pub content: Option<String>,
+}
+
+/// Serialisable representation of a note template returned to the client.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateResponse {
+    /// The unique identifier of the template.
+    pub id: i64,
+    /// The unique display name of the template.
+    pub name: String,
+    /// The title pattern.
+    pub title: String,
+    /// The content pattern.
+    pub content: String,
+    /// The variables used by the patterns, in order of first use.
+    pub variables: Vec<String>,
+    /// The timestamp at which the template was created.
+    pub created_at: FormattedDateTime,
+    /// The timestamp at which the template was last updated.
+    pub updated_at: FormattedDateTime,
+}
+
+/// Request body for creating a note from a template; may be omitted when
+/// the template only uses `{{date}}`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RenderTemplateRequest {
+    /// The value of `{{user}}`, which may instead be given in
+    /// [`variables`](Self::variables).
+    pub user: Option<String>,
+    /// The values of the template's custom variables, and possibly of
+    /// `{{user}}`.
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
+}
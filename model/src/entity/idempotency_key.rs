@@ -0,0 +1,33 @@
+//! SeaORM entity for the `idempotency_keys` table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Derives the SeaORM model, relation, and active-model boilerplate.
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "idempotency_keys")]
+pub struct Model {
+    /// The client-supplied `Idempotency-Key` header value.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub key: String,
+
+    /// Hex-encoded SHA-256 digest of the request that first used the key.
+    pub fingerprint: String,
+
+    /// HTTP status code of the stored response.
+    pub status_code: i16,
+
+    /// JSON body of the stored response, with RFC 3339 UTC timestamps.
+    #[sea_orm(column_type = "Text")]
+    pub response_body: String,
+
+    /// Timestamp set to the current UTC time when the row is first inserted.
+    #[sea_orm(default_value = "Expr::current_timestamp()")]
+    pub created_at: ChronoDateTimeUtc,
+
+    /// Timestamp after which the key may be reused.
+    pub expires_at: ChronoDateTimeUtc,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,3 +1,7 @@
 //! SeaORM entity definitions that map to database tables.
 
+pub mod idempotency_key;
 pub mod note;
+pub mod property_schema;
+pub mod saved_search;
+pub mod template;
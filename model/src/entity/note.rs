@@ -27,6 +27,29 @@ pub struct Model {
     /// Timestamp updated to the current UTC time whenever the row is modified.
     #[sea_orm(default_value = "Expr::current_timestamp()")]
     pub updated_at: ChronoDateTimeUtc,
+
+    /// Whether the note is listed before the others on request.
+    #[sea_orm(default_value = false)]
+    pub pinned: bool,
+
+    /// Whether the note is marked as a favourite.
+    #[sea_orm(default_value = false)]
+    pub starred: bool,
+
+    /// Whether the note is hidden from default listings.
+    #[sea_orm(default_value = false)]
+    pub archived: bool,
+
+    /// When the next reminder for the note is due, if any.
+    pub remind_at: Option<ChronoDateTimeUtc>,
+
+    /// The recurrence rule of the reminder, in canonical `RRULE` form.
+    #[sea_orm(column_type = "Text")]
+    pub recurrence: Option<String>,
+
+    /// The custom properties of the note, as a JSON object of scalars.
+    #[sea_orm(column_type = "JsonBinary")]
+    pub properties: Json,
 }
 
 impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,27 @@
+//! SeaORM entity for the `property_schemas` table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Derives the SeaORM model, relation, and active-model boilerplate.
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "property_schemas")]
+pub struct Model {
+    /// Auto-incrementing primary key.
+    #[sea_orm(primary_key)]
+    pub id: i64,
+
+    /// The unique property key the schema applies to.
+    #[sea_orm(unique)]
+    pub key: String,
+
+    /// The required value type: `string`, `number`, or `boolean`.
+    pub r#type: String,
+
+    /// Timestamp set to the current UTC time when the row is first inserted.
+    #[sea_orm(default_value = "Expr::current_timestamp()")]
+    pub created_at: ChronoDateTimeUtc,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
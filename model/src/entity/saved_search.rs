@@ -0,0 +1,28 @@
+//! SeaORM entity for the `saved_searches` table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Derives the SeaORM model, relation, and active-model boilerplate.
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "saved_searches")]
+pub struct Model {
+    /// Auto-incrementing primary key.
+    #[sea_orm(primary_key)]
+    pub id: i64,
+
+    /// The unique display name of the search.
+    #[sea_orm(unique)]
+    pub name: String,
+
+    /// The raw search parameters, serialised as a JSON object.
+    #[sea_orm(column_type = "Text")]
+    pub params: String,
+
+    /// Timestamp set to the current UTC time when the row is first inserted.
+    #[sea_orm(default_value = "Expr::current_timestamp()")]
+    pub created_at: ChronoDateTimeUtc,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
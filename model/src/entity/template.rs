@@ -0,0 +1,36 @@
+//! SeaORM entity for the `templates` table.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Derives the SeaORM model, relation, and active-model boilerplate.
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "templates")]
+pub struct Model {
+    /// Auto-incrementing primary key.
+    #[sea_orm(primary_key)]
+    pub id: i64,
+
+    /// The unique display name of the template.
+    #[sea_orm(unique)]
+    pub name: String,
+
+    /// The pattern rendered into the title of new notes.
+    #[sea_orm(column_type = "Text")]
+    pub title: String,
+
+    /// The pattern rendered into the content of new notes.
+    #[sea_orm(column_type = "Text")]
+    pub content: String,
+
+    /// Timestamp set to the current UTC time when the row is first inserted.
+    #[sea_orm(default_value = "Expr::current_timestamp()")]
+    pub created_at: ChronoDateTimeUtc,
+
+    /// Timestamp refreshed to the current UTC time on every update.
+    #[sea_orm(default_value = "Expr::current_timestamp()")]
+    pub updated_at: ChronoDateTimeUtc,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,11 +1,19 @@
 //! Shared domain models for the notes application.
 //!
-//! This crate houses two sub-modules:
+//! This crate houses three sub-modules:
 //!
 //! * [`dto`] – Data Transfer Objects used at the API boundary (requests,
 //!   responses, and pagination helpers).
 //! * [`entity`] – SeaORM entity definitions that map directly to database
 //!   tables.
+//! * [`query`] – The boolean filter expression language and its parser.
+//! * [`rrule`] – The recurrence rules of note reminders.
+//! * [`task`] – GFM task list items found in note content.
+//! * [`template`] – The placeholder patterns of note templates.
 
 pub mod dto;
 pub mod entity;
+pub mod query;
+pub mod rrule;
+pub mod task;
+pub mod template;
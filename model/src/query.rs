@@ -0,0 +1,557 @@
+//! Boolean filter expressions for searching notes.
+//!
+//! [`parse`] turns an expression such as
+//! `title:~"release" AND (created>2025-01-01 OR id:7) AND NOT content:draft`
+//! into an [`Expr`] tree. Parsing is purely syntactic: field names and values
+//! are kept verbatim together with their positions, so that the service
+//! layer can reject unknown fields or malformed values with a precise
+//! location.
+//!
+//! The grammar, from lowest to highest precedence:
+//!
+//! ```text
+//! expr     := and ("OR" and)*
+//! and      := unary ("AND" unary)*
+//! unary    := "NOT" unary | "(" expr ")" | term
+//! term     := field operator value
+//! operator := ":" | ":~" | ":^" | ">" | ">=" | "<" | "<="
+//! value    := '"' (char | '\"' | '\\')* '"' | bare
+//! ```
+//!
+//! Keywords are upper-case. A bare value runs until whitespace or a
+//! parenthesis, so timestamps such as `2025-01-01T10:00:00Z` need no quotes.
+
+use std::fmt;
+
+/// Maximum nesting depth of parentheses and `NOT`, guarding against stack
+/// exhaustion on hostile input.
+const MAX_DEPTH: usize = 32;
+
+/// A parsed filter expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    /// Every operand must match.
+    And(Vec<Expr>),
+    /// At least one operand must match.
+    Or(Vec<Expr>),
+    /// The operand must not match.
+    Not(Box<Expr>),
+    /// A single field comparison.
+    Term(Term),
+}
+
+/// A comparison of one field against a value, e.g. `created>2025-01-01`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Term {
+    /// The field name as written.
+    pub field: String,
+    /// The one-based character position of the field name.
+    pub field_position: usize,
+    /// The comparison operator.
+    pub operator: Operator,
+    /// The value, with quotes and escapes removed.
+    pub value: String,
+    /// The one-based character position of the value.
+    pub value_position: usize,
+}
+
+/// A comparison operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    /// `:` – equality.
+    Equals,
+    /// `:~` – substring match.
+    Contains,
+    /// `:^` – prefix match.
+    Prefix,
+    /// `>` – strictly greater.
+    Greater,
+    /// `>=` – greater or equal.
+    GreaterOrEqual,
+    /// `<` – strictly less.
+    Less,
+    /// `<=` – less or equal.
+    LessOrEqual,
+}
+
+impl Operator {
+    /// All operators, longest spelling first so that tokenisation is greedy.
+    const BY_LENGTH: &[Operator] = &[
+        Self::Contains,
+        Self::Prefix,
+        Self::GreaterOrEqual,
+        Self::LessOrEqual,
+        Self::Equals,
+        Self::Greater,
+        Self::Less,
+    ];
+
+    /// Returns the operator as written in an expression.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::Equals => ":",
+            Self::Contains => ":~",
+            Self::Prefix => ":^",
+            Self::Greater => ">",
+            Self::GreaterOrEqual => ">=",
+            Self::Less => "<",
+            Self::LessOrEqual => "<=",
+        }
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.symbol())
+    }
+}
+
+/// A syntax error in a filter expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// What went wrong.
+    pub message: String,
+    /// The one-based character position at which the error was detected.
+    pub position: usize,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses a filter expression.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] locating the first syntax error.
+pub fn parse(input: &str) -> Result<Expr, ParseError> {
+    let mut parser = Parser {
+        chars: input.chars().collect(),
+        position: 0,
+        depth: 0,
+    };
+
+    parser.skip_whitespace();
+    if parser.at_end() {
+        return Err(parser.error("Filter expression is empty"));
+    }
+
+    let expr = parser.parse_or()?;
+
+    parser.skip_whitespace();
+    if let Some(character) = parser.peek() {
+        let message = if character == ')' {
+            "Unmatched ')'".to_owned()
+        } else {
+            format!("Unexpected '{character}'; expected AND, OR, or the end of the expression")
+        };
+        return Err(parser.error(message));
+    }
+
+    Ok(expr)
+}
+
+/// A recursive-descent parser over the characters of an expression.
+struct Parser {
+    /// The expression, one element per character.
+    chars: Vec<char>,
+    /// The zero-based index of the next character.
+    position: usize,
+    /// The current nesting depth.
+    depth: usize,
+}
+
+impl Parser {
+    /// Parses a disjunction.
+    fn parse_or(&mut self) -> Result<Expr, ParseError> {
+        let mut operands = vec![self.parse_and()?];
+
+        while self.keyword("OR") {
+            operands.push(self.parse_and()?);
+        }
+
+        Ok(if operands.len() == 1 { operands.remove(0) } else { Expr::Or(operands) })
+    }
+
+    /// Parses a conjunction.
+    fn parse_and(&mut self) -> Result<Expr, ParseError> {
+        let mut operands = vec![self.parse_unary()?];
+
+        while self.keyword("AND") {
+            operands.push(self.parse_unary()?);
+        }
+
+        Ok(if operands.len() == 1 { operands.remove(0) } else { Expr::And(operands) })
+    }
+
+    /// Parses a negation, a parenthesised group, or a term.
+    fn parse_unary(&mut self) -> Result<Expr, ParseError> {
+        self.skip_whitespace();
+
+        if self.keyword("NOT") {
+            self.enter()?;
+            let operand = self.parse_unary()?;
+            self.depth -= 1;
+            return Ok(Expr::Not(Box::new(operand)));
+        }
+
+        if self.peek() == Some('(') {
+            let open = self.position;
+            self.enter()?;
+            self.position += 1;
+            let expr = self.parse_or()?;
+            self.skip_whitespace();
+
+            if self.peek() != Some(')') {
+                return Err(self.error(format!("Expected ')' to close '(' at position {}", open + 1)));
+            }
+
+            self.position += 1;
+            self.depth -= 1;
+            return Ok(expr);
+        }
+
+        self.parse_term().map(Expr::Term)
+    }
+
+    /// Parses `field operator value`.
+    fn parse_term(&mut self) -> Result<Term, ParseError> {
+        let field_position = self.position + 1;
+        let field: String = self.take_while(|character| character.is_ascii_alphanumeric() || character == '_');
+
+        if field.is_empty() || field.starts_with(|character: char| character.is_ascii_digit()) {
+            return Err(ParseError {
+                message: "Expected a field name, '(' or NOT".into(),
+                position: field_position,
+            });
+        }
+
+        let operator = self.parse_operator().ok_or_else(|| {
+            let expected: Vec<&str> = Operator::BY_LENGTH.iter().map(|operator| operator.symbol()).collect();
+            self.error(format!("Expected an operator ({}) after field '{field}'", expected.join(", ")))
+        })?;
+
+        let value_position = self.position + 1;
+        let value = if self.peek() == Some('"') {
+            self.parse_quoted()?
+        } else {
+            self.take_while(|character| !character.is_whitespace() && character != '(' && character != ')')
+        };
+
+        if value.is_empty() && self.chars.get(value_position - 1) != Some(&'"') {
+            return Err(self.error(format!("Expected a value after '{field}{operator}'")));
+        }
+
+        Ok(Term {
+            field,
+            field_position,
+            operator,
+            value,
+            value_position,
+        })
+    }
+
+    /// Consumes the longest operator at the current position.
+    fn parse_operator(&mut self) -> Option<Operator> {
+        let operator = Operator::BY_LENGTH.iter().copied().find(|operator| self.lookahead(operator.symbol()))?;
+        self.position += operator.symbol().chars().count();
+        Some(operator)
+    }
+
+    /// Parses a double-quoted string, resolving `\"` and `\\` escapes.
+    fn parse_quoted(&mut self) -> Result<String, ParseError> {
+        let open = self.position;
+        self.position += 1;
+        let mut value = String::new();
+
+        loop {
+            match self.peek() {
+                None => {
+                    return Err(ParseError {
+                        message: "Unterminated string".into(),
+                        position: open + 1,
+                    });
+                },
+                Some('"') => {
+                    self.position += 1;
+                    return Ok(value);
+                },
+                Some('\\') if matches!(self.chars.get(self.position + 1), Some('"' | '\\')) => {
+                    value.push(self.chars[self.position + 1]);
+                    self.position += 2;
+                },
+                Some(character) => {
+                    value.push(character);
+                    self.position += 1;
+                },
+            }
+        }
+    }
+
+    /// Consumes `keyword` if it appears next as a whole word.
+    fn keyword(&mut self, keyword: &str) -> bool {
+        self.skip_whitespace();
+
+        let length = keyword.chars().count();
+        let bounded = self
+            .chars
+            .get(self.position + length)
+            .is_none_or(|character| character.is_whitespace() || *character == '(' || *character == ')');
+
+        if self.lookahead(keyword) && bounded {
+            self.position += length;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns whether `text` appears at the current position.
+    fn lookahead(&self, text: &str) -> bool {
+        text.chars()
+            .enumerate()
+            .all(|(offset, character)| self.chars.get(self.position + offset) == Some(&character))
+    }
+
+    /// Consumes and returns characters while `predicate` holds.
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> String {
+        let start = self.position;
+        while self.peek().is_some_and(&predicate) {
+            self.position += 1;
+        }
+        self.chars[start..self.position].iter().collect()
+    }
+
+    /// Enters one level of nesting.
+    fn enter(&mut self) -> Result<(), ParseError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(self.error(format!("Expression is nested more than {MAX_DEPTH} levels deep")));
+        }
+        Ok(())
+    }
+
+    /// Skips whitespace.
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.position += 1;
+        }
+    }
+
+    /// Returns the next character without consuming it.
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    /// Returns whether all input has been consumed.
+    fn at_end(&self) -> bool {
+        self.position >= self.chars.len()
+    }
+
+    /// Builds an error at the current position.
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError {
+            message: message.into(),
+            position: self.position + 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a term without checking positions.
+    fn term(field: &str, operator: Operator, value: &str) -> Expr {
+        Expr::Term(Term {
+            field: field.into(),
+            field_position: 0,
+            operator,
+            value: value.into(),
+            value_position: 0,
+        })
+    }
+
+    /// Clears every position, so that trees can be compared by shape.
+    fn shape(expr: Expr) -> Expr {
+        match expr {
+            Expr::And(operands) => Expr::And(operands.into_iter().map(shape).collect()),
+            Expr::Or(operands) => Expr::Or(operands.into_iter().map(shape).collect()),
+            Expr::Not(operand) => Expr::Not(Box::new(shape(*operand))),
+            Expr::Term(term) => Expr::Term(Term {
+                field_position: 0,
+                value_position: 0,
+                ..term
+            }),
+        }
+    }
+
+    /// Parses `input`, which must be valid, and clears its positions.
+    fn parse_shape(input: &str) -> Expr {
+        shape(parse(input).unwrap())
+    }
+
+    /// Parses `input`, which must be invalid, returning the error message
+    /// and position.
+    fn error_at(input: &str) -> (String, usize) {
+        let error = parse(input).unwrap_err();
+        (error.message, error.position)
+    }
+
+    #[test]
+    fn parses_a_term_with_positions() {
+        let Expr::Term(term) = parse(r#"  title:~"release""#).unwrap() else {
+            panic!("expected a term");
+        };
+
+        assert_eq!(term.field, "title");
+        assert_eq!(term.field_position, 3);
+        assert_eq!(term.operator, Operator::Contains);
+        assert_eq!(term.value, "release");
+        assert_eq!(term.value_position, 10);
+    }
+
+    #[test]
+    fn reads_operators_greedily() {
+        assert_eq!(
+            parse_shape("created>=2025-01-01"),
+            term("created", Operator::GreaterOrEqual, "2025-01-01")
+        );
+        assert_eq!(parse_shape("created<=2025-01-01"), term("created", Operator::LessOrEqual, "2025-01-01"));
+        assert_eq!(parse_shape("title:^draft"), term("title", Operator::Prefix, "draft"));
+        assert_eq!(parse_shape("id:7"), term("id", Operator::Equals, "7"));
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        assert_eq!(
+            parse_shape("a:1 OR b:2 AND c:3"),
+            Expr::Or(vec![
+                term("a", Operator::Equals, "1"),
+                Expr::And(vec![term("b", Operator::Equals, "2"), term("c", Operator::Equals, "3")]),
+            ])
+        );
+    }
+
+    #[test]
+    fn not_binds_tighter_than_and() {
+        assert_eq!(
+            parse_shape("NOT a:1 AND b:2"),
+            Expr::And(vec![
+                Expr::Not(Box::new(term("a", Operator::Equals, "1"))),
+                term("b", Operator::Equals, "2")
+            ])
+        );
+    }
+
+    #[test]
+    fn parentheses_override_precedence() {
+        assert_eq!(
+            parse_shape("(a:1 OR b:2) AND NOT(c:3)"),
+            Expr::And(vec![
+                Expr::Or(vec![term("a", Operator::Equals, "1"), term("b", Operator::Equals, "2")]),
+                Expr::Not(Box::new(term("c", Operator::Equals, "3"))),
+            ])
+        );
+    }
+
+    #[test]
+    fn chains_operands_of_the_same_operator() {
+        assert_eq!(
+            parse_shape("a:1 OR b:2 OR c:3"),
+            Expr::Or(vec![
+                term("a", Operator::Equals, "1"),
+                term("b", Operator::Equals, "2"),
+                term("c", Operator::Equals, "3")
+            ])
+        );
+    }
+
+    #[test]
+    fn bare_values_end_at_whitespace_or_parentheses() {
+        assert_eq!(
+            parse_shape("(created>2025-01-01T10:00:00Z)"),
+            term("created", Operator::Greater, "2025-01-01T10:00:00Z")
+        );
+    }
+
+    #[test]
+    fn resolves_escapes_in_quoted_values() {
+        assert_eq!(
+            parse_shape(r#"title:"say \"hi\" \\ (now)""#),
+            term("title", Operator::Equals, r#"say "hi" \ (now)"#)
+        );
+        assert_eq!(parse_shape(r#"title:"a\nb""#), term("title", Operator::Equals, r"a\nb"));
+    }
+
+    #[test]
+    fn accepts_an_empty_quoted_value() {
+        assert_eq!(parse_shape(r#"title:"""#), term("title", Operator::Equals, ""));
+    }
+
+    #[test]
+    fn keywords_must_be_upper_case_whole_words() {
+        assert_eq!(error_at("a:1 or b:2").1, 5);
+        assert_eq!(error_at("a:1 ORb:2").1, 5);
+        assert_eq!(parse_shape("ORDER:1"), term("ORDER", Operator::Equals, "1"));
+    }
+
+    #[test]
+    fn reports_an_empty_expression() {
+        assert_eq!(error_at("   "), ("Filter expression is empty".into(), 4));
+    }
+
+    #[test]
+    fn reports_an_unmatched_closing_parenthesis() {
+        assert_eq!(error_at("a:1)"), ("Unmatched ')'".into(), 4));
+    }
+
+    #[test]
+    fn reports_an_unclosed_parenthesis_with_its_position() {
+        assert_eq!(error_at("a:1 AND (b:2"), ("Expected ')' to close '(' at position 9".into(), 13));
+    }
+
+    #[test]
+    fn reports_an_unterminated_string_at_its_opening_quote() {
+        assert_eq!(error_at(r#"title:"abc"#), ("Unterminated string".into(), 7));
+    }
+
+    #[test]
+    fn reports_a_missing_operator() {
+        let (message, position) = error_at("title release");
+        assert!(message.starts_with("Expected an operator"), "{message}");
+        assert_eq!(position, 6);
+    }
+
+    #[test]
+    fn reports_a_missing_value() {
+        assert_eq!(error_at("title: AND a:1"), ("Expected a value after 'title:'".into(), 7));
+    }
+
+    #[test]
+    fn reports_a_missing_field() {
+        assert_eq!(error_at("a:1 AND :x"), ("Expected a field name, '(' or NOT".into(), 9));
+        assert_eq!(error_at("1a:x").1, 1);
+    }
+
+    #[test]
+    fn counts_positions_in_characters() {
+        assert_eq!(error_at(r#"content:"café" ⊕"#).1, 16);
+    }
+
+    #[test]
+    fn limits_nesting_depth() {
+        let nested = format!("{}a:1", "NOT ".repeat(MAX_DEPTH));
+        assert!(parse(&nested).is_ok());
+
+        let too_deep = format!("{}a:1{}", "(".repeat(MAX_DEPTH + 1), ")".repeat(MAX_DEPTH + 1));
+        assert_eq!(
+            error_at(&too_deep),
+            (format!("Expression is nested more than {MAX_DEPTH} levels deep"), MAX_DEPTH + 1)
+        );
+    }
+}
@@ -0,0 +1,437 @@
+//! Recurrence rules for note reminders.
+//!
+//! [`Recurrence`] implements the subset of RFC 5545 `RRULE`s that suits
+//! reminders, e.g. `FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH;COUNT=10`:
+//!
+//! * `FREQ` – `DAILY`, `WEEKLY`, `MONTHLY`, or `YEARLY` (required).
+//! * `INTERVAL` – repeat every *n* periods (default 1).
+//! * `COUNT` – the number of occurrences left, including the next one.
+//! * `UNTIL` – the last permitted instant, as `YYYYMMDD` (inclusive) or
+//!   `YYYYMMDDTHHMMSSZ`; exclusive with `COUNT`.
+//! * `BYDAY` – weekday codes such as `MO,WE`, only with `FREQ=WEEKLY`.
+//!
+//! Occurrences are computed on the calendar of a given time zone and keep
+//! the reminder's local time of day, so a 09:00 reminder stays at 09:00
+//! across daylight saving changes. As in RFC 5545, a local time skipped by a
+//! change moves forward by the length of the gap, a repeated one resolves to
+//! its first instance, and a monthly or yearly rule skips periods that lack
+//! the reminder's day, such as 31 April or 29 February outside leap years.
+//! Each occurrence is derived from the previous one rather than from a fixed
+//! start, so occurrences after a skipped time keep the moved time of day.
+
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+
+/// The rule parts understood by [`Recurrence`].
+const SUPPORTED_PARTS: &str = "FREQ, INTERVAL, COUNT, UNTIL, BYDAY";
+
+/// Maximum value of `INTERVAL` and `COUNT`.
+const MAX_REPEAT: u32 = 1_000;
+
+/// Maximum number of periods skipped while looking for a monthly or yearly
+/// occurrence; enough for 29 February with any interval that can ever hit a
+/// leap year again.
+const MAX_SKIPPED_PERIODS: u32 = 400;
+
+/// How often a rule repeats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    /// Every day.
+    Daily,
+    /// Every week.
+    Weekly,
+    /// Every month, on the same day of the month.
+    Monthly,
+    /// Every year, on the same day of the year.
+    Yearly,
+}
+
+impl fmt::Display for Frequency {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Daily => "DAILY",
+            Self::Weekly => "WEEKLY",
+            Self::Monthly => "MONTHLY",
+            Self::Yearly => "YEARLY",
+        };
+        formatter.write_str(name)
+    }
+}
+
+/// A parsed recurrence rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recurrence {
+    /// The period the rule repeats in.
+    pub frequency: Frequency,
+    /// The number of periods between occurrences.
+    pub interval: u32,
+    /// The number of occurrences left, if limited.
+    pub count: Option<u32>,
+    /// The last instant an occurrence may fall on, if limited.
+    pub until: Option<DateTime<Utc>>,
+    /// The weekdays of a weekly rule; empty means the reminder's weekday.
+    pub by_day: Vec<Weekday>,
+}
+
+impl Recurrence {
+    /// Returns the occurrence following `occurrence` on the calendar of
+    /// `time_zone`, or [`None`] when it would fall after
+    /// [`until`](Self::until). [`count`](Self::count) is left to the caller.
+    pub fn next_after(&self, occurrence: DateTime<Utc>, time_zone: Tz) -> Option<DateTime<Utc>> {
+        let local = occurrence.with_timezone(&time_zone).naive_local();
+        let date = local.date();
+        let time = local.time();
+
+        let next = match self.frequency {
+            Frequency::Daily => date.checked_add_signed(Duration::days(i64::from(self.interval))),
+            Frequency::Weekly if self.by_day.is_empty() => date.checked_add_signed(Duration::weeks(i64::from(self.interval))),
+            Frequency::Weekly => self.next_weekday(date),
+            Frequency::Monthly => (1..=MAX_SKIPPED_PERIODS).find_map(|periods| {
+                let months = date.month0() + self.interval * periods;
+                let year = date.year().checked_add(i32::try_from(months / 12).ok()?)?;
+                NaiveDate::from_ymd_opt(year, months % 12 + 1, date.day())
+            }),
+            Frequency::Yearly => (1..=MAX_SKIPPED_PERIODS).find_map(|periods| {
+                let year = date.year().checked_add(i32::try_from(self.interval * periods).ok()?)?;
+                NaiveDate::from_ymd_opt(year, date.month(), date.day())
+            }),
+        }?;
+
+        let next = to_utc(next.and_time(time), time_zone)?;
+        self.until.is_none_or(|until| next <= until).then_some(next)
+    }
+
+    /// Returns the next listed weekday after `date` in a week that is a
+    /// whole number of intervals after the week of `date`. Weeks start on
+    /// Monday.
+    fn next_weekday(&self, date: NaiveDate) -> Option<NaiveDate> {
+        let week_start = date.week(Weekday::Mon).first_day();
+        let horizon = 7 * i64::from(self.interval) + 6;
+
+        (1..=horizon).find_map(|offset| {
+            let candidate = date.checked_add_signed(Duration::days(offset))?;
+            let weeks = (candidate.week(Weekday::Mon).first_day() - week_start).num_weeks();
+            (weeks % i64::from(self.interval) == 0 && self.by_day.contains(&candidate.weekday())).then_some(candidate)
+        })
+    }
+}
+
+impl FromStr for Recurrence {
+    type Err = String;
+
+    /// Parses a rule, with or without the `RRULE:` prefix. Part names and
+    /// values are case-insensitive.
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let rule = rule.trim().to_ascii_uppercase();
+        let rule = rule.strip_prefix("RRULE:").unwrap_or(&rule);
+
+        let mut frequency = None;
+        let mut interval = None;
+        let mut count = None;
+        let mut until = None;
+        let mut by_day = None;
+
+        for part in rule.split(';').filter(|part| !part.is_empty()) {
+            let (name, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("Rule part '{part}' must have the form NAME=VALUE"))?;
+
+            let duplicate = match name {
+                "FREQ" => frequency.replace(parse_frequency(value)?).is_some(),
+                "INTERVAL" => interval.replace(parse_repeat(name, value)?).is_some(),
+                "COUNT" => count.replace(parse_repeat(name, value)?).is_some(),
+                "UNTIL" => until.replace(parse_until(value)?).is_some(),
+                "BYDAY" => by_day.replace(parse_weekdays(value)?).is_some(),
+                other => return Err(format!("Unsupported rule part '{other}'. Supported parts: {SUPPORTED_PARTS}")),
+            };
+
+            if duplicate {
+                return Err(format!("Rule part '{name}' must not be repeated"));
+            }
+        }
+
+        let frequency = frequency.ok_or("Rule must contain FREQ")?;
+
+        if count.is_some() && until.is_some() {
+            return Err("Rule parts COUNT and UNTIL must not be combined".into());
+        }
+
+        if by_day.is_some() && frequency != Frequency::Weekly {
+            return Err("Rule part BYDAY is only supported with FREQ=WEEKLY".into());
+        }
+
+        Ok(Self {
+            frequency,
+            interval: interval.unwrap_or(1),
+            count,
+            until,
+            by_day: by_day.unwrap_or_default(),
+        })
+    }
+}
+
+impl fmt::Display for Recurrence {
+    /// Writes the rule in canonical form, without the `RRULE:` prefix.
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "FREQ={}", self.frequency)?;
+
+        if self.interval != 1 {
+            write!(formatter, ";INTERVAL={}", self.interval)?;
+        }
+
+        if !self.by_day.is_empty() {
+            let days: Vec<&str> = self.by_day.iter().map(|day| weekday_code(*day)).collect();
+            write!(formatter, ";BYDAY={}", days.join(","))?;
+        }
+
+        if let Some(count) = self.count {
+            write!(formatter, ";COUNT={count}")?;
+        }
+
+        if let Some(until) = self.until {
+            write!(formatter, ";UNTIL={}", until.format("%Y%m%dT%H%M%SZ"))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Converts a local time to UTC. A time repeated by a daylight saving change
+/// resolves to its first instance, and one skipped by a change is read with
+/// the offset in force before it, i.e. moved forward by the gap.
+fn to_utc(local: NaiveDateTime, time_zone: Tz) -> Option<DateTime<Utc>> {
+    if let Some(instant) = time_zone.from_local_datetime(&local).earliest() {
+        return Some(instant.with_timezone(&Utc));
+    }
+
+    let before = time_zone.from_local_datetime(&local.checked_sub_signed(Duration::days(1))?).earliest()?;
+    let offset = before.offset().fix().local_minus_utc();
+
+    local.checked_sub_signed(Duration::seconds(i64::from(offset))).map(|utc| utc.and_utc())
+}
+
+/// Parses the value of `FREQ`.
+fn parse_frequency(value: &str) -> Result<Frequency, String> {
+    match value {
+        "DAILY" => Ok(Frequency::Daily),
+        "WEEKLY" => Ok(Frequency::Weekly),
+        "MONTHLY" => Ok(Frequency::Monthly),
+        "YEARLY" => Ok(Frequency::Yearly),
+        other => Err(format!("Unsupported FREQ '{other}'. Supported: DAILY, WEEKLY, MONTHLY, YEARLY")),
+    }
+}
+
+/// Parses the value of `INTERVAL` or `COUNT`.
+fn parse_repeat(name: &str, value: &str) -> Result<u32, String> {
+    value
+        .parse()
+        .ok()
+        .filter(|repeat| (1..=MAX_REPEAT).contains(repeat))
+        .ok_or_else(|| format!("Rule part {name} must be an integer from 1 to {MAX_REPEAT}, got '{value}'"))
+}
+
+/// Parses the value of `UNTIL`; a bare date includes the whole day.
+fn parse_until(value: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(date_time) = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%SZ") {
+        return Ok(date_time.and_utc());
+    }
+
+    NaiveDate::parse_from_str(value, "%Y%m%d")
+        .map(|date| date.and_time(NaiveTime::MIN).and_utc() + Duration::days(1) - Duration::seconds(1))
+        .map_err(|_| format!("Rule part UNTIL must be YYYYMMDD or YYYYMMDDTHHMMSSZ, got '{value}'"))
+}
+
+/// Parses the comma-separated weekday codes of `BYDAY`.
+fn parse_weekdays(value: &str) -> Result<Vec<Weekday>, String> {
+    let mut days = Vec::new();
+
+    for code in value.split(',') {
+        let day = match code {
+            "MO" => Weekday::Mon,
+            "TU" => Weekday::Tue,
+            "WE" => Weekday::Wed,
+            "TH" => Weekday::Thu,
+            "FR" => Weekday::Fri,
+            "SA" => Weekday::Sat,
+            "SU" => Weekday::Sun,
+            other => return Err(format!("Unsupported BYDAY value '{other}'. Supported: MO, TU, WE, TH, FR, SA, SU")),
+        };
+
+        if !days.contains(&day) {
+            days.push(day);
+        }
+    }
+
+    days.sort_by_key(Weekday::num_days_from_monday);
+    Ok(days)
+}
+
+/// Returns the RFC 5545 code of a weekday.
+fn weekday_code(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses an RFC 3339 timestamp, which must be valid.
+    fn utc(timestamp: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(timestamp).unwrap().with_timezone(&Utc)
+    }
+
+    /// Returns up to `limit` occurrences of `rule` after `start` on the
+    /// calendar of `time_zone`, as RFC 3339 UTC timestamps.
+    fn occurrences(rule: &str, start: &str, time_zone: Tz, limit: usize) -> Vec<String> {
+        let rule: Recurrence = rule.parse().unwrap();
+        std::iter::successors(Some(utc(start)), |occurrence| rule.next_after(*occurrence, time_zone))
+            .skip(1)
+            .take(limit)
+            .map(|occurrence| occurrence.to_rfc3339())
+            .collect()
+    }
+
+    #[test]
+    fn monthly_rule_skips_months_without_the_day() {
+        assert_eq!(
+            occurrences("FREQ=MONTHLY", "2026-01-31T09:00:00Z", Tz::UTC, 4),
+            [
+                "2026-03-31T09:00:00+00:00",
+                "2026-05-31T09:00:00+00:00",
+                "2026-07-31T09:00:00+00:00",
+                "2026-08-31T09:00:00+00:00"
+            ]
+        );
+    }
+
+    #[test]
+    fn monthly_rule_with_interval_crosses_years() {
+        assert_eq!(
+            occurrences("FREQ=MONTHLY;INTERVAL=5", "2026-10-31T09:00:00Z", Tz::UTC, 3),
+            ["2027-03-31T09:00:00+00:00", "2027-08-31T09:00:00+00:00", "2028-01-31T09:00:00+00:00"]
+        );
+    }
+
+    #[test]
+    fn yearly_rule_on_leap_day_waits_for_leap_years() {
+        assert_eq!(
+            occurrences("FREQ=YEARLY", "2024-02-29T12:00:00Z", Tz::UTC, 2),
+            ["2028-02-29T12:00:00+00:00", "2032-02-29T12:00:00+00:00"]
+        );
+        assert_eq!(
+            occurrences("FREQ=YEARLY;INTERVAL=3", "2024-02-29T12:00:00Z", Tz::UTC, 1),
+            ["2036-02-29T12:00:00+00:00"]
+        );
+    }
+
+    #[test]
+    fn weekly_rule_with_days_and_interval_skips_weeks() {
+        assert_eq!(
+            occurrences("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH", "2026-01-05T08:00:00Z", Tz::UTC, 4),
+            [
+                "2026-01-08T08:00:00+00:00",
+                "2026-01-19T08:00:00+00:00",
+                "2026-01-22T08:00:00+00:00",
+                "2026-02-02T08:00:00+00:00"
+            ]
+        );
+    }
+
+    #[test]
+    fn weekly_rule_starting_on_an_unlisted_day_counts_from_its_week() {
+        assert_eq!(
+            occurrences("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR", "2026-01-07T08:00:00Z", Tz::UTC, 2),
+            ["2026-01-09T08:00:00+00:00", "2026-01-19T08:00:00+00:00"]
+        );
+    }
+
+    #[test]
+    fn weekly_rule_without_days_repeats_the_weekday() {
+        assert_eq!(
+            occurrences("FREQ=WEEKLY;INTERVAL=3", "2026-01-07T08:00:00Z", Tz::UTC, 1),
+            ["2026-01-28T08:00:00+00:00"]
+        );
+    }
+
+    #[test]
+    fn local_time_is_kept_across_daylight_saving_changes() {
+        assert_eq!(
+            occurrences("FREQ=DAILY", "2026-03-28T07:00:00Z", Tz::Europe__Bucharest, 1),
+            ["2026-03-29T06:00:00+00:00"]
+        );
+        assert_eq!(
+            occurrences("FREQ=DAILY", "2026-10-24T06:00:00Z", Tz::Europe__Bucharest, 1),
+            ["2026-10-25T07:00:00+00:00"]
+        );
+    }
+
+    #[test]
+    fn skipped_local_time_moves_forward_by_the_gap() {
+        // 02:30 on 8 March 2026 does not exist in New York; 03:30 EDT does,
+        // and later occurrences stay at 03:30.
+        assert_eq!(
+            occurrences("FREQ=DAILY", "2026-03-07T07:30:00Z", Tz::America__New_York, 2),
+            ["2026-03-08T07:30:00+00:00", "2026-03-09T07:30:00+00:00"]
+        );
+    }
+
+    #[test]
+    fn repeated_local_time_resolves_to_its_first_instance() {
+        // 01:30 on 1 November 2026 occurs twice in New York; EDT comes first.
+        assert_eq!(
+            occurrences("FREQ=DAILY", "2026-10-31T05:30:00Z", Tz::America__New_York, 2),
+            ["2026-11-01T05:30:00+00:00", "2026-11-02T06:30:00+00:00"]
+        );
+    }
+
+    #[test]
+    fn until_date_includes_the_whole_day() {
+        assert_eq!(
+            occurrences("FREQ=DAILY;UNTIL=20260103", "2026-01-01T23:00:00Z", Tz::UTC, 5),
+            ["2026-01-02T23:00:00+00:00", "2026-01-03T23:00:00+00:00"]
+        );
+        assert_eq!(
+            occurrences("FREQ=DAILY;UNTIL=20260102T120000Z", "2026-01-01T13:00:00Z", Tz::UTC, 5),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn parsing_is_case_insensitive_and_display_is_canonical() {
+        let rule: Recurrence = " rrule:freq=weekly;byday=th,mo,th;interval=2;count=3 ".parse().unwrap();
+
+        assert_eq!(rule.by_day, [Weekday::Mon, Weekday::Thu]);
+        assert_eq!(rule.to_string(), "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH;COUNT=3");
+        assert_eq!(rule.to_string().parse::<Recurrence>().unwrap(), rule);
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected() {
+        for rule in [
+            "",
+            "INTERVAL=2",
+            "FREQ=HOURLY",
+            "FREQ=DAILY;INTERVAL=0",
+            "FREQ=DAILY;COUNT=1001",
+            "FREQ=DAILY;COUNT=2;UNTIL=20260101",
+            "FREQ=DAILY;BYDAY=MO",
+            "FREQ=WEEKLY;BYDAY=XX",
+            "FREQ=DAILY;FREQ=WEEKLY",
+            "FREQ=DAILY;UNTIL=2026-01-01",
+            "FREQ=DAILY;BYMONTH=1",
+            "FREQ",
+        ] {
+            assert!(rule.parse::<Recurrence>().is_err(), "{rule:?} should be rejected");
+        }
+    }
+}
@@ -0,0 +1,230 @@
+//! GitHub Flavored Markdown task list items.
+//!
+//! [`parse`] finds the task list items of a note's content, such as
+//! `- [ ] write tests` or `2. [x] ship it`, in document order; [`set_done`]
+//! rewrites the checkbox of one of them in place. Items may be nested in
+//! lists or block quotes. Lines inside fenced code blocks are skipped.
+//!
+//! As in GFM, an item is a list marker (`-`, `*`, `+`, or a number followed
+//! by `.` or `)`), whitespace, a checkbox (`[ ]`, `[x]`, or `[X]`), and
+//! whitespace before its text.
+
+/// Maximum number of digits in an ordered list marker.
+const MAX_ORDERED_DIGITS: usize = 9;
+
+/// A task list item of a note.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskItem {
+    /// The zero-based position of the item among the note's tasks.
+    pub index: usize,
+    /// Whether the checkbox is ticked.
+    pub done: bool,
+    /// The text after the checkbox, trimmed.
+    pub text: String,
+    /// The one-based line number of the item.
+    pub line: usize,
+    /// The byte offset of the checkbox mark (` `, `x`, or `X`).
+    mark: usize,
+}
+
+/// Returns every task list item of `content`, in document order.
+pub fn parse(content: &str) -> Vec<TaskItem> {
+    let mut tasks = Vec::new();
+    let mut fence: Option<(char, usize)> = None;
+    let mut offset = 0;
+
+    for (number, line) in content.split_inclusive('\n').enumerate() {
+        let start = offset;
+        offset += line.len();
+
+        let body = strip_container(line);
+
+        if let Some((character, length)) = fence_marker(body) {
+            fence = match fence {
+                None => Some((character, length)),
+                Some(open) if open.0 == character && open.1 <= length && body.trim_start_matches(character).trim().is_empty() => None,
+                open => open,
+            };
+            continue;
+        }
+
+        if fence.is_some() {
+            continue;
+        }
+
+        if let Some((mark, text)) = checkbox(body) {
+            let mark = start + (line.len() - body.len()) + mark;
+
+            tasks.push(TaskItem {
+                index: tasks.len(),
+                done: content.as_bytes()[mark] != b' ',
+                text: text.trim().to_owned(),
+                line: number + 1,
+                mark,
+            });
+        }
+    }
+
+    tasks
+}
+
+/// Ticks or clears the checkbox of the task at `index`, or toggles it when
+/// `done` is [`None`]. Returns the rewritten content and the updated item,
+/// or [`None`] if there is no such task. Everything else in the content is
+/// left untouched.
+pub fn set_done(content: &str, index: usize, done: Option<bool>) -> Option<(String, TaskItem)> {
+    let mut task = parse(content).into_iter().nth(index)?;
+    let mut rewritten = content.to_owned();
+
+    task.done = done.unwrap_or(!task.done);
+    rewritten.replace_range(task.mark..=task.mark, if task.done { "x" } else { " " });
+
+    Some((rewritten, task))
+}
+
+/// Strips the indentation and block-quote markers in front of a line's
+/// content.
+fn strip_container(line: &str) -> &str {
+    let mut body = line.trim_start_matches([' ', '\t']);
+
+    while let Some(quoted) = body.strip_prefix('>') {
+        body = quoted.trim_start_matches([' ', '\t']);
+    }
+
+    body
+}
+
+/// Returns the character and length of the code fence a line starts with,
+/// if any; a closing fence must repeat the opening character at least as
+/// many times and carry no info string.
+fn fence_marker(body: &str) -> Option<(char, usize)> {
+    let character = body.chars().next().filter(|character| matches!(character, '`' | '~'))?;
+    let length = body.chars().take_while(|next| *next == character).count();
+
+    (length >= 3).then_some((character, length))
+}
+
+/// Parses a list item with a checkbox, returning the offset of the mark
+/// within `body` and the text after the checkbox.
+fn checkbox(body: &str) -> Option<(usize, &str)> {
+    let after_marker = match body.as_bytes().first()? {
+        b'-' | b'*' | b'+' => &body[1..],
+        _ => {
+            let digits = body.bytes().take_while(u8::is_ascii_digit).count();
+            if !(1..=MAX_ORDERED_DIGITS).contains(&digits) {
+                return None;
+            }
+            body[digits..].strip_prefix(['.', ')'])?
+        },
+    };
+
+    let item = after_marker.trim_start_matches([' ', '\t']);
+    if item.len() == after_marker.len() {
+        return None;
+    }
+
+    let rest = item.strip_prefix('[')?;
+    if !rest.starts_with([' ', 'x', 'X']) {
+        return None;
+    }
+
+    let text = rest[1..].strip_prefix(']')?;
+    text.starts_with([' ', '\t']).then_some((body.len() - rest.len(), text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the done flag and text of every task of `content`.
+    fn tasks(content: &str) -> Vec<(bool, String)> {
+        parse(content).into_iter().map(|task| (task.done, task.text)).collect()
+    }
+
+    #[test]
+    fn parses_bullet_and_ordered_items() {
+        let content = "- [ ] one\n* [x] two\n+ [X] three\n1. [ ] four\n10) [x] five\n";
+
+        assert_eq!(
+            tasks(content),
+            [
+                (false, "one".into()),
+                (true, "two".into()),
+                (true, "three".into()),
+                (false, "four".into()),
+                (true, "five".into())
+            ]
+        );
+        assert_eq!(parse(content).iter().map(|task| task.line).collect::<Vec<_>>(), [1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn parses_nested_and_quoted_items() {
+        assert_eq!(
+            tasks("- [ ] parent\n  - [x] child\n> - [ ] quoted\n> > 1. [x] deeper"),
+            [
+                (false, "parent".into()),
+                (true, "child".into()),
+                (false, "quoted".into()),
+                (true, "deeper".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_lines_that_are_not_task_items() {
+        assert_eq!(
+            tasks("[ ] no marker\n-[ ] no space\n- [] empty\n- [y] other mark\n- [ ]no space after\n1234567890. [ ] too many digits\n- [ ]"),
+            []
+        );
+    }
+
+    #[test]
+    fn skips_items_in_fenced_code_blocks() {
+        let content = "- [ ] before\n```\n- [ ] in backticks\n```\n~~~rust\n- [x] in tildes\n~~~\n- [x] after\n";
+
+        assert_eq!(tasks(content), [(false, "before".into()), (true, "after".into())]);
+    }
+
+    #[test]
+    fn fence_closes_only_on_a_matching_bare_fence() {
+        let content = "````\n```\n- [ ] shorter fence\n~~~~\n- [ ] other character\n```` info\n- [ ] info string\n`````\n- [ ] closed\n";
+
+        assert_eq!(tasks(content), [(false, "closed".into())]);
+    }
+
+    #[test]
+    fn fence_in_block_quote_is_recognised() {
+        assert_eq!(tasks("> ```\n> - [ ] code\n> ```\n> - [ ] quoted"), [(false, "quoted".into())]);
+    }
+
+    #[test]
+    fn unclosed_fence_hides_the_rest_of_the_content() {
+        assert_eq!(tasks("- [ ] visible\n```\n- [ ] hidden"), [(false, "visible".into())]);
+    }
+
+    #[test]
+    fn set_done_rewrites_only_the_checkbox() {
+        let content = "# Plan\n```\n- [ ] code\n```\n- [ ] first\n  - [x] second ünïcode\n";
+
+        let (ticked, task) = set_done(content, 0, Some(true)).unwrap();
+        assert_eq!(ticked, "# Plan\n```\n- [ ] code\n```\n- [x] first\n  - [x] second ünïcode\n");
+        assert_eq!((task.index, task.done, task.line), (0, true, 5));
+
+        let (toggled, task) = set_done(content, 1, None).unwrap();
+        assert_eq!(toggled, "# Plan\n```\n- [ ] code\n```\n- [ ] first\n  - [ ] second ünïcode\n");
+        assert!(!task.done);
+    }
+
+    #[test]
+    fn set_done_writes_a_lowercase_mark_when_state_is_unchanged() {
+        assert_eq!(set_done("- [X] done", 0, Some(true)).unwrap().0, "- [x] done");
+        assert_eq!(set_done("- [ ] open", 0, Some(false)).unwrap().0, "- [ ] open");
+    }
+
+    #[test]
+    fn set_done_rejects_missing_tasks() {
+        assert!(set_done("- [ ] only", 1, Some(true)).is_none());
+        assert!(set_done("```\n- [ ] code\n```", 0, Some(true)).is_none());
+    }
+}
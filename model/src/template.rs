@@ -0,0 +1,195 @@
+//! Placeholder substitution for note templates.
+//!
+//! A [`Pattern`] is text with `{{name}}` placeholders, e.g.
+//! `Stand-up {{date}} ({{ user }})`. Names start with an ASCII letter or
+//! `_` and continue with ASCII letters, digits, `_`, or `-`; spaces inside
+//! the braces are ignored. Every `{{` must open a valid placeholder, so that
+//! typos are reported when a template is saved rather than copied into
+//! notes.
+
+use std::{collections::BTreeMap, str::FromStr};
+
+/// The opening delimiter of a placeholder.
+const OPEN: &str = "{{";
+
+/// The closing delimiter of a placeholder.
+const CLOSE: &str = "}}";
+
+/// Maximum length of a variable name, in characters.
+pub const MAX_VARIABLE_NAME_LEN: usize = 64;
+
+/// A parsed template pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    /// The literal text and placeholders, in order.
+    segments: Vec<Segment>,
+}
+
+/// A piece of a [`Pattern`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    /// Text copied verbatim.
+    Text(String),
+    /// A placeholder replaced by the value of the named variable.
+    Variable(String),
+}
+
+impl Pattern {
+    /// Returns the names of the variables used, in order of first use.
+    pub fn variables(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+
+        for segment in &self.segments {
+            if let Segment::Variable(name) = segment
+                && !names.contains(&name.as_str())
+            {
+                names.push(name);
+            }
+        }
+
+        names
+    }
+
+    /// Substitutes every placeholder with its value.
+    ///
+    /// # Errors
+    ///
+    /// Returns the name of the first variable without a value.
+    pub fn render(&self, values: &BTreeMap<String, String>) -> Result<String, String> {
+        let mut rendered = String::new();
+
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(text) => rendered.push_str(text),
+                Segment::Variable(name) => rendered.push_str(values.get(name).ok_or_else(|| name.clone())?),
+            }
+        }
+
+        Ok(rendered)
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = String;
+
+    /// Parses a pattern, reporting malformed placeholders with their
+    /// one-based character position.
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        let mut segments = Vec::new();
+        let mut rest = pattern;
+
+        while let Some(start) = rest.find(OPEN) {
+            let position = pattern[..pattern.len() - rest.len() + start].chars().count() + 1;
+
+            if start > 0 {
+                segments.push(Segment::Text(rest[..start].to_owned()));
+            }
+
+            let inner = &rest[start + OPEN.len()..];
+            let end = inner.find(CLOSE).ok_or_else(|| format!("Unclosed placeholder at position {position}"))?;
+            let name = inner[..end].trim_matches(' ');
+
+            if !is_variable_name(name) {
+                return Err(format!(
+                    "Invalid variable name '{name}' at position {position}: expected a letter or '_' followed by letters, digits, '_', or '-', \
+                     at most {MAX_VARIABLE_NAME_LEN} characters"
+                ));
+            }
+
+            segments.push(Segment::Variable(name.to_owned()));
+            rest = &inner[end + CLOSE.len()..];
+        }
+
+        if !rest.is_empty() {
+            segments.push(Segment::Text(rest.to_owned()));
+        }
+
+        Ok(Self { segments })
+    }
+}
+
+/// Returns whether `name` is a valid variable name.
+pub fn is_variable_name(name: &str) -> bool {
+    let mut characters = name.chars();
+
+    characters.next().is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && characters.all(|next| next.is_ascii_alphanumeric() || matches!(next, '_' | '-'))
+        && name.len() <= MAX_VARIABLE_NAME_LEN
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a value map from name and value pairs.
+    fn values(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs.iter().map(|(name, value)| ((*name).to_owned(), (*value).to_owned())).collect()
+    }
+
+    #[test]
+    fn renders_placeholders_with_their_values() {
+        let pattern: Pattern = "Stand-up {{date}} ({{ user }}) – {{date}}".parse().unwrap();
+
+        assert_eq!(
+            pattern.render(&values(&[("date", "2026-10-18"), ("user", "Ana")])).unwrap(),
+            "Stand-up 2026-10-18 (Ana) – 2026-10-18"
+        );
+    }
+
+    #[test]
+    fn lists_variables_in_order_of_first_use() {
+        let pattern: Pattern = "{{b}}{{a}} {{b}} {{_c-1}}".parse().unwrap();
+
+        assert_eq!(pattern.variables(), ["b", "a", "_c-1"]);
+    }
+
+    #[test]
+    fn text_without_placeholders_renders_verbatim() {
+        for text in ["", "plain", "single { brace }", "closing }} only", "ünïcode"] {
+            let pattern: Pattern = text.parse().unwrap();
+
+            assert!(pattern.variables().is_empty());
+            assert_eq!(pattern.render(&BTreeMap::new()).unwrap(), text);
+        }
+    }
+
+    #[test]
+    fn values_are_not_expanded_again() {
+        let pattern: Pattern = "{{a}}".parse().unwrap();
+
+        assert_eq!(pattern.render(&values(&[("a", "{{b}}"), ("b", "x")])).unwrap(), "{{b}}");
+    }
+
+    #[test]
+    fn render_reports_the_first_missing_variable() {
+        let pattern: Pattern = "{{a}} {{b}} {{c}}".parse().unwrap();
+
+        assert_eq!(pattern.render(&values(&[("a", "1")])).unwrap_err(), "b");
+    }
+
+    #[test]
+    fn malformed_placeholders_report_their_character_position() {
+        assert_eq!("ñ {{date".parse::<Pattern>().unwrap_err(), "Unclosed placeholder at position 3");
+        assert!(
+            "ab {{1st}}"
+                .parse::<Pattern>()
+                .unwrap_err()
+                .starts_with("Invalid variable name '1st' at position 4")
+        );
+        assert!(
+            "{{}}"
+                .parse::<Pattern>()
+                .unwrap_err()
+                .starts_with("Invalid variable name '' at position 1")
+        );
+        assert!("{{a b}}".parse::<Pattern>().unwrap_err().starts_with("Invalid variable name 'a b'"));
+    }
+
+    #[test]
+    fn variable_names_are_bounded() {
+        assert!(is_variable_name(&"a".repeat(MAX_VARIABLE_NAME_LEN)));
+        assert!(!is_variable_name(&"a".repeat(MAX_VARIABLE_NAME_LEN + 1)));
+        assert!(!is_variable_name("-a"));
+        assert!(!is_variable_name("é"));
+    }
+}
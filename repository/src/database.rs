@@ -1,18 +1,68 @@
 //! Database connection management.
 //!
 //! Provides [`DatabaseManager`], a thin wrapper around a SeaORM
-//! [`DatabaseConnection`] that applies backend-specific pool settings
-//! for SQLite and PostgreSQL.
+//! [`DatabaseConnection`] that applies [`PoolOptions`], with
+//! backend-specific defaults for SQLite and PostgreSQL.
 
 use sea_orm::{ConnectOptions, Database, DatabaseConnection, DbErr};
 use std::time::Duration;
 
+/// Connection-pool settings applied when connecting to the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolOptions {
+    /// Minimum number of idle connections kept open.
+    pub min_connections: u32,
+    /// Maximum number of open connections.
+    pub max_connections: u32,
+    /// Maximum time to wait while establishing a new connection.
+    pub connect_timeout: Duration,
+    /// Maximum time to wait for a connection to become available.
+    pub acquire_timeout: Duration,
+    /// Time after which an idle connection is closed.
+    pub idle_timeout: Duration,
+    /// Maximum lifetime of a connection, or [`None`] for no limit.
+    pub max_lifetime: Option<Duration>,
+}
+
+impl PoolOptions {
+    /// Defaults for SQLite – single connection, long idle timeout, and no
+    /// maximum lifetime.
+    pub const SQLITE: Self = Self {
+        min_connections: 1,
+        max_connections: 1,
+        connect_timeout: Duration::from_secs(5),
+        acquire_timeout: Duration::from_secs(5),
+        idle_timeout: Duration::from_secs(600),
+        max_lifetime: None,
+    };
+
+    /// Defaults for PostgreSQL – larger pool (2–20), shorter idle timeout,
+    /// and 30-minute maximum lifetime.
+    pub const POSTGRES: Self = Self {
+        min_connections: 2,
+        max_connections: 20,
+        connect_timeout: Duration::from_secs(10),
+        acquire_timeout: Duration::from_secs(10),
+        idle_timeout: Duration::from_secs(300),
+        max_lifetime: Some(Duration::from_secs(1800)),
+    };
+
+    /// Returns the backend-appropriate defaults for the given database URL.
+    pub fn for_url(database_url: &str) -> Self {
+        if is_sqlite(database_url) { Self::SQLITE } else { Self::POSTGRES }
+    }
+}
+
+/// Returns `true` if the URL scheme designates a SQLite database.
+pub fn is_sqlite(database_url: &str) -> bool {
+    database_url.starts_with("sqlite")
+}
+
 /// Manages the database connection lifecycle with backend-aware configuration.
 ///
 /// On construction the manager inspects the URL scheme to determine whether the
-/// target database is SQLite or PostgreSQL, then configures connection-pool
-/// parameters that are appropriate for each backend (e.g. a single connection
-/// for SQLite, a larger pool for PostgreSQL).
+/// target database is SQLite or PostgreSQL and applies the supplied
+/// [`PoolOptions`] (see [`PoolOptions::for_url`] for backend defaults).
 #[derive(Debug, Clone)]
 pub struct DatabaseManager {
     /// The underlying SeaORM database connection.
@@ -21,17 +71,14 @@ pub struct DatabaseManager {
 
 impl DatabaseManager {
     /// Creates a new [`DatabaseManager`] by connecting to the database at the
-    /// given URL and applying backend-specific pool optimisations.
+    /// given URL with the given pool settings.
     ///
     /// # Errors
     ///
     /// Returns a [`DbErr`] if the connection cannot be established.
-    pub async fn new(database_url: &str) -> Result<Self, DbErr> {
-        let is_sqlite = database_url.starts_with("sqlite");
-        let connection_options = Self::build_options(database_url, is_sqlite);
-
-        tracing::debug!(is_sqlite, "Building connection options");
-        let connection = Database::connect(connection_options).await?;
+    pub async fn new(database_url: &str, pool: PoolOptions) -> Result<Self, DbErr> {
+        tracing::debug!(is_sqlite = is_sqlite(database_url), ?pool, "Building connection options");
+        let connection = Database::connect(Self::build_options(database_url, pool)).await?;
         tracing::info!("Database connection established");
 
         Ok(Self { connection })
@@ -47,34 +94,32 @@ impl DatabaseManager {
         self.connection
     }
 
-    /// Builds backend-specific [`ConnectOptions`].
+    /// Closes the connection pool, waiting for checked-out connections to be
+    /// returned first.
+    ///
+    /// # Errors
     ///
-    /// * **SQLite** – single connection, long idle timeout, no maximum
-    ///   lifetime, and SQLx logging disabled.
-    /// * **PostgreSQL** – larger pool (2–20), shorter idle timeout, 30-minute
-    ///   maximum lifetime, and SQLx logging disabled.
-    fn build_options(database_url: &str, is_sqlite: bool) -> ConnectOptions {
+    /// Returns a [`DbErr`] if the pool cannot be closed cleanly.
+    pub async fn close(self) -> Result<(), DbErr> {
+        self.connection.close().await?;
+        tracing::info!("Database connection closed");
+
+        Ok(())
+    }
+
+    /// Builds [`ConnectOptions`] from the given pool settings, with SQLx
+    /// logging disabled.
+    fn build_options(database_url: &str, pool: PoolOptions) -> ConnectOptions {
         let mut options = ConnectOptions::new(database_url);
 
-        if is_sqlite {
-            options
-                .min_connections(1)
-                .max_connections(1)
-                .connect_timeout(Duration::from_secs(5))
-                .acquire_timeout(Duration::from_secs(5))
-                .idle_timeout(Duration::from_secs(600))
-                .max_lifetime(None)
-                .sqlx_logging(false);
-        } else {
-            options
-                .min_connections(2)
-                .max_connections(20)
-                .connect_timeout(Duration::from_secs(10))
-                .acquire_timeout(Duration::from_secs(10))
-                .idle_timeout(Duration::from_secs(300))
-                .max_lifetime(Duration::from_secs(1800))
-                .sqlx_logging(false);
-        }
+        options
+            .min_connections(pool.min_connections)
+            .max_connections(pool.max_connections)
+            .connect_timeout(pool.connect_timeout)
+            .acquire_timeout(pool.acquire_timeout)
+            .idle_timeout(pool.idle_timeout)
+            .max_lifetime(pool.max_lifetime)
+            .sqlx_logging(false);
 
         options
     }
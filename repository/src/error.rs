@@ -1,7 +1,8 @@
 //! Error types for the repository layer.
 //!
 //! [`RepositoryError`] is the generic, entity-agnostic error, whilst
-//! [`NoteRepositoryError`] is specific to note persistence operations.
+//! [`NoteRepositoryError`] is returned by the repositories of notes and of
+//! the resources attached to them.
 //! A [`From`] conversion between the two is provided for convenience.
 
 use thiserror::Error;
@@ -21,9 +22,17 @@ pub enum RepositoryError {
         /// The primary-key identifier that was looked up.
         id: i64,
     },
+
+    /// The operation would violate a uniqueness constraint.
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    /// The operation would store a value exceeding a limit.
+    #[error("Validation error: {0}")]
+    Validation(String),
 }
 
-/// An error specific to note repository operations.
+/// An error returned by the note and related repositories.
 #[derive(Debug, Error)]
 pub enum NoteRepositoryError {
     /// An error originating from the underlying database driver.
@@ -33,6 +42,13 @@ pub enum NoteRepositoryError {
     /// The note with the given ID could not be found.
     #[error("Note with ID {0} not found")]
     NotFound(i64),
+
+    /// The content of a note would exceed the given length, in characters.
+    #[error("Content would exceed {max} characters")]
+    ContentTooLong {
+        /// The maximum content length, in characters.
+        max: usize,
+    },
 }
 
 impl From<NoteRepositoryError> for RepositoryError {
@@ -40,6 +56,7 @@ impl From<NoteRepositoryError> for RepositoryError {
         match error {
             NoteRepositoryError::DatabaseError(err) => RepositoryError::DatabaseError(err),
             NoteRepositoryError::NotFound(id) => RepositoryError::NotFound { entity: "Note".into(), id },
+            NoteRepositoryError::ContentTooLong { .. } => RepositoryError::Validation(error.to_string()),
         }
     }
 }
@@ -0,0 +1,257 @@
+//! Translation of validated note filters, including boolean `filter`
+//! expressions, into SeaORM conditions.
+//!
+//! Case-insensitive text filters compare lower-cased values with an escaped
+//! `LIKE` pattern, so `%` and `_` in user input match literally on every
+//! backend. Case-sensitive filters avoid `LIKE`, whose case handling differs
+//! between SQLite and PostgreSQL, and use substring functions instead.
+//! Timestamp comparisons normalise SQLite's textual timestamps first.
+//! Property comparisons read the JSON value with `json_extract` on SQLite
+//! and `->` on PostgreSQL, and only match values of the filter's type.
+//! Fuzzy title matching is only expressed in SQL on PostgreSQL, with the
+//! `pg_trgm` `%` operator that the title trigram index serves; elsewhere the
+//! repository ranks the titles in-process.
+
+use chrono::{DateTime, Utc};
+use model::{
+    dto::{
+        pagination::{Comparison, FilterExpr, NoteFilters, PropertyFilter, TextField, TextFilter, TextMatch, TimestampField},
+        property::PropertyValue,
+    },
+    entity::note,
+};
+use sea_orm::{
+    ColumnTrait, Condition, DbBackend, Value,
+    sea_query::{BinOper, Expr, ExprTrait, Func, LikeExpr, SimpleExpr},
+};
+
+use crate::sort::IntoColumn;
+
+/// The `STRFTIME` layout SQLite timestamps are normalised to, with
+/// millisecond precision.
+const SQLITE_TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%f";
+
+/// The escape character used in `LIKE` patterns.
+const LIKE_ESCAPE: char = '\\';
+
+/// Builds the condition that a note must satisfy to pass every filter.
+pub(crate) fn note_condition(filters: &NoteFilters, backend: DbBackend) -> Condition {
+    let mut condition = Condition::all();
+
+    for filter in &filters.text {
+        let matches = text_condition(filter, filters.case_sensitive, backend);
+        condition = condition.add(if filter.negated { matches.not() } else { matches });
+    }
+
+    if !filters.ids.is_empty() {
+        condition = condition.add(note::Column::Id.is_in(filters.ids.iter().copied()));
+    }
+
+    for (flag, value) in &filters.flags {
+        condition = condition.add(flag.into_column().eq(*value));
+    }
+
+    let bounds = [
+        (note::Column::CreatedAt, filters.created_after, filters.created_before),
+        (note::Column::UpdatedAt, filters.updated_after, filters.updated_before),
+    ];
+
+    for (column, after, before) in bounds {
+        if let Some(after) = after {
+            condition = condition.add(compare_timestamp(column, Comparison::Greater, after, backend));
+        }
+
+        if let Some(before) = before {
+            condition = condition.add(compare_timestamp(column, Comparison::Less, before, backend));
+        }
+    }
+
+    for filter in &filters.properties {
+        condition = condition.add(property_condition(filter, backend));
+    }
+
+    if let Some(expression) = &filters.expression {
+        condition = condition.add(expression_condition(expression, filters.case_sensitive, backend));
+    }
+
+    if let Some(title) = &filters.fuzzy_title
+        && backend == DbBackend::Postgres
+    {
+        condition = condition.add(Expr::col(note::Column::Title).binary(BinOper::Custom("%"), title));
+    }
+
+    condition
+}
+
+/// Builds the `pg_trgm` similarity of the note title to `title`, for
+/// ranking; filtering uses the indexable `%` operator instead.
+pub(crate) fn title_similarity(title: &str) -> SimpleExpr {
+    Func::cust("SIMILARITY").arg(Expr::col(note::Column::Title)).arg(title).into()
+}
+
+/// Builds the conditions that the lower-cased title starts with `prefix`,
+/// and that one of its later, space-separated words does.
+pub(crate) fn title_prefix_conditions(prefix: &str) -> (SimpleExpr, SimpleExpr) {
+    let escaped = escape_like(&prefix.to_lowercase());
+    let title = || Func::lower(Expr::col(note::Column::Title));
+
+    (
+        title().like(LikeExpr::new(format!("{escaped}%")).escape(LIKE_ESCAPE)),
+        title().like(LikeExpr::new(format!("% {escaped}%")).escape(LIKE_ESCAPE)),
+    )
+}
+
+/// Compiles a boolean filter expression into a condition.
+fn expression_condition(expression: &FilterExpr, case_sensitive: bool, backend: DbBackend) -> Condition {
+    match expression {
+        FilterExpr::All(operands) => operands.iter().fold(Condition::all(), |condition, operand| {
+            condition.add(expression_condition(operand, case_sensitive, backend))
+        }),
+        FilterExpr::Any(operands) => operands.iter().fold(Condition::any(), |condition, operand| {
+            condition.add(expression_condition(operand, case_sensitive, backend))
+        }),
+        FilterExpr::Not(operand) => expression_condition(operand, case_sensitive, backend).not(),
+        FilterExpr::Text(filter) => text_condition(filter, case_sensitive, backend),
+        FilterExpr::Id(comparison, id) => Condition::all().add(compare(Expr::col(note::Column::Id), *comparison, *id)),
+        FilterExpr::Timestamp(field, comparison, instant) => {
+            let column = match field {
+                TimestampField::CreatedAt => note::Column::CreatedAt,
+                TimestampField::UpdatedAt => note::Column::UpdatedAt,
+            };
+
+            Condition::all().add(compare_timestamp(column, *comparison, *instant, backend))
+        },
+        FilterExpr::Flag(flag, value) => Condition::all().add(flag.into_column().eq(*value)),
+    }
+}
+
+/// Compares a timestamp column against an instant.
+///
+/// SQLite stores timestamps as text in more than one layout (the column
+/// default writes `YYYY-MM-DD HH:MM:SS`, whilst bound values are RFC 3339),
+/// so both sides are normalised with `STRFTIME` before comparing.
+pub(crate) fn compare_timestamp(column: note::Column, comparison: Comparison, instant: DateTime<Utc>, backend: DbBackend) -> SimpleExpr {
+    match backend {
+        DbBackend::Sqlite => {
+            let normalised = Func::cust("STRFTIME").arg(SQLITE_TIMESTAMP_FORMAT).arg(Expr::col(column));
+            compare(normalised.into(), comparison, instant.format("%Y-%m-%d %H:%M:%S%.3f").to_string())
+        },
+        _ => compare(Expr::col(column), comparison, instant),
+    }
+}
+
+/// Builds the JSON value of the property `key`, or `NULL` if the note does
+/// not have it.
+pub(crate) fn property_value(key: &str, backend: DbBackend) -> SimpleExpr {
+    match backend {
+        DbBackend::Postgres => Expr::cust_with_exprs("$1 -> $2", [Expr::col(note::Column::Properties), Expr::val(key)]),
+        _ => Func::cust("JSON_EXTRACT")
+            .arg(Expr::col(note::Column::Properties))
+            .arg(json_path(key))
+            .into(),
+    }
+}
+
+/// Builds the condition that a note has the filter's property, with a value
+/// of the filter's type that compares as requested.
+fn property_condition(filter: &PropertyFilter, backend: DbBackend) -> Condition {
+    let value = property_value(&filter.key, backend);
+
+    let (kind, types, comparison): (SimpleExpr, &[&str], SimpleExpr) = match backend {
+        DbBackend::Postgres => {
+            let (types, json): (&[&str], serde_json::Value) = match &filter.value {
+                PropertyValue::String(text) => (&["string"], text.as_str().into()),
+                PropertyValue::Number(number) => (&["number"], (*number).into()),
+                PropertyValue::Boolean(flag) => (&["boolean"], (*flag).into()),
+            };
+
+            (
+                Func::cust("JSONB_TYPEOF").arg(value.clone()).into(),
+                types,
+                compare(value, filter.comparison, json),
+            )
+        },
+        _ => {
+            let kind = Func::cust("JSON_TYPE")
+                .arg(Expr::col(note::Column::Properties))
+                .arg(json_path(&filter.key));
+            let (types, comparison): (&[&str], SimpleExpr) = match &filter.value {
+                PropertyValue::String(text) => (&["text"], compare(value, filter.comparison, text.as_str())),
+                PropertyValue::Number(number) => (&["integer", "real"], compare(value, filter.comparison, *number)),
+                PropertyValue::Boolean(flag) => (&["true", "false"], compare(value, filter.comparison, i64::from(*flag))),
+            };
+
+            (kind.into(), types, comparison)
+        },
+    };
+
+    Condition::all().add(kind.is_in(types.iter().copied())).add(comparison)
+}
+
+/// Builds the SQLite JSON path of a top-level key, quoted so that `-` is
+/// read literally.
+fn json_path(key: &str) -> String {
+    format!("$.\"{key}\"")
+}
+
+/// Compares an expression against a value.
+fn compare<V: Into<Value>>(left: SimpleExpr, comparison: Comparison, value: V) -> SimpleExpr {
+    match comparison {
+        Comparison::Equal => left.eq(value),
+        Comparison::Greater => left.gt(value),
+        Comparison::GreaterOrEqual => left.gte(value),
+        Comparison::Less => left.lt(value),
+        Comparison::LessOrEqual => left.lte(value),
+    }
+}
+
+/// Builds the condition matching a single text filter, ignoring negation.
+fn text_condition(filter: &TextFilter, case_sensitive: bool, backend: DbBackend) -> Condition {
+    let column = match filter.field {
+        TextField::Title => note::Column::Title,
+        TextField::Content => note::Column::Content,
+    };
+
+    let expr = match (case_sensitive, filter.matching) {
+        (false, TextMatch::Exact) => Func::lower(Expr::col(column)).eq(filter.pattern.to_lowercase()),
+        (false, matching) => {
+            let escaped = escape_like(&filter.pattern.to_lowercase());
+            let pattern = match matching {
+                TextMatch::Prefix => format!("{escaped}%"),
+                _ => format!("%{escaped}%"),
+            };
+
+            Func::lower(Expr::col(column)).like(LikeExpr::new(pattern).escape(LIKE_ESCAPE))
+        },
+        (true, TextMatch::Exact) => Expr::col(column).eq(filter.pattern.as_str()),
+        (true, TextMatch::Contains) => {
+            let position = match backend {
+                DbBackend::Postgres => "STRPOS",
+                _ => "INSTR",
+            };
+
+            Func::cust(position).arg(Expr::col(column)).arg(filter.pattern.as_str()).gt(0)
+        },
+        (true, TextMatch::Prefix) => {
+            let length = filter.pattern.chars().count() as i64;
+
+            Func::cust("SUBSTR").arg(Expr::col(column)).arg(1).arg(length).eq(filter.pattern.as_str())
+        },
+    };
+
+    Condition::all().add(expr)
+}
+
+/// Escapes `LIKE` wildcards and the escape character itself.
+fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for character in value.chars() {
+        if matches!(character, '%' | '_' | LIKE_ESCAPE) {
+            escaped.push(LIKE_ESCAPE);
+        }
+        escaped.push(character);
+    }
+
+    escaped
+}
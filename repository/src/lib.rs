@@ -1,11 +1,22 @@
 //! Data access layer for the notes application.
 //!
 //! This crate provides the [`DatabaseManager`](database::DatabaseManager) for managing
-//! database connections, the [`NoteRepository`](note::NoteRepository) trait for abstracting
-//! persistence operations, and its concrete implementation
-//! [`NoteRepositoryImpl`](note::NoteRepositoryImpl).
+//! database connections and one repository trait per resource, each with a
+//! SeaORM-backed implementation: [`NoteRepository`](note::NoteRepository),
+//! [`ReminderRepository`](reminder::ReminderRepository),
+//! [`TaskRepository`](task::TaskRepository),
+//! [`SavedSearchRepository`](saved_search::SavedSearchRepository),
+//! [`TemplateRepository`](template::TemplateRepository), and
+//! [`PropertySchemaRepository`](property_schema::PropertySchemaRepository).
 
 pub mod database;
 pub mod error;
+mod filter;
 pub mod note;
+pub mod property_schema;
+pub mod reminder;
+pub mod saved_search;
 mod sort;
+pub mod task;
+pub mod template;
+mod trigram;
@@ -6,22 +6,34 @@
 use chrono::Utc;
 use model::{
     dto::{
-        note::{CreateNoteRequest, NoteResponse, UpdateNoteRequest},
+        datetime::DateDisplay,
+        idempotency::{IdempotencyRecord, IdempotentOutcome},
+        note::{CreateNoteRequest, NoteFlag, NoteResponse, NoteSuggestion, SourceAction, SuggestQuery, UpdateNoteRequest},
         pagination::{PageInfo, PaginatedResponse, SearchParams},
     },
-    entity::note,
+    entity::{idempotency_key, note},
 };
 use sea_orm::{
-    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DatabaseTransaction, DeleteResult, EntityTrait, Order, PaginatorTrait,
-    QueryFilter, QueryOrder, Select, TransactionTrait,
+    ActiveModelTrait,
+    ActiveValue::Set,
+    ColumnTrait, ConnectionTrait, DatabaseConnection, DatabaseTransaction, DbBackend, DbErr, DeleteResult, EntityTrait, Order, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect, Select, SqlErr, TransactionTrait,
+    sea_query::{Expr, ExprTrait, Func, SimpleExpr},
 };
+use serde_json::{Map, Value};
 use std::future::Future;
 
 use crate::{
     error::NoteRepositoryError,
-    sort::{IntoColumn, IntoOrder},
+    filter::{note_condition, title_prefix_conditions, title_similarity},
+    sort::{IntoColumn, IntoOrder, IntoSortExpr},
+    trigram::{SIMILARITY_THRESHOLD, Trigrams},
 };
 
+/// Number of candidate titles fetched at a time by a fuzzy title search on
+/// backends without `pg_trgm`.
+const FUZZY_SCAN_BATCH: u64 = 1_000;
+
 /// Trait abstracting CRUD operations for notes.
 ///
 /// Implementations must be [`Send`], [`Sync`], [`Clone`], and `'static` so
@@ -30,17 +42,55 @@ pub trait NoteRepository: Send + Sync + Clone + 'static {
     /// Persists a new note and returns its full representation.
     fn create(&self, req: CreateNoteRequest) -> impl Future<Output = Result<NoteResponse, NoteRepositoryError>> + Send;
 
+    /// Persists a new note and stores its serialised response under the
+    /// given idempotency key in the same transaction.
+    ///
+    /// If an unexpired record for the key already exists, nothing is
+    /// persisted and the stored response is returned instead; the caller is
+    /// responsible for comparing fingerprints.
+    fn create_idempotent(
+        &self,
+        req: CreateNoteRequest,
+        record: IdempotencyRecord,
+    ) -> impl Future<Output = Result<IdempotentOutcome<NoteResponse>, NoteRepositoryError>> + Send;
+
     /// Retrieves a single note by its primary key.
     fn find_by_id(&self, id: i64) -> impl Future<Output = Result<NoteResponse, NoteRepositoryError>> + Send;
 
+    /// Returns those of the given titles that at least one note already has.
+    fn find_taken_titles(&self, titles: Vec<String>) -> impl Future<Output = Result<Vec<String>, NoteRepositoryError>> + Send;
+
     /// Returns a paginated list of notes matching the given search parameters.
     fn find_all(&self, parameters: SearchParams) -> impl Future<Output = Result<PaginatedResponse<NoteResponse>, NoteRepositoryError>> + Send;
 
+    /// Returns the titles of unarchived notes starting with, or containing a
+    /// word starting with, the query prefix; whole-title matches and shorter
+    /// titles first.
+    fn suggest(&self, query: SuggestQuery) -> impl Future<Output = Result<Vec<NoteSuggestion>, NoteRepositoryError>> + Send;
+
     /// Partially updates an existing note and returns its updated representation.
     fn update(&self, id: i64, req: UpdateNoteRequest) -> impl Future<Output = Result<NoteResponse, NoteRepositoryError>> + Send;
 
+    /// Appends the content of `sources`, in order and each preceded by
+    /// `separator`, to the note `target`, then archives or deletes the
+    /// sources, all in one transaction. Fails with
+    /// [`NoteRepositoryError::ContentTooLong`] if the merged content would
+    /// exceed `max_content_len` characters.
+    fn merge(
+        &self,
+        target: i64,
+        sources: Vec<i64>,
+        separator: String,
+        action: SourceAction,
+        max_content_len: usize,
+    ) -> impl Future<Output = Result<NoteResponse, NoteRepositoryError>> + Send;
+
     /// Deletes a note by its primary key.
     fn delete(&self, id: i64) -> impl Future<Output = Result<(), NoteRepositoryError>> + Send;
+
+    /// Sets a flag of an existing note and returns its updated
+    /// representation; `updated_at` is left untouched.
+    fn set_flag(&self, id: i64, flag: NoteFlag, value: bool) -> impl Future<Output = Result<NoteResponse, NoteRepositoryError>> + Send;
 }
 
 /// Concrete [`NoteRepository`] backed by a SeaORM [`DatabaseConnection`].
@@ -56,32 +106,21 @@ impl NoteRepositoryImpl {
         Self { database }
     }
 
-    /// Converts a SeaORM [`note::Model`] into a [`NoteResponse`] DTO.
-    fn to_response(&self, model: note::Model) -> NoteResponse {
-        NoteResponse {
-            id: model.id,
-            title: model.title,
-            content: model.content,
-            created_at: model.created_at.into(),
-            updated_at: model.updated_at.into(),
-        }
-    }
-
     /// Builds a filtered and sorted [`Select`] query from the given
     /// [`SearchParams`].
     ///
-    /// Applies an optional title substring filter and the caller-supplied sort
-    /// fields in order. Falls back to ascending ID order when no sort fields
-    /// are present.
+    /// Applies the validated filters and the caller-supplied sort fields in
+    /// order. Falls back to ascending ID order when no sort fields are
+    /// present. On PostgreSQL, fuzzy searches list the most similar titles
+    /// first.
     fn build_note_query(&self, parameters: &SearchParams) -> Select<note::Entity> {
-        let mut query = note::Entity::find();
-
-        if let Some(ref title) = parameters.title {
-            query = query.filter(note::Column::Title.contains(title.as_str()));
-        }
+        let backend = self.database.get_database_backend();
+        let mut query = note::Entity::find().filter(note_condition(&parameters.filters, backend));
 
-        if let Some(ref content) = parameters.content {
-            query = query.filter(note::Column::Content.contains(content.as_str()));
+        if let Some(title) = &parameters.filters.fuzzy_title
+            && backend == DbBackend::Postgres
+        {
+            query = query.order_by(title_similarity(title), Order::Desc);
         }
 
         if parameters.sort_fields.is_empty() {
@@ -89,12 +128,67 @@ impl NoteRepositoryImpl {
         }
 
         for sort_field in &parameters.sort_fields {
-            query = query.order_by(sort_field.name.into_column(), sort_field.direction.into_order());
+            query = query.order_by(sort_field.name.into_sort_expr(backend), sort_field.direction.into_order());
         }
 
         query
     }
 
+    /// Lists a page of a fuzzy title search on backends without `pg_trgm`.
+    ///
+    /// Fetches the ID and title of the notes passing the other filters, in
+    /// the requested order (ties broken by ID, so that batches do not
+    /// overlap) and [`FUZZY_SCAN_BATCH`] at a time, and ranks them by trigram
+    /// similarity in-process; equally similar notes keep the requested order.
+    /// Only the IDs of similar enough notes are kept, and only the page
+    /// itself is then loaded in full.
+    async fn find_all_fuzzy(&self, parameters: &SearchParams, title: &str) -> Result<PaginatedResponse<NoteResponse>, NoteRepositoryError> {
+        let page = parameters.parsed_page;
+        let size = parameters.parsed_size;
+
+        let mut candidates = self
+            .build_note_query(parameters)
+            .order_by(note::Column::Id, Order::Asc)
+            .select_only()
+            .column(note::Column::Id)
+            .column(note::Column::Title)
+            .into_tuple::<(i64, String)>()
+            .paginate(&self.database, FUZZY_SCAN_BATCH);
+
+        let wanted = Trigrams::new(title);
+        let mut ranked: Vec<(i64, f64)> = Vec::new();
+        while let Some(batch) = candidates.fetch_and_next().await? {
+            ranked.extend(
+                batch
+                    .into_iter()
+                    .map(|(id, candidate)| (id, wanted.similarity(&Trigrams::new(&candidate))))
+                    .filter(|(_, similarity)| *similarity >= SIMILARITY_THRESHOLD),
+            );
+        }
+        ranked.sort_by(|left, right| right.1.total_cmp(&left.1));
+
+        let total = ranked.len() as u64;
+        let ids: Vec<i64> = ranked
+            .iter()
+            .skip(((page - 1) * size) as usize)
+            .take(size as usize)
+            .map(|(id, _)| *id)
+            .collect();
+
+        let mut models = note::Entity::find()
+            .filter(note::Column::Id.is_in(ids.iter().copied()))
+            .all(&self.database)
+            .await?;
+        models.sort_by_key(|model| ids.iter().position(|id| *id == model.id));
+
+        tracing::debug!(total, count = models.len(), "Fuzzy query completed");
+
+        Ok(PaginatedResponse {
+            notes: models.into_iter().map(to_response).collect(),
+            page: Self::build_page_info(page, size, total),
+        })
+    }
+
     /// Constructs a [`PageInfo`] from pagination state and the total element
     /// count.
     fn build_page_info(page: u64, size: u64, total: u64) -> PageInfo {
@@ -107,17 +201,68 @@ impl NoteRepositoryImpl {
         }
     }
 
-    /// Fetches a note by ID within an active transaction and returns it as an
-    /// [`ActiveModel`](note::ActiveModel) ready for mutation.
+    /// Looks up an unexpired idempotency record by key.
+    async fn find_idempotency_key<C: ConnectionTrait>(key: &str, connection: &C) -> Result<Option<idempotency_key::Model>, NoteRepositoryError> {
+        let record = idempotency_key::Entity::find_by_id(key.to_owned())
+            .filter(idempotency_key::Column::ExpiresAt.gt(Utc::now()))
+            .one(connection)
+            .await?;
+
+        Ok(record)
+    }
+
+    /// Inserts the note and its idempotency record in one transaction, unless
+    /// an unexpired record for the key already exists.
     ///
-    /// Returns [`NoteRepositoryError::NotFound`] when no matching row exists.
-    async fn find_note_in_transaction(&self, id: i64, transaction: &DatabaseTransaction) -> Result<note::ActiveModel, NoteRepositoryError> {
-        let model = note::Entity::find_by_id(id)
-            .one(transaction)
-            .await?
-            .ok_or(NoteRepositoryError::NotFound(id))?;
+    /// Expired records are purged first so that their keys can be reused.
+    async fn insert_idempotent(
+        &self,
+        req: CreateNoteRequest,
+        record: &IdempotencyRecord,
+    ) -> Result<IdempotentOutcome<NoteResponse>, NoteRepositoryError> {
+        let transaction = self.database.begin().await?;
+
+        let purged = idempotency_key::Entity::delete_many()
+            .filter(idempotency_key::Column::ExpiresAt.lte(Utc::now()))
+            .exec(&transaction)
+            .await?;
 
-        Ok(model.into())
+        if purged.rows_affected > 0 {
+            tracing::debug!(count = purged.rows_affected, "Expired idempotency keys purged");
+        }
+
+        if let Some(existing) = Self::find_idempotency_key(&record.key, &transaction).await? {
+            transaction.commit().await?;
+            return Ok(IdempotentOutcome::Replayed(existing.into()));
+        }
+
+        let new_note = note::ActiveModel {
+            title: Set(req.title),
+            content: Set(req.content),
+            properties: Set(Value::Object(req.properties)),
+            ..Default::default()
+        };
+
+        let response = to_response(new_note.insert(&transaction).await?);
+        let body = DateDisplay::STORED
+            .sync_scope(|| serde_json::to_string(&response))
+            .map_err(|err| DbErr::Custom(format!("Failed to serialise response: {err}")))?;
+
+        let new_record = idempotency_key::ActiveModel {
+            key: Set(record.key.clone()),
+            fingerprint: Set(record.fingerprint.clone()),
+            status_code: Set(i16::try_from(record.status_code).unwrap_or(i16::MAX)),
+            response_body: Set(body),
+            expires_at: Set(record.expires_at),
+            ..Default::default()
+        };
+
+        new_record.insert(&transaction).await?;
+        transaction.commit().await?;
+
+        tracing::debug!(id = response.id, "Note inserted with idempotency key");
+
+        Ok(IdempotentOutcome::Created(response))
     }
 
     /// Applies the fields from an [`UpdateNoteRequest`] to an active model,
@@ -133,6 +278,10 @@ impl NoteRepositoryImpl {
         if let Some(content) = req.content {
             active.content = Set(content);
         }
+
+        if let Some(properties) = req.properties {
+            active.properties = Set(Value::Object(properties));
+        }
     }
 }
 
@@ -143,13 +292,36 @@ impl NoteRepository for NoteRepositoryImpl {
         let new_note = note::ActiveModel {
             title: Set(req.title),
             content: Set(req.content),
+            properties: Set(Value::Object(req.properties)),
             ..Default::default()
         };
 
         let note_model: note::Model = new_note.insert(&self.database).await?;
         tracing::debug!(id = note_model.id, "Note inserted");
 
-        Ok(self.to_response(note_model))
+        Ok(to_response(note_model))
+    }
+
+    /// Inserts a new note guarded by an idempotency key. A concurrent request
+    /// that claimed the same key first causes a unique-constraint violation,
+    /// in which case the record it stored is returned.
+    #[tracing::instrument(skip_all)]
+    async fn create_idempotent(
+        &self,
+        req: CreateNoteRequest,
+        record: IdempotencyRecord,
+    ) -> Result<IdempotentOutcome<NoteResponse>, NoteRepositoryError> {
+        match self.insert_idempotent(req, &record).await {
+            Err(NoteRepositoryError::DatabaseError(err)) if matches!(err.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+                tracing::debug!("Idempotency key claimed concurrently, replaying stored response");
+
+                match Self::find_idempotency_key(&record.key, &self.database).await? {
+                    Some(existing) => Ok(IdempotentOutcome::Replayed(existing.into())),
+                    None => Err(NoteRepositoryError::DatabaseError(err)),
+                }
+            },
+            result => result,
+        }
     }
 
     /// Fetches a single note by ID, returning [`NoteRepositoryError::NotFound`]
@@ -163,7 +335,24 @@ impl NoteRepository for NoteRepositoryImpl {
             .await?
             .ok_or(NoteRepositoryError::NotFound(id))?;
 
-        Ok(self.to_response(note_model))
+        Ok(to_response(note_model))
+    }
+
+    /// Fetches the distinct titles among the given ones in a single query.
+    #[tracing::instrument(skip_all)]
+    async fn find_taken_titles(&self, titles: Vec<String>) -> Result<Vec<String>, NoteRepositoryError> {
+        tracing::debug!(count = titles.len(), "Fetching taken titles");
+
+        let taken = note::Entity::find()
+            .select_only()
+            .column(note::Column::Title)
+            .filter(note::Column::Title.is_in(titles))
+            .distinct()
+            .into_tuple()
+            .all(&self.database)
+            .await?;
+
+        Ok(taken)
     }
 
     /// Queries notes with optional filtering and caller-specified ordering,
@@ -175,13 +364,19 @@ impl NoteRepository for NoteRepositoryImpl {
 
         tracing::debug!(page, size, "Fetching paginated notes");
 
+        if let Some(title) = &parameters.filters.fuzzy_title
+            && self.database.get_database_backend() != DbBackend::Postgres
+        {
+            return self.find_all_fuzzy(&parameters, title).await;
+        }
+
         let paginator = self.build_note_query(&parameters).paginate(&self.database, size);
         let total = paginator.num_items().await?;
         let models = paginator.fetch_page(page - 1).await?;
 
         tracing::debug!(total, count = models.len(), "Query completed");
 
-        let notes = models.into_iter().map(|m| self.to_response(m)).collect();
+        let notes = models.into_iter().map(to_response).collect();
 
         Ok(PaginatedResponse {
             notes,
@@ -189,6 +384,33 @@ impl NoteRepository for NoteRepositoryImpl {
         })
     }
 
+    /// Ranks titles starting with the prefix before those with a later word
+    /// starting with it, then shorter titles first.
+    #[tracing::instrument(skip_all)]
+    async fn suggest(&self, query: SuggestQuery) -> Result<Vec<NoteSuggestion>, NoteRepositoryError> {
+        let prefix = query.prefix.unwrap_or_default();
+        let (starts, word_starts) = title_prefix_conditions(&prefix);
+        let rank: SimpleExpr = Expr::case(starts.clone(), 0).finally(1).into();
+
+        tracing::debug!(limit = query.parsed_limit, "Suggesting titles");
+
+        let suggestions: Vec<(i64, String)> = note::Entity::find()
+            .select_only()
+            .column(note::Column::Id)
+            .column(note::Column::Title)
+            .filter(note::Column::Archived.eq(false))
+            .filter(starts.or(word_starts))
+            .order_by(rank, Order::Asc)
+            .order_by(Func::cust("LENGTH").arg(Expr::col(note::Column::Title)), Order::Asc)
+            .order_by(note::Column::Title, Order::Asc)
+            .limit(query.parsed_limit)
+            .into_tuple()
+            .all(&self.database)
+            .await?;
+
+        Ok(suggestions.into_iter().map(|(id, title)| NoteSuggestion { id, title }).collect())
+    }
+
     /// Updates a note inside a transaction, touching only the fields present
     /// in the request, and stamps the current UTC time on `updated_at`.
     #[tracing::instrument(skip_all)]
@@ -196,7 +418,7 @@ impl NoteRepository for NoteRepositoryImpl {
         tracing::debug!(id, "Updating note");
 
         let transaction = self.database.begin().await?;
-        let mut active = self.find_note_in_transaction(id, &transaction).await?;
+        let mut active = find_note_in_transaction(id, &transaction).await?;
 
         Self::apply_update_fields(&mut active, req);
 
@@ -205,7 +427,7 @@ impl NoteRepository for NoteRepositoryImpl {
 
         tracing::debug!(id, "Note updated");
 
-        Ok(self.to_response(updated))
+        Ok(to_response(updated))
     }
 
     /// Deletes a note by ID, returning [`NoteRepositoryError::NotFound`] if no
@@ -222,4 +444,110 @@ impl NoteRepository for NoteRepositoryImpl {
 
         Ok(())
     }
+
+    /// Loads the target and the sources inside a transaction, so that the
+    /// merged content and the removal of the sources are applied together
+    /// or not at all. The target keeps its title and properties and has its
+    /// `updated_at` stamped.
+    #[tracing::instrument(skip_all)]
+    async fn merge(
+        &self,
+        target: i64,
+        sources: Vec<i64>,
+        separator: String,
+        action: SourceAction,
+        max_content_len: usize,
+    ) -> Result<NoteResponse, NoteRepositoryError> {
+        tracing::debug!(target, ?sources, ?action, "Merging notes");
+
+        let transaction = self.database.begin().await?;
+        let mut active = find_note_in_transaction(target, &transaction).await?;
+        let found = note::Entity::find()
+            .filter(note::Column::Id.is_in(sources.iter().copied()))
+            .all(&transaction)
+            .await?;
+
+        let mut content = active.content.take().unwrap_or_default();
+        for id in &sources {
+            let source = found.iter().find(|source| source.id == *id).ok_or(NoteRepositoryError::NotFound(*id))?;
+            content.push_str(&separator);
+            content.push_str(&source.content);
+        }
+
+        if content.chars().count() > max_content_len {
+            return Err(NoteRepositoryError::ContentTooLong { max: max_content_len });
+        }
+
+        active.content = Set(content);
+        active.updated_at = Set(Utc::now());
+        let merged = active.update(&transaction).await?;
+
+        let removed = note::Column::Id.is_in(sources.iter().copied());
+        let affected = match action {
+            SourceAction::Archive => {
+                note::Entity::update_many()
+                    .col_expr(note::Column::Archived, Expr::value(true))
+                    .filter(removed)
+                    .exec(&transaction)
+                    .await?
+                    .rows_affected
+            },
+            SourceAction::Delete => note::Entity::delete_many().filter(removed).exec(&transaction).await?.rows_affected,
+        };
+        transaction.commit().await?;
+
+        tracing::debug!(target, affected, "Notes merged");
+
+        Ok(to_response(merged))
+    }
+
+    /// Sets a single flag column inside a transaction.
+    #[tracing::instrument(skip_all)]
+    async fn set_flag(&self, id: i64, flag: NoteFlag, value: bool) -> Result<NoteResponse, NoteRepositoryError> {
+        tracing::debug!(id, %flag, value, "Setting note flag");
+
+        let transaction = self.database.begin().await?;
+        let mut active = find_note_in_transaction(id, &transaction).await?;
+
+        active.set(flag.into_column(), value.into());
+
+        let updated = active.update(&transaction).await?;
+        transaction.commit().await?;
+
+        Ok(to_response(updated))
+    }
+}
+
+/// Converts a SeaORM [`note::Model`] into a [`NoteResponse`] DTO.
+pub(crate) fn to_response(model: note::Model) -> NoteResponse {
+    NoteResponse {
+        id: model.id,
+        title: model.title,
+        content: model.content,
+        created_at: model.created_at.into(),
+        updated_at: model.updated_at.into(),
+        pinned: model.pinned,
+        starred: model.starred,
+        archived: model.archived,
+        remind_at: model.remind_at.map(Into::into),
+        recurrence: model.recurrence,
+        properties: match model.properties {
+            Value::Object(properties) => properties,
+            _ => Map::new(),
+        },
+        highlights: None,
+    }
+}
+
+/// Fetches a note by ID within an active transaction and returns it as an
+/// [`ActiveModel`](note::ActiveModel) ready for mutation.
+///
+/// Returns [`NoteRepositoryError::NotFound`] when no matching row exists.
+pub(crate) async fn find_note_in_transaction(id: i64, transaction: &DatabaseTransaction) -> Result<note::ActiveModel, NoteRepositoryError> {
+    let model = note::Entity::find_by_id(id)
+        .one(transaction)
+        .await?
+        .ok_or(NoteRepositoryError::NotFound(id))?;
+
+    Ok(model.into())
 }
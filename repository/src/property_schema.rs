@@ -0,0 +1,114 @@
+//! Property schema repository trait and its SeaORM-backed implementation.
+//!
+//! The [`PropertySchemaRepository`] trait defines the persistence contract
+//! for the declared value types of note properties, whilst
+//! [`PropertySchemaRepositoryImpl`] fulfils it using a
+//! [`DatabaseConnection`].
+
+use model::{
+    dto::property::{CreatePropertySchemaRequest, PropertySchemaResponse},
+    entity::property_schema,
+};
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, DatabaseConnection, DbErr, DeleteResult, EntityTrait, Order, QueryOrder, SqlErr};
+use std::future::Future;
+
+use crate::error::RepositoryError;
+
+/// Trait abstracting the persistence of property schemas.
+///
+/// Implementations must be [`Send`], [`Sync`], [`Clone`], and `'static` so
+/// that they can be shared across Axum handler threads.
+pub trait PropertySchemaRepository: Send + Sync + Clone + 'static {
+    /// Persists a property schema; fails with
+    /// [`RepositoryError::Conflict`] if the key already has one.
+    fn create(&self, req: CreatePropertySchemaRequest) -> impl Future<Output = Result<PropertySchemaResponse, RepositoryError>> + Send;
+
+    /// Returns every property schema, ordered by key.
+    fn find_all(&self) -> impl Future<Output = Result<Vec<PropertySchemaResponse>, RepositoryError>> + Send;
+
+    /// Deletes a property schema by its primary key.
+    fn delete(&self, id: i64) -> impl Future<Output = Result<(), RepositoryError>> + Send;
+}
+
+/// Concrete [`PropertySchemaRepository`] backed by a SeaORM [`DatabaseConnection`].
+#[derive(Clone)]
+pub struct PropertySchemaRepositoryImpl {
+    /// The SeaORM database connection used for all queries.
+    database: DatabaseConnection,
+}
+
+impl PropertySchemaRepositoryImpl {
+    /// Creates a new [`PropertySchemaRepositoryImpl`] wrapping the given database connection.
+    pub fn new(database: DatabaseConnection) -> Self {
+        Self { database }
+    }
+
+    /// Converts a [`property_schema::Model`] into a
+    /// [`PropertySchemaResponse`], decoding the stored type.
+    fn to_response(model: property_schema::Model) -> Result<PropertySchemaResponse, RepositoryError> {
+        let kind = model
+            .r#type
+            .parse()
+            .map_err(|err| DbErr::Custom(format!("Invalid stored property type: {err}")))?;
+
+        Ok(PropertySchemaResponse {
+            id: model.id,
+            key: model.key,
+            kind,
+            created_at: model.created_at.into(),
+        })
+    }
+}
+
+impl PropertySchemaRepository for PropertySchemaRepositoryImpl {
+    /// Inserts a property schema, translating a unique-key violation into
+    /// [`RepositoryError::Conflict`].
+    #[tracing::instrument(skip_all)]
+    async fn create(&self, req: CreatePropertySchemaRequest) -> Result<PropertySchemaResponse, RepositoryError> {
+        let new_schema = property_schema::ActiveModel {
+            key: Set(req.key.clone()),
+            r#type: Set(req.kind.to_string()),
+            ..Default::default()
+        };
+
+        let model = match new_schema.insert(&self.database).await {
+            Err(err) if matches!(err.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+                return Err(RepositoryError::Conflict(format!("Property '{}' already has a schema", req.key)));
+            },
+            result => result?,
+        };
+
+        tracing::debug!(id = model.id, "Property schema inserted");
+
+        Self::to_response(model)
+    }
+
+    /// Fetches every property schema ordered by key.
+    #[tracing::instrument(skip_all)]
+    async fn find_all(&self) -> Result<Vec<PropertySchemaResponse>, RepositoryError> {
+        let models = property_schema::Entity::find()
+            .order_by(property_schema::Column::Key, Order::Asc)
+            .all(&self.database)
+            .await?;
+
+        models.into_iter().map(Self::to_response).collect()
+    }
+
+    /// Deletes a property schema, returning [`RepositoryError::NotFound`] if
+    /// no row was affected. Notes keep their values for the key.
+    #[tracing::instrument(skip_all)]
+    async fn delete(&self, id: i64) -> Result<(), RepositoryError> {
+        tracing::debug!(id, "Deleting property schema");
+
+        let delete_result: DeleteResult = property_schema::Entity::delete_by_id(id).exec(&self.database).await?;
+
+        if delete_result.rows_affected == 0 {
+            return Err(RepositoryError::NotFound {
+                entity: "Property schema".into(),
+                id,
+            });
+        }
+
+        Ok(())
+    }
+}
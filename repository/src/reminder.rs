@@ -0,0 +1,153 @@
+//! Note reminder repository trait and its SeaORM-backed implementation.
+//!
+//! The [`ReminderRepository`] trait defines the persistence contract for the
+//! reminder columns of notes, whilst [`ReminderRepositoryImpl`] fulfils it
+//! using a [`DatabaseConnection`].
+
+use chrono::{DateTime, Utc};
+use model::{
+    dto::{note::NoteResponse, pagination::Comparison, reminder::ReminderResponse},
+    entity::note,
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, Order, QueryFilter, QueryOrder, QuerySelect, TransactionTrait,
+    sea_query::Expr,
+};
+use std::future::Future;
+
+use crate::{
+    error::NoteRepositoryError,
+    filter::compare_timestamp,
+    note::{find_note_in_transaction, to_response},
+};
+
+/// Trait abstracting the persistence of note reminders.
+///
+/// Implementations must be [`Send`], [`Sync`], [`Clone`], and `'static` so
+/// that they can be shared across Axum handler threads.
+pub trait ReminderRepository: Send + Sync + Clone + 'static {
+    /// Sets or, given [`None`], clears the reminder of an existing note and
+    /// returns its updated representation; `updated_at` is left untouched.
+    fn set_reminder(
+        &self,
+        id: i64,
+        remind_at: Option<DateTime<Utc>>,
+        recurrence: Option<String>,
+    ) -> impl Future<Output = Result<NoteResponse, NoteRepositoryError>> + Send;
+
+    /// Returns up to `limit` pending reminders, soonest first, optionally
+    /// only those due strictly before `before`.
+    fn find_reminders(
+        &self,
+        before: Option<DateTime<Utc>>,
+        limit: u64,
+    ) -> impl Future<Output = Result<Vec<ReminderResponse>, NoteRepositoryError>> + Send;
+
+    /// Moves a reminder that fired at `fired` on to `next` (clearing it when
+    /// [`None`]) with the remaining `recurrence`.
+    ///
+    /// Returns `false` without changing anything if the reminder is no longer
+    /// due at `fired`, i.e. it was rescheduled, cleared, or already advanced
+    /// by another instance.
+    fn advance_reminder(
+        &self,
+        id: i64,
+        fired: DateTime<Utc>,
+        next: Option<DateTime<Utc>>,
+        recurrence: Option<String>,
+    ) -> impl Future<Output = Result<bool, NoteRepositoryError>> + Send;
+}
+
+/// Concrete [`ReminderRepository`] backed by a SeaORM [`DatabaseConnection`].
+#[derive(Clone)]
+pub struct ReminderRepositoryImpl {
+    /// The SeaORM database connection used for all queries.
+    database: DatabaseConnection,
+}
+
+impl ReminderRepositoryImpl {
+    /// Creates a new [`ReminderRepositoryImpl`] wrapping the given database connection.
+    pub fn new(database: DatabaseConnection) -> Self {
+        Self { database }
+    }
+}
+
+impl ReminderRepository for ReminderRepositoryImpl {
+    /// Sets both reminder columns inside a transaction.
+    #[tracing::instrument(skip_all)]
+    async fn set_reminder(&self, id: i64, remind_at: Option<DateTime<Utc>>, recurrence: Option<String>) -> Result<NoteResponse, NoteRepositoryError> {
+        tracing::debug!(id, ?remind_at, ?recurrence, "Setting note reminder");
+
+        let transaction = self.database.begin().await?;
+        let mut active = find_note_in_transaction(id, &transaction).await?;
+
+        active.remind_at = Set(remind_at);
+        active.recurrence = Set(recurrence);
+
+        let updated = active.update(&transaction).await?;
+        transaction.commit().await?;
+
+        Ok(to_response(updated))
+    }
+
+    /// Fetches notes with a reminder, ordered by `remind_at` and then ID.
+    #[tracing::instrument(skip_all)]
+    async fn find_reminders(&self, before: Option<DateTime<Utc>>, limit: u64) -> Result<Vec<ReminderResponse>, NoteRepositoryError> {
+        let mut select = note::Entity::find().filter(note::Column::RemindAt.is_not_null());
+
+        if let Some(before) = before {
+            select = select.filter(compare_timestamp(
+                note::Column::RemindAt,
+                Comparison::Less,
+                before,
+                self.database.get_database_backend(),
+            ));
+        }
+
+        let models = select
+            .order_by(note::Column::RemindAt, Order::Asc)
+            .order_by(note::Column::Id, Order::Asc)
+            .limit(limit)
+            .all(&self.database)
+            .await?;
+
+        Ok(models
+            .into_iter()
+            .filter_map(|model| {
+                Some(ReminderResponse {
+                    note_id: model.id,
+                    title: model.title,
+                    remind_at: model.remind_at?.into(),
+                    recurrence: model.recurrence,
+                })
+            })
+            .collect())
+    }
+
+    /// Updates the reminder columns only where `remind_at` still equals
+    /// `fired`, so that concurrent schedulers fire each occurrence once.
+    /// The column is compared as stored rather than normalised like the
+    /// timestamp filters: `remind_at` is only ever written from bound values,
+    /// and SQLite's `STRFTIME` rounds to milliseconds, so a normalised
+    /// comparison could never match some sub-millisecond instants.
+    #[tracing::instrument(skip_all)]
+    async fn advance_reminder(
+        &self,
+        id: i64,
+        fired: DateTime<Utc>,
+        next: Option<DateTime<Utc>>,
+        recurrence: Option<String>,
+    ) -> Result<bool, NoteRepositoryError> {
+        tracing::debug!(id, %fired, ?next, "Advancing note reminder");
+
+        let result = note::Entity::update_many()
+            .col_expr(note::Column::RemindAt, Expr::value(next))
+            .col_expr(note::Column::Recurrence, Expr::value(recurrence))
+            .filter(note::Column::Id.eq(id))
+            .filter(note::Column::RemindAt.eq(fired))
+            .exec(&self.database)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+}
@@ -0,0 +1,131 @@
+//! Saved search repository trait and its SeaORM-backed implementation.
+//!
+//! The [`SavedSearchRepository`] trait defines the persistence contract for
+//! named searches, whilst [`SavedSearchRepositoryImpl`] fulfils it using a
+//! [`DatabaseConnection`]. The search parameters are stored as JSON.
+
+use model::{
+    dto::saved_search::{CreateSavedSearchRequest, SavedSearchResponse},
+    entity::saved_search,
+};
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, DatabaseConnection, DbErr, DeleteResult, EntityTrait, Order, QueryOrder, SqlErr};
+use std::future::Future;
+
+use crate::error::RepositoryError;
+
+/// Trait abstracting the persistence of saved searches.
+///
+/// Implementations must be [`Send`], [`Sync`], [`Clone`], and `'static` so
+/// that they can be shared across Axum handler threads.
+pub trait SavedSearchRepository: Send + Sync + Clone + 'static {
+    /// Persists a named search; fails with [`RepositoryError::Conflict`]
+    /// if the name is taken.
+    fn create(&self, req: CreateSavedSearchRequest) -> impl Future<Output = Result<SavedSearchResponse, RepositoryError>> + Send;
+
+    /// Returns every saved search, ordered by name.
+    fn find_all(&self) -> impl Future<Output = Result<Vec<SavedSearchResponse>, RepositoryError>> + Send;
+
+    /// Retrieves a single saved search by its primary key.
+    fn find_by_id(&self, id: i64) -> impl Future<Output = Result<SavedSearchResponse, RepositoryError>> + Send;
+
+    /// Deletes a saved search by its primary key.
+    fn delete(&self, id: i64) -> impl Future<Output = Result<(), RepositoryError>> + Send;
+}
+
+/// Concrete [`SavedSearchRepository`] backed by a SeaORM [`DatabaseConnection`].
+#[derive(Clone)]
+pub struct SavedSearchRepositoryImpl {
+    /// The SeaORM database connection used for all queries.
+    database: DatabaseConnection,
+}
+
+impl SavedSearchRepositoryImpl {
+    /// Creates a new [`SavedSearchRepositoryImpl`] wrapping the given database connection.
+    pub fn new(database: DatabaseConnection) -> Self {
+        Self { database }
+    }
+
+    /// Converts a [`saved_search::Model`] into a [`SavedSearchResponse`],
+    /// decoding the stored parameters.
+    fn to_response(model: saved_search::Model) -> Result<SavedSearchResponse, RepositoryError> {
+        let params = serde_json::from_str(&model.params).map_err(|err| DbErr::Json(format!("Invalid stored search parameters: {err}")))?;
+
+        Ok(SavedSearchResponse {
+            id: model.id,
+            name: model.name,
+            params,
+            created_at: model.created_at.into(),
+        })
+    }
+}
+
+impl SavedSearchRepository for SavedSearchRepositoryImpl {
+    /// Inserts a saved search, translating a unique-name violation into
+    /// [`RepositoryError::Conflict`].
+    #[tracing::instrument(skip_all)]
+    async fn create(&self, req: CreateSavedSearchRequest) -> Result<SavedSearchResponse, RepositoryError> {
+        let params = serde_json::to_string(&req.params).map_err(|err| DbErr::Json(format!("Failed to serialise search parameters: {err}")))?;
+        let new_search = saved_search::ActiveModel {
+            name: Set(req.name.clone()),
+            params: Set(params),
+            ..Default::default()
+        };
+
+        let model = match new_search.insert(&self.database).await {
+            Err(err) if matches!(err.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+                return Err(RepositoryError::Conflict(format!("A saved search named '{}' already exists", req.name)));
+            },
+            result => result?,
+        };
+
+        tracing::debug!(id = model.id, "Saved search inserted");
+
+        Self::to_response(model)
+    }
+
+    /// Fetches every saved search ordered by name.
+    #[tracing::instrument(skip_all)]
+    async fn find_all(&self) -> Result<Vec<SavedSearchResponse>, RepositoryError> {
+        let models = saved_search::Entity::find()
+            .order_by(saved_search::Column::Name, Order::Asc)
+            .all(&self.database)
+            .await?;
+
+        models.into_iter().map(Self::to_response).collect()
+    }
+
+    /// Fetches a single saved search by ID, returning
+    /// [`RepositoryError::NotFound`] if no matching row exists.
+    #[tracing::instrument(skip_all)]
+    async fn find_by_id(&self, id: i64) -> Result<SavedSearchResponse, RepositoryError> {
+        tracing::debug!(id, "Fetching saved search by ID");
+
+        let model = saved_search::Entity::find_by_id(id)
+            .one(&self.database)
+            .await?
+            .ok_or(RepositoryError::NotFound {
+                entity: "Saved search".into(),
+                id,
+            })?;
+
+        Self::to_response(model)
+    }
+
+    /// Deletes a saved search, returning
+    /// [`RepositoryError::NotFound`] if no row was affected.
+    #[tracing::instrument(skip_all)]
+    async fn delete(&self, id: i64) -> Result<(), RepositoryError> {
+        tracing::debug!(id, "Deleting saved search");
+
+        let delete_result: DeleteResult = saved_search::Entity::delete_by_id(id).exec(&self.database).await?;
+
+        if delete_result.rows_affected == 0 {
+            return Err(RepositoryError::NotFound {
+                entity: "Saved search".into(),
+                id,
+            });
+        }
+
+        Ok(())
+    }
+}
@@ -1,36 +1,69 @@
 //! Conversion traits bridging model sort types to SeaORM query primitives.
 //!
-//! [`IntoColumn`] converts a [`SortFieldName`] into the corresponding
-//! [`note::Column`], and [`IntoOrder`] converts a [`SortDirection`] into a
-//! SeaORM [`Order`]. Both traits are implemented here, keeping the mapping
-//! logic in one place and out of the repository method bodies.
+//! [`IntoColumn`] converts a [`NoteFlag`] into the corresponding
+//! [`note::Column`], [`IntoSortExpr`] converts a [`SortFieldName`] into the
+//! expression notes are ordered by, and [`IntoOrder`] converts a
+//! [`SortDirection`] into a SeaORM [`Order`]. All traits are implemented
+//! here, keeping the mapping logic in one place and out of the repository
+//! method bodies.
 
 use model::{
-    dto::pagination::{SortDirection, SortFieldName},
+    dto::{
+        note::NoteFlag,
+        pagination::{SortDirection, SortFieldName},
+    },
     entity::note,
 };
-use sea_orm::Order;
+use sea_orm::{
+    DbBackend, Order,
+    sea_query::{Expr, SimpleExpr},
+};
+
+use crate::filter::property_value;
 
-/// Converts a sort field name into the corresponding SeaORM column.
+/// Converts a flag into the corresponding SeaORM column.
 pub(crate) trait IntoColumn {
     /// Returns the SeaORM [`note::Column`] that corresponds to this field.
     fn into_column(self) -> note::Column;
 }
 
+/// Converts a sort field into the expression notes are ordered by.
+pub(crate) trait IntoSortExpr {
+    /// Returns the column or, for a custom property, the JSON value that
+    /// corresponds to this field on the given backend.
+    fn into_sort_expr(self, backend: DbBackend) -> SimpleExpr;
+}
+
 /// Converts a sort direction into a SeaORM [`Order`].
 pub(crate) trait IntoOrder {
     /// Returns the SeaORM [`Order`] that corresponds to this direction.
     fn into_order(self) -> Order;
 }
 
-impl IntoColumn for SortFieldName {
-    fn into_column(self) -> note::Column {
-        match self {
+impl IntoSortExpr for &SortFieldName {
+    fn into_sort_expr(self, backend: DbBackend) -> SimpleExpr {
+        let column = match self {
             SortFieldName::Id => note::Column::Id,
             SortFieldName::Title => note::Column::Title,
             SortFieldName::Content => note::Column::Content,
             SortFieldName::CreatedAt => note::Column::CreatedAt,
             SortFieldName::UpdatedAt => note::Column::UpdatedAt,
+            SortFieldName::Pinned => NoteFlag::Pinned.into_column(),
+            SortFieldName::Starred => NoteFlag::Starred.into_column(),
+            SortFieldName::Archived => NoteFlag::Archived.into_column(),
+            SortFieldName::Property(key) => return property_value(key, backend),
+        };
+
+        Expr::col(column)
+    }
+}
+
+impl IntoColumn for NoteFlag {
+    fn into_column(self) -> note::Column {
+        match self {
+            NoteFlag::Pinned => note::Column::Pinned,
+            NoteFlag::Starred => note::Column::Starred,
+            NoteFlag::Archived => note::Column::Archived,
         }
     }
 }
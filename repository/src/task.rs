@@ -0,0 +1,164 @@
+//! Task list repository trait and its SeaORM-backed implementation.
+//!
+//! The [`TaskRepository`] trait defines the persistence contract for the
+//! task list items in note content, whilst [`TaskRepositoryImpl`] fulfils it
+//! using a [`DatabaseConnection`].
+
+use chrono::Utc;
+use model::{
+    dto::task::{NoteTaskResponse, TaskResponse},
+    entity::note,
+    task,
+};
+use sea_orm::{
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, Order, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, sea_query::Expr,
+};
+use std::future::Future;
+
+use crate::error::RepositoryError;
+
+/// Number of notes scanned at a time when listing task list items across
+/// notes.
+const TASK_SCAN_BATCH: u64 = 100;
+
+/// Number of times a task update is retried when the note's content changes
+/// between reading and rewriting it.
+const MAX_TASK_UPDATE_ATTEMPTS: usize = 3;
+
+/// Trait abstracting the persistence of note task list items.
+///
+/// Implementations must be [`Send`], [`Sync`], [`Clone`], and `'static` so
+/// that they can be shared across Axum handler threads.
+pub trait TaskRepository: Send + Sync + Clone + 'static {
+    /// Returns the task list items of a note, in document order.
+    fn find_note_tasks(&self, id: i64) -> impl Future<Output = Result<Vec<NoteTaskResponse>, RepositoryError>> + Send;
+
+    /// Ticks, clears, or (given [`None`]) toggles the task list item at
+    /// `index` by rewriting the note's content, and returns the updated
+    /// item.
+    fn set_task(&self, id: i64, index: usize, done: Option<bool>) -> impl Future<Output = Result<NoteTaskResponse, RepositoryError>> + Send;
+
+    /// Returns up to `limit` task list items of unarchived notes, in note ID
+    /// and document order, optionally only ticked or open ones.
+    fn find_tasks(&self, done: Option<bool>, limit: u64) -> impl Future<Output = Result<Vec<TaskResponse>, RepositoryError>> + Send;
+}
+
+/// Concrete [`TaskRepository`] backed by a SeaORM [`DatabaseConnection`].
+#[derive(Clone)]
+pub struct TaskRepositoryImpl {
+    /// The SeaORM database connection used for all queries.
+    database: DatabaseConnection,
+}
+
+impl TaskRepositoryImpl {
+    /// Creates a new [`TaskRepositoryImpl`] wrapping the given database connection.
+    pub fn new(database: DatabaseConnection) -> Self {
+        Self { database }
+    }
+}
+
+impl TaskRepository for TaskRepositoryImpl {
+    /// Fetches the note's content and parses it.
+    #[tracing::instrument(skip_all)]
+    async fn find_note_tasks(&self, id: i64) -> Result<Vec<NoteTaskResponse>, RepositoryError> {
+        tracing::debug!(id, "Fetching note tasks");
+
+        let content: String = note::Entity::find_by_id(id)
+            .select_only()
+            .column(note::Column::Content)
+            .into_tuple()
+            .one(&self.database)
+            .await?
+            .ok_or_else(|| RepositoryError::NotFound { entity: "Note".into(), id })?;
+
+        Ok(task::parse(&content).into_iter().map(NoteTaskResponse::from).collect())
+    }
+
+    /// Rewrites the checkbox, stamping `updated_at` only if the item
+    /// actually changed. The update only applies if the content is still the
+    /// one the item was read from; otherwise a concurrent edit won, and the
+    /// note is read again, up to [`MAX_TASK_UPDATE_ATTEMPTS`] times.
+    #[tracing::instrument(skip_all)]
+    async fn set_task(&self, id: i64, index: usize, done: Option<bool>) -> Result<NoteTaskResponse, RepositoryError> {
+        tracing::debug!(id, index, ?done, "Updating note task");
+
+        for _ in 0..MAX_TASK_UPDATE_ATTEMPTS {
+            let model = note::Entity::find_by_id(id)
+                .one(&self.database)
+                .await?
+                .ok_or_else(|| RepositoryError::NotFound { entity: "Note".into(), id })?;
+
+            let (content, task) = task::set_done(&model.content, index, done).ok_or_else(|| RepositoryError::NotFound {
+                entity: format!("Task of note {id}"),
+                id: i64::try_from(index).unwrap_or(i64::MAX),
+            })?;
+
+            if content == model.content {
+                return Ok(task.into());
+            }
+
+            let result = note::Entity::update_many()
+                .col_expr(note::Column::Content, Expr::value(content))
+                .col_expr(note::Column::UpdatedAt, Expr::value(Utc::now()))
+                .filter(note::Column::Id.eq(id))
+                .filter(note::Column::Content.eq(model.content))
+                .exec(&self.database)
+                .await?;
+
+            if result.rows_affected > 0 {
+                return Ok(task.into());
+            }
+
+            tracing::debug!(id, index, "Note content changed concurrently, retrying task update");
+        }
+
+        Err(RepositoryError::Conflict(format!(
+            "Note {id} is being modified concurrently; retry the task update"
+        )))
+    }
+
+    /// Scans unarchived notes whose content contains a matching checkbox in
+    /// batches, parsing their task list items until `limit` are found.
+    #[tracing::instrument(skip_all)]
+    async fn find_tasks(&self, done: Option<bool>, limit: u64) -> Result<Vec<TaskResponse>, RepositoryError> {
+        let marks: &[&str] = match done {
+            None => &["[ ]", "[x]", "[X]"],
+            Some(true) => &["[x]", "[X]"],
+            Some(false) => &["[ ]"],
+        };
+        let has_checkbox = marks
+            .iter()
+            .fold(Condition::any(), |condition, mark| condition.add(note::Column::Content.contains(*mark)));
+
+        let mut paginator = note::Entity::find()
+            .filter(note::Column::Archived.eq(false))
+            .filter(has_checkbox)
+            .order_by(note::Column::Id, Order::Asc)
+            .paginate(&self.database, TASK_SCAN_BATCH);
+
+        let limit = usize::try_from(limit).unwrap_or(usize::MAX);
+        let mut tasks = Vec::new();
+
+        while tasks.len() < limit
+            && let Some(models) = paginator.fetch_and_next().await?
+        {
+            for model in models {
+                let found = task::parse(&model.content)
+                    .into_iter()
+                    .filter(|task| done.is_none_or(|done| task.done == done));
+
+                tasks.extend(found.map(|task| TaskResponse {
+                    note_id: model.id,
+                    note_title: model.title.clone(),
+                    index: task.index,
+                    text: task.text,
+                    done: task.done,
+                    line: task.line,
+                }));
+            }
+        }
+
+        tasks.truncate(limit);
+        Ok(tasks)
+    }
+}
@@ -0,0 +1,201 @@
+//! Note template repository trait and its SeaORM-backed implementation.
+//!
+//! The [`TemplateRepository`] trait defines the persistence contract for
+//! note templates, whilst [`TemplateRepositoryImpl`] fulfils it using a
+//! [`DatabaseConnection`].
+
+use chrono::Utc;
+use model::{
+    dto::template::{CreateTemplateRequest, TemplateResponse, UpdateTemplateRequest},
+    entity::template,
+    template::Pattern,
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, DatabaseConnection, DbErr, DeleteResult, EntityTrait, Order, QueryOrder, SqlErr, TransactionTrait,
+};
+use std::future::Future;
+
+use crate::error::RepositoryError;
+
+/// Trait abstracting the persistence of note templates.
+///
+/// Implementations must be [`Send`], [`Sync`], [`Clone`], and `'static` so
+/// that they can be shared across Axum handler threads.
+pub trait TemplateRepository: Send + Sync + Clone + 'static {
+    /// Persists a note template; fails with [`RepositoryError::Conflict`]
+    /// if the name is taken.
+    fn create(&self, req: CreateTemplateRequest) -> impl Future<Output = Result<TemplateResponse, RepositoryError>> + Send;
+
+    /// Returns every note template, ordered by name.
+    fn find_all(&self) -> impl Future<Output = Result<Vec<TemplateResponse>, RepositoryError>> + Send;
+
+    /// Retrieves a single note template by its primary key.
+    fn find_by_id(&self, id: i64) -> impl Future<Output = Result<TemplateResponse, RepositoryError>> + Send;
+
+    /// Partially updates a note template; fails with
+    /// [`RepositoryError::Conflict`] if the new name is taken.
+    fn update(&self, id: i64, req: UpdateTemplateRequest) -> impl Future<Output = Result<TemplateResponse, RepositoryError>> + Send;
+
+    /// Deletes a note template by its primary key.
+    fn delete(&self, id: i64) -> impl Future<Output = Result<(), RepositoryError>> + Send;
+}
+
+/// Concrete [`TemplateRepository`] backed by a SeaORM [`DatabaseConnection`].
+#[derive(Clone)]
+pub struct TemplateRepositoryImpl {
+    /// The SeaORM database connection used for all queries.
+    database: DatabaseConnection,
+}
+
+impl TemplateRepositoryImpl {
+    /// Creates a new [`TemplateRepositoryImpl`] wrapping the given database connection.
+    pub fn new(database: DatabaseConnection) -> Self {
+        Self { database }
+    }
+
+    /// Converts a [`template::Model`] into a [`TemplateResponse`], listing
+    /// the variables of the stored patterns.
+    fn to_response(model: template::Model) -> Result<TemplateResponse, RepositoryError> {
+        let mut variables: Vec<String> = Vec::new();
+
+        for stored in [&model.title, &model.content] {
+            let pattern: Pattern = stored
+                .parse()
+                .map_err(|err| DbErr::Custom(format!("Invalid stored template pattern: {err}")))?;
+
+            for name in pattern.variables() {
+                if !variables.iter().any(|known| known == name) {
+                    variables.push(name.to_owned());
+                }
+            }
+        }
+
+        Ok(TemplateResponse {
+            id: model.id,
+            name: model.name,
+            title: model.title,
+            content: model.content,
+            variables,
+            created_at: model.created_at.into(),
+            updated_at: model.updated_at.into(),
+        })
+    }
+}
+
+impl TemplateRepository for TemplateRepositoryImpl {
+    /// Inserts a note template, translating a unique-name violation into
+    /// [`RepositoryError::Conflict`].
+    #[tracing::instrument(skip_all)]
+    async fn create(&self, req: CreateTemplateRequest) -> Result<TemplateResponse, RepositoryError> {
+        let name = req.name.clone();
+        let new_template = template::ActiveModel {
+            name: Set(req.name),
+            title: Set(req.title),
+            content: Set(req.content),
+            ..Default::default()
+        };
+
+        let model = match new_template.insert(&self.database).await {
+            Err(err) if matches!(err.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+                return Err(RepositoryError::Conflict(format!("A template named '{name}' already exists")));
+            },
+            result => result?,
+        };
+
+        tracing::debug!(id = model.id, "Template inserted");
+
+        Self::to_response(model)
+    }
+
+    /// Fetches every note template ordered by name.
+    #[tracing::instrument(skip_all)]
+    async fn find_all(&self) -> Result<Vec<TemplateResponse>, RepositoryError> {
+        let models = template::Entity::find()
+            .order_by(template::Column::Name, Order::Asc)
+            .all(&self.database)
+            .await?;
+
+        models.into_iter().map(Self::to_response).collect()
+    }
+
+    /// Fetches a single note template by ID, returning
+    /// [`RepositoryError::NotFound`] if no matching row exists.
+    #[tracing::instrument(skip_all)]
+    async fn find_by_id(&self, id: i64) -> Result<TemplateResponse, RepositoryError> {
+        tracing::debug!(id, "Fetching template by ID");
+
+        let model = template::Entity::find_by_id(id)
+            .one(&self.database)
+            .await?
+            .ok_or(RepositoryError::NotFound {
+                entity: "Template".into(),
+                id,
+            })?;
+
+        Self::to_response(model)
+    }
+
+    /// Updates a note template inside a transaction, touching only the
+    /// fields present in the request, and stamps the current UTC time on
+    /// `updated_at`. A unique-name violation becomes
+    /// [`RepositoryError::Conflict`].
+    #[tracing::instrument(skip_all)]
+    async fn update(&self, id: i64, req: UpdateTemplateRequest) -> Result<TemplateResponse, RepositoryError> {
+        tracing::debug!(id, "Updating template");
+
+        let transaction = self.database.begin().await?;
+        let mut active: template::ActiveModel = template::Entity::find_by_id(id)
+            .one(&transaction)
+            .await?
+            .ok_or(RepositoryError::NotFound {
+                entity: "Template".into(),
+                id,
+            })?
+            .into();
+
+        active.updated_at = Set(Utc::now());
+
+        if let Some(name) = &req.name {
+            active.name = Set(name.clone());
+        }
+
+        if let Some(title) = req.title {
+            active.title = Set(title);
+        }
+
+        if let Some(content) = req.content {
+            active.content = Set(content);
+        }
+
+        let updated = match active.update(&transaction).await {
+            Err(err) if matches!(err.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+                let name = req.name.unwrap_or_default();
+                return Err(RepositoryError::Conflict(format!("A template named '{name}' already exists")));
+            },
+            result => result?,
+        };
+        transaction.commit().await?;
+
+        tracing::debug!(id, "Template updated");
+
+        Self::to_response(updated)
+    }
+
+    /// Deletes a note template, returning
+    /// [`RepositoryError::NotFound`] if no row was affected.
+    #[tracing::instrument(skip_all)]
+    async fn delete(&self, id: i64) -> Result<(), RepositoryError> {
+        tracing::debug!(id, "Deleting template");
+
+        let delete_result: DeleteResult = template::Entity::delete_by_id(id).exec(&self.database).await?;
+
+        if delete_result.rows_affected == 0 {
+            return Err(RepositoryError::NotFound {
+                entity: "Template".into(),
+                id,
+            });
+        }
+
+        Ok(())
+    }
+}
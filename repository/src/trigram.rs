@@ -0,0 +1,40 @@
+//! In-process trigram similarity, mirroring PostgreSQL's `pg_trgm`.
+//!
+//! Backends without `pg_trgm` rank fuzzy title searches with [`Trigrams`]
+//! instead. As in `pg_trgm`, text is lower-cased and split into words of
+//! alphanumeric characters, each word is padded with two spaces in front and
+//! one behind, and similarity is the number of shared trigrams divided by
+//! the number of distinct trigrams in either text.
+
+use std::collections::HashSet;
+
+/// The minimum similarity for a title to match a fuzzy search; the default
+/// `pg_trgm.similarity_threshold`, which the `%` operator applies on
+/// PostgreSQL.
+pub(crate) const SIMILARITY_THRESHOLD: f64 = 0.3;
+
+/// The set of trigrams of a text.
+pub(crate) struct Trigrams(HashSet<[char; 3]>);
+
+impl Trigrams {
+    /// Extracts the trigrams of `text`.
+    pub(crate) fn new(text: &str) -> Self {
+        let mut trigrams = HashSet::new();
+
+        for word in text.split(|character: char| !character.is_alphanumeric()).filter(|word| !word.is_empty()) {
+            let padded: Vec<char> = "  ".chars().chain(word.chars().flat_map(char::to_lowercase)).chain([' ']).collect();
+            trigrams.extend(padded.windows(3).map(|window| [window[0], window[1], window[2]]));
+        }
+
+        Self(trigrams)
+    }
+
+    /// Returns the similarity of two texts, from `0.0` (no shared trigrams)
+    /// to `1.0` (identical trigram sets).
+    pub(crate) fn similarity(&self, other: &Self) -> f64 {
+        let shared = self.0.intersection(&other.0).count();
+        let total = self.0.len() + other.0.len() - shared;
+
+        if total == 0 { 0.0 } else { shared as f64 / total as f64 }
+    }
+}
@@ -1,8 +1,9 @@
 //! Service-layer error types.
 //!
-//! [`ServiceError`] unifies validation failures, not-found conditions, and
-//! internal errors into a single enum that the controller layer can map to
-//! appropriate HTTP status codes.
+//! [`ServiceError`] unifies validation failures, not-found conditions,
+//! conflicts, semantically unprocessable requests, and internal errors into
+//! a single enum that the controller layer can map to appropriate HTTP
+//! status codes.
 
 use repository::error::{NoteRepositoryError, RepositoryError};
 use thiserror::Error;
@@ -23,6 +24,15 @@ pub enum ServiceError {
         id: i64,
     },
 
+    /// The request conflicts with existing data, e.g. a duplicate name.
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    /// A well-formed request could not be processed, e.g. because an
+    /// idempotency key was reused with a different request body.
+    #[error("Unprocessable request: {0}")]
+    Unprocessable(String),
+
     /// An unexpected internal error occurred.
     #[error("Internal error: {0}")]
     Internal(String),
@@ -32,6 +42,8 @@ impl From<RepositoryError> for ServiceError {
     fn from(error: RepositoryError) -> Self {
         match error {
             RepositoryError::NotFound { entity, id } => ServiceError::NotFound { entity, id },
+            RepositoryError::Conflict(message) => ServiceError::Conflict(message),
+            RepositoryError::Validation(message) => ServiceError::Validation(message),
             RepositoryError::DatabaseError(e) => ServiceError::Internal(e.to_string()),
         }
     }
@@ -41,6 +53,7 @@ impl From<NoteRepositoryError> for ServiceError {
     fn from(error: NoteRepositoryError) -> Self {
         match error {
             NoteRepositoryError::NotFound(id) => ServiceError::NotFound { entity: "Note".into(), id },
+            NoteRepositoryError::ContentTooLong { max } => ServiceError::Validation(format!("Merged content would exceed {max} characters")),
             NoteRepositoryError::DatabaseError(err) => ServiceError::Internal(err.to_string()),
         }
     }
@@ -0,0 +1,400 @@
+//! Search-result highlighting.
+//!
+//! A [`Highlighter`] is built once per listing from the validated
+//! [`NoteFilters`] and marks, in every listed note, the text that made it
+//! match: the whole title, and up to [`MAX_FRAGMENTS`] fragments of the
+//! content cut around the matches. Only terms that include notes are
+//! highlighted; negated filters and terms under `NOT` are ignored.
+//!
+//! The note text is HTML-escaped around the markers, so that the markers are
+//! the only markup in a highlight and it can be rendered as HTML without
+//! letting note content inject its own.
+
+use model::dto::{
+    note::{NoteHighlights, NoteResponse},
+    pagination::{FilterExpr, HighlightOptions, NoteFilters, TextField, TextFilter, TextMatch},
+};
+
+/// Maximum number of content fragments returned per note.
+const MAX_FRAGMENTS: usize = 3;
+
+/// Marks text cut from either end of a fragment.
+const ELLIPSIS: &str = "…";
+
+/// A byte range of a match within a note field.
+type Span = (usize, usize);
+
+/// Marks the text filter matches of listed notes.
+pub(crate) struct Highlighter {
+    /// The text filters that include notes.
+    terms: Vec<TextFilter>,
+    /// Whether matching distinguishes upper from lower case.
+    case_sensitive: bool,
+    /// The fragment length and markers.
+    options: HighlightOptions,
+}
+
+impl Highlighter {
+    /// Creates a highlighter for the given filters.
+    pub(crate) fn new(filters: &NoteFilters, options: HighlightOptions) -> Self {
+        let mut terms: Vec<TextFilter> = filters.text.iter().filter(|filter| !filter.negated).cloned().collect();
+
+        if let Some(expression) = &filters.expression {
+            collect_terms(expression, false, &mut terms);
+        }
+
+        Self {
+            terms,
+            case_sensitive: filters.case_sensitive,
+            options,
+        }
+    }
+
+    /// Returns the highlights of a single note.
+    pub(crate) fn highlight(&self, note: &NoteResponse) -> NoteHighlights {
+        let title = self.find_matches(&note.title, TextField::Title);
+        let content = self.find_matches(&note.content, TextField::Content);
+
+        NoteHighlights {
+            title: if title.is_empty() {
+                Vec::new()
+            } else {
+                vec![self.mark(&note.title, 0, note.title.len(), &title)]
+            },
+            content: self.fragments(&note.content, &content),
+        }
+    }
+
+    /// Finds the sorted, non-overlapping spans of `text` matched by the
+    /// terms on `field`.
+    fn find_matches(&self, text: &str, field: TextField) -> Vec<Span> {
+        let terms: Vec<&TextFilter> = self.terms.iter().filter(|term| term.field == field).collect();
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let folded = Folded::new(text, self.case_sensitive);
+        let mut spans = Vec::new();
+
+        for term in terms {
+            let pattern = Folded::new(&term.pattern, self.case_sensitive).text;
+
+            match term.matching {
+                TextMatch::Contains => {
+                    spans.extend(
+                        folded
+                            .text
+                            .match_indices(&pattern)
+                            .map(|(start, found)| folded.span(start, start + found.len())),
+                    );
+                },
+                TextMatch::Prefix if folded.text.starts_with(&pattern) => spans.push(folded.span(0, pattern.len())),
+                TextMatch::Exact if folded.text == pattern => spans.push((0, text.len())),
+                TextMatch::Prefix | TextMatch::Exact => {},
+            }
+        }
+
+        spans.sort_unstable();
+        spans.into_iter().fold(Vec::new(), |mut merged: Vec<Span>, (start, end)| {
+            match merged.last_mut() {
+                Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+            merged
+        })
+    }
+
+    /// Cuts up to [`MAX_FRAGMENTS`] non-overlapping fragments of `text`
+    /// around `spans`.
+    fn fragments(&self, text: &str, spans: &[Span]) -> Vec<String> {
+        let mut fragments = Vec::new();
+        let mut covered = 0;
+
+        for &(start, end) in spans {
+            if fragments.len() == MAX_FRAGMENTS {
+                break;
+            }
+            if start < covered {
+                continue;
+            }
+
+            let (from, to) = self.window(text, start, end);
+            let from = from.max(covered);
+            let to = spans.iter().filter(|span| span.0 < to).fold(to, |to, span| to.max(span.1));
+
+            let mut fragment = String::new();
+            if from > 0 {
+                fragment.push_str(ELLIPSIS);
+            }
+            fragment.push_str(&self.mark(text, from, to, spans));
+            if to < text.len() {
+                fragment.push_str(ELLIPSIS);
+            }
+
+            fragments.push(fragment);
+            covered = to;
+        }
+
+        fragments
+    }
+
+    /// Returns the byte range of a fragment of about
+    /// [`fragment_size`](HighlightOptions::fragment_size) characters centred
+    /// on the span, trimmed to whole words and without surrounding
+    /// whitespace.
+    fn window(&self, text: &str, start: usize, end: usize) -> Span {
+        let context = self.options.fragment_size.saturating_sub(text[start..end].chars().count());
+
+        let mut from = skip_back(text, start, context / 2);
+        let mut to = skip_forward(text, end, context - text[from..start].chars().count());
+        from = skip_back(text, start, context - text[end..to].chars().count());
+
+        if from > 0
+            && let Some(offset) = text[from..start].find(char::is_whitespace)
+        {
+            from += offset;
+        }
+        if to < text.len()
+            && let Some(offset) = text[end..to].rfind(char::is_whitespace)
+        {
+            to = end + offset;
+        }
+
+        let trimmed = text[from..start].trim_start();
+        (start - trimmed.len(), end + text[end..to].trim_end().len())
+    }
+
+    /// Copies `text[from..to]` HTML-escaped, wrapping each span within it in
+    /// the markers.
+    fn mark(&self, text: &str, from: usize, to: usize, spans: &[Span]) -> String {
+        let mut marked = String::new();
+        let mut cursor = from;
+
+        for &(start, end) in spans.iter().filter(|span| span.0 >= from && span.1 <= to) {
+            escape_html(&text[cursor..start], &mut marked);
+            marked.push_str(&self.options.pre_tag);
+            escape_html(&text[start..end], &mut marked);
+            marked.push_str(&self.options.post_tag);
+            cursor = end;
+        }
+
+        escape_html(&text[cursor..to], &mut marked);
+        marked
+    }
+}
+
+/// Collects the text filters of `expression` that include notes, i.e. those
+/// under an even number of negations.
+fn collect_terms(expression: &FilterExpr, negated: bool, terms: &mut Vec<TextFilter>) {
+    match expression {
+        FilterExpr::All(operands) | FilterExpr::Any(operands) => {
+            for operand in operands {
+                collect_terms(operand, negated, terms);
+            }
+        },
+        FilterExpr::Not(operand) => collect_terms(operand, !negated, terms),
+        FilterExpr::Text(filter) if !negated => terms.push(filter.clone()),
+        FilterExpr::Text(_) | FilterExpr::Id(..) | FilterExpr::Timestamp(..) | FilterExpr::Flag(..) => {},
+    }
+}
+
+/// Appends `text` to `out`, escaping the characters that are significant
+/// in HTML text and attribute values.
+fn escape_html(text: &str, out: &mut String) {
+    for character in text.chars() {
+        match character {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            other => out.push(other),
+        }
+    }
+}
+
+/// Returns the byte index `count` characters before `index`, or zero.
+fn skip_back(text: &str, index: usize, count: usize) -> usize {
+    match count {
+        0 => index,
+        _ => text[..index].char_indices().rev().nth(count - 1).map_or(0, |(offset, _)| offset),
+    }
+}
+
+/// Returns the byte index `count` characters after `index`, or the end of
+/// the text.
+fn skip_forward(text: &str, index: usize, count: usize) -> usize {
+    text[index..].char_indices().nth(count).map_or(text.len(), |(offset, _)| index + offset)
+}
+
+/// A note field prepared for matching, lower-cased unless matching is
+/// case-sensitive.
+struct Folded {
+    /// The text to search in.
+    text: String,
+    /// For every byte of `text`, the byte range of the original character it
+    /// was derived from; empty when `text` is the original.
+    sources: Vec<Span>,
+}
+
+impl Folded {
+    /// Prepares `original` for matching.
+    fn new(original: &str, case_sensitive: bool) -> Self {
+        if case_sensitive {
+            return Self {
+                text: original.to_owned(),
+                sources: Vec::new(),
+            };
+        }
+
+        let mut text = String::with_capacity(original.len());
+        let mut sources = Vec::with_capacity(original.len());
+
+        for (offset, character) in original.char_indices() {
+            let source = (offset, offset + character.len_utf8());
+            for lower in character.to_lowercase() {
+                text.push(lower);
+                sources.resize(text.len(), source);
+            }
+        }
+
+        Self { text, sources }
+    }
+
+    /// Maps a byte range of `text` back to the original.
+    fn span(&self, start: usize, end: usize) -> Span {
+        if self.sources.is_empty() {
+            (start, end)
+        } else {
+            (self.sources[start].0, self.sources[end - 1].1)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a highlighter for `terms`, each matched with `matching`.
+    fn highlighter(terms: &[(TextField, &str)], matching: TextMatch, case_sensitive: bool, fragment_size: usize) -> Highlighter {
+        let filters = NoteFilters {
+            text: terms
+                .iter()
+                .map(|&(field, pattern)| TextFilter {
+                    field,
+                    pattern: pattern.into(),
+                    matching,
+                    negated: false,
+                })
+                .collect(),
+            case_sensitive,
+            ..NoteFilters::default()
+        };
+
+        Highlighter::new(
+            &filters,
+            HighlightOptions {
+                fragment_size,
+                ..HighlightOptions::default()
+            },
+        )
+    }
+
+    /// Returns the content fragments of `text` for a single content term.
+    fn content_fragments(pattern: &str, text: &str, fragment_size: usize) -> Vec<String> {
+        let highlighter = highlighter(&[(TextField::Content, pattern)], TextMatch::Contains, false, fragment_size);
+        highlighter.fragments(text, &highlighter.find_matches(text, TextField::Content))
+    }
+
+    #[test]
+    fn maps_case_folded_matches_back_to_the_original_text() {
+        assert_eq!(
+            content_fragments("ärger", "Ärger über ÄRGER", 150),
+            ["<mark>Ärger</mark> über <mark>ÄRGER</mark>"]
+        );
+    }
+
+    #[test]
+    fn handles_folding_that_changes_the_byte_length() {
+        let highlighter = highlighter(&[(TextField::Title, "İST")], TextMatch::Prefix, false, 150);
+        let title = "İstanbul";
+        let spans = highlighter.find_matches(title, TextField::Title);
+
+        assert_eq!(spans, [(0, "İst".len())]);
+        assert_eq!(highlighter.mark(title, 0, title.len(), &spans), "<mark>İst</mark>anbul");
+    }
+
+    #[test]
+    fn respects_case_sensitivity() {
+        let highlighter = highlighter(&[(TextField::Content, "Rust")], TextMatch::Contains, true, 150);
+
+        assert_eq!(highlighter.find_matches("rust Rust RUST", TextField::Content), [(5, 9)]);
+    }
+
+    #[test]
+    fn cuts_fragments_at_character_and_word_boundaries() {
+        let text = format!("{} needle {}", "é".repeat(100), "ü".repeat(100));
+
+        assert_eq!(content_fragments("needle", &text, 20), ["…<mark>needle</mark>…"]);
+    }
+
+    #[test]
+    fn keeps_context_around_a_match() {
+        let text = "the quick brown fox jumps over the lazy dog";
+
+        assert_eq!(content_fragments("fox", text, 20), ["…brown <mark>fox</mark> jumps…"]);
+    }
+
+    #[test]
+    fn returns_at_most_the_maximum_number_of_fragments() {
+        let text = ["needle"; MAX_FRAGMENTS + 2].join(&format!(" {} ", "hay ".repeat(20)));
+
+        assert_eq!(content_fragments("needle", &text, 20).len(), MAX_FRAGMENTS);
+    }
+
+    #[test]
+    fn merges_overlapping_matches() {
+        let highlighter = highlighter(&[(TextField::Content, "ab"), (TextField::Content, "bc")], TextMatch::Contains, false, 150);
+
+        assert_eq!(highlighter.find_matches("xabcx", TextField::Content), [(1, 4)]);
+    }
+
+    #[test]
+    fn escapes_note_text_but_not_the_markers() {
+        assert_eq!(
+            content_fragments("needle", r#"<script>alert("x")</script> & 'needle'"#, 150),
+            ["&lt;script&gt;alert(&quot;x&quot;)&lt;/script&gt; &amp; &#39;<mark>needle</mark>&#39;"]
+        );
+        assert_eq!(content_fragments("<b>", "a <b> c", 150), ["a <mark>&lt;b&gt;</mark> c"]);
+    }
+
+    #[test]
+    fn ignores_negated_terms() {
+        let negated = TextFilter {
+            field: TextField::Title,
+            pattern: "draft".into(),
+            matching: TextMatch::Contains,
+            negated: true,
+        };
+        let filters = NoteFilters {
+            text: vec![negated.clone()],
+            expression: Some(FilterExpr::All(vec![
+                FilterExpr::Not(Box::new(FilterExpr::Text(TextFilter {
+                    negated: false,
+                    ..negated.clone()
+                }))),
+                FilterExpr::Not(Box::new(FilterExpr::Not(Box::new(FilterExpr::Text(TextFilter {
+                    pattern: "final".into(),
+                    negated: false,
+                    ..negated
+                }))))),
+            ])),
+            ..NoteFilters::default()
+        };
+
+        let highlighter = Highlighter::new(&filters, HighlightOptions::default());
+
+        assert_eq!(highlighter.terms.len(), 1);
+        assert_eq!(highlighter.terms[0].pattern, "final");
+    }
+}
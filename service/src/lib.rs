@@ -2,6 +2,18 @@
 //!
 //! This crate sits between the controller (HTTP) and the repository (database)
 //! layers, providing validation, default pagination, and error translation.
+//! Each resource has its own service trait: [`note::NoteService`],
+//! [`reminder::ReminderService`], [`task::TaskService`],
+//! [`saved_search::SavedSearchService`], [`template::TemplateService`], and
+//! [`property_schema::PropertySchemaService`].
 
 pub mod error;
+mod highlight;
 pub mod note;
+pub mod property_schema;
+pub mod reminder;
+pub mod saved_search;
+mod search;
+pub mod task;
+pub mod template;
+mod validation;
@@ -1,140 +1,119 @@
 //! Note service trait, its implementation, and request validation.
 //!
-//! The [`NoteService`] trait defines the business operations exposed to the
-//! controller layer, whilst [`NoteServiceImpl`] provides the concrete
-//! implementation backed by a [`NoteRepository`].
+//! The [`NoteService`] trait defines the business operations on notes
+//! exposed to the controller layer, whilst [`NoteServiceImpl`] provides the
+//! concrete implementation backed by a [`NoteRepository`].
 
+use chrono::Utc;
 use model::dto::{
-    note::{CreateNoteRequest, NoteResponse, UpdateNoteRequest},
-    pagination::{PaginatedResponse, SearchParams, SortDirection, SortField, SortFieldName},
+    idempotency::{IdempotencyRecord, IdempotentOutcome},
+    note::{CreateNoteRequest, MergeNotesRequest, NoteFlag, NoteResponse, NoteSuggestion, SuggestQuery, UpdateNoteRequest},
+    pagination::{PaginatedResponse, PropertyFilter, SearchParams},
+    property::{MAX_PROPERTY_KEY_LEN, PropertyType, is_property_key},
+};
+use repository::{note::NoteRepository, property_schema::PropertySchemaRepository};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::{future::Future, time::Duration};
+
+use crate::{
+    error::ServiceError,
+    highlight::Highlighter,
+    search::{MAX_IDS, typed_property_filter},
+    validation::{Validate, validate_limit},
 };
-use repository::note::NoteRepository;
-use std::future::Future;
 
-use crate::error::ServiceError;
+/// Number of title suggestions returned when no `limit` is provided.
+const DEFAULT_SUGGESTIONS: u64 = 10;
 
-/// Maximum allowed length for a note title, in characters.
-const MAX_TITLE_LEN: usize = 255;
+/// Maximum number of title suggestions per request.
+const MAX_SUGGESTIONS: u64 = 25;
 
-/// Default page number when none is provided by the client.
-const DEFAULT_PAGE: u64 = 1;
+/// Maximum length of the suggestion `prefix`, in characters.
+const MAX_PREFIX_LEN: usize = 100;
 
-/// Default page size when none is provided by the client.
-const DEFAULT_SIZE: u64 = 20;
+/// Maximum number of custom properties on a note.
+const MAX_PROPERTIES: usize = 50;
 
-/// Hard upper limit on page size to prevent excessively large responses.
-const MAX_SIZE: u64 = 100;
+/// Maximum length of a string property value, in characters.
+const MAX_PROPERTY_VALUE_LEN: usize = 1_000;
 
-/// Validates that a string filter parameter is not blank when present.
-///
-/// Returns `Ok(())` immediately when the parameter is absent. `name` is the
-/// query-string key used verbatim in the error message.
-fn validate_string_filter(raw: &Option<String>, name: &str) -> Result<(), ServiceError> {
-    let Some(value) = raw else {
-        return Ok(());
-    };
+/// Number of suffixed titles looked up at a time when duplicating a note.
+const COPY_TITLE_BATCH: u32 = 10;
 
-    if value.trim().is_empty() {
-        tracing::warn!(parameter = name, "Validation failed: string filter is blank");
-        return Err(ServiceError::Validation(format!("Parameter '{name}' must not be blank")));
-    }
+/// The text inserted before each note appended by a merge, unless the
+/// request supplies its own.
+const DEFAULT_MERGE_SEPARATOR: &str = "\n\n";
 
-    Ok(())
-}
+/// Maximum allowed length for a merge separator, in characters.
+const MAX_MERGE_SEPARATOR_LEN: usize = 100;
 
-/// Validates and parses the `page` query parameter.
-///
-/// Returns [`DEFAULT_PAGE`] when the parameter is absent. Returns a
-/// [`ServiceError::Validation`] when the value is blank or not a valid
-/// positive integer. The result is always floored at `1`.
-fn validate_page(raw: &Option<String>) -> Result<u64, ServiceError> {
-    let Some(raw) = raw else {
-        return Ok(DEFAULT_PAGE);
-    };
+/// Maximum allowed length for an idempotency key, in bytes.
+const MAX_IDEMPOTENCY_KEY_LEN: usize = 255;
 
-    let trimmed = raw.trim();
+/// Default time for which an idempotency key and its response are retained.
+pub const DEFAULT_IDEMPOTENCY_TTL: Duration = Duration::from_secs(24 * 60 * 60);
 
-    if trimmed.is_empty() {
-        tracing::warn!("Validation failed: page is blank");
-        return Err(ServiceError::Validation("Parameter 'page' must not be blank".into()));
-    }
+/// HTTP status code stored for a note created through an idempotent request.
+const CREATED_STATUS: u16 = 201;
 
-    trimmed.parse::<u64>().map(|value| value.max(1)).map_err(|_| {
-        tracing::warn!(value = trimmed, "Validation failed: page is not a valid positive integer");
-        ServiceError::Validation(format!("Parameter 'page' must be a positive integer, got '{trimmed}'"))
-    })
+/// Configurable limits enforced by the services during validation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoteLimits {
+    /// Maximum allowed length for a note title, in characters.
+    pub max_title_len: usize,
+    /// Maximum allowed length for a note's content, in characters.
+    pub max_content_len: usize,
+    /// Default page size when none is provided by the client.
+    pub default_size: u64,
+    /// Hard upper limit on page size to prevent excessively large responses.
+    pub max_size: u64,
 }
 
-/// Validates and parses the `size` query parameter.
-///
-/// Returns [`DEFAULT_SIZE`] when the parameter is absent. Returns a
-/// [`ServiceError::Validation`] when the value is blank, not a valid positive
-/// integer, or exceeds [`MAX_SIZE`].
-fn validate_size(raw: &Option<String>) -> Result<u64, ServiceError> {
-    let Some(raw) = raw else {
-        return Ok(DEFAULT_SIZE);
-    };
-
-    let trimmed = raw.trim();
-
-    if trimmed.is_empty() {
-        tracing::warn!("Validation failed: size is blank");
-        return Err(ServiceError::Validation("Parameter 'size' must not be blank".into()));
+impl Default for NoteLimits {
+    fn default() -> Self {
+        Self {
+            max_title_len: 255,
+            max_content_len: 100_000,
+            default_size: 20,
+            max_size: 100,
+        }
     }
+}
 
-    let value = trimmed.parse::<u64>().map_err(|_| {
-        tracing::warn!(value = trimmed, "Validation failed: size is not a valid positive integer");
-        ServiceError::Validation(format!("Parameter 'size' must be a positive integer, got '{trimmed}'"))
-    })?;
-
-    if value > MAX_SIZE {
-        tracing::warn!(size = value, max = MAX_SIZE, "Validation failed: page size too large");
-        return Err(ServiceError::Validation(format!("Parameter 'size' must not exceed {MAX_SIZE}")));
+/// Validates an `Idempotency-Key` value: it must be non-empty, at most
+/// [`MAX_IDEMPOTENCY_KEY_LEN`] bytes, and consist of visible ASCII
+/// characters.
+fn validate_idempotency_key(key: &str) -> Result<(), ServiceError> {
+    if key.is_empty() || key.len() > MAX_IDEMPOTENCY_KEY_LEN || !key.bytes().all(|byte| byte.is_ascii_graphic()) {
+        tracing::warn!(length = key.len(), "Validation failed: malformed idempotency key");
+        return Err(ServiceError::Validation(format!(
+            "Header 'Idempotency-Key' must be 1 to {MAX_IDEMPOTENCY_KEY_LEN} visible ASCII characters"
+        )));
     }
 
-    Ok(value)
+    Ok(())
 }
 
-/// Validates and parses the `orderBy` query parameter.
+/// Computes a hex-encoded SHA-256 fingerprint of a create request.
 ///
-/// Returns `Ok(None)` immediately when the parameter is absent. Returns a
-/// [`ServiceError::Validation`] when the string is blank or contains only
-/// commas, or when a field name is unrecognised. Each token may be prefixed
-/// with `+` (ascending, default) or `-` (descending).
-fn validate_order_by(raw: &Option<String>) -> Result<Option<Vec<SortField>>, ServiceError> {
-    let Some(raw) = raw else {
-        return Ok(None);
-    };
+/// Each field is length-prefixed so that different splits of the same bytes
+/// between title and content yield different fingerprints.
+fn fingerprint(request: &CreateNoteRequest) -> String {
+    let mut hasher = Sha256::new();
+
+    for field in [&request.title, &request.content] {
+        hasher.update((field.len() as u64).to_be_bytes());
+        hasher.update(field.as_bytes());
+    }
 
-    let fields: Vec<SortField> = raw
-        .split(',')
-        .map(str::trim)
-        .filter(|string| !string.is_empty())
-        .map(|token| {
-            let (direction, name) = if let Some(rest) = token.strip_prefix('-') {
-                (SortDirection::Descending, rest)
-            } else {
-                (SortDirection::Ascending, token.strip_prefix('+').unwrap_or(token))
-            };
-
-            let name: SortFieldName = name.parse().map_err(|err: String| {
-                tracing::warn!(field = name, "Validation failed: unknown sort field");
-                ServiceError::Validation(err)
-            })?;
-
-            Ok(SortField { name, direction })
-        })
-        .collect::<Result<Vec<SortField>, ServiceError>>()?;
-
-    if fields.is_empty() {
-        tracing::warn!("Validation failed: orderBy is present but contains no fields");
-        return Err(ServiceError::Validation(format!(
-            "Parameter 'orderBy' must contain at least one field. Valid fields: {}",
-            SortFieldName::all_names()
-        )));
+    if !request.properties.is_empty() {
+        let properties = Value::Object(request.properties.clone()).to_string();
+        hasher.update((properties.len() as u64).to_be_bytes());
+        hasher.update(properties.as_bytes());
     }
 
-    Ok(Some(fields))
+    hasher.finalize().iter().map(|byte| format!("{byte:02x}")).collect()
 }
 
 /// Trait abstracting CRUD business operations for notes.
@@ -145,54 +124,130 @@ pub trait NoteService: Send + Sync + Clone + 'static {
     /// Validates and creates a new note.
     fn create(&self, request: CreateNoteRequest) -> impl Future<Output = Result<NoteResponse, ServiceError>> + Send;
 
+    /// Validates and creates a new note at most once per idempotency key.
+    ///
+    /// Retries with the same key and body replay the original response;
+    /// reusing the key with a different body yields
+    /// [`ServiceError::Unprocessable`].
+    fn create_idempotent(
+        &self,
+        request: CreateNoteRequest,
+        key: String,
+    ) -> impl Future<Output = Result<IdempotentOutcome<NoteResponse>, ServiceError>> + Send;
+
     /// Retrieves a single note by its primary key.
     fn find_by_id(&self, id: i64) -> impl Future<Output = Result<NoteResponse, ServiceError>> + Send;
 
     /// Returns a paginated, optionally filtered list of notes.
     fn find_all(&self, params: SearchParams) -> impl Future<Output = Result<PaginatedResponse<NoteResponse>, ServiceError>> + Send;
 
+    /// Sets or clears a flag of a note.
+    fn set_flag(&self, id: i64, flag: NoteFlag, value: bool) -> impl Future<Output = Result<NoteResponse, ServiceError>> + Send;
+
+    /// Validates the prefix and returns matching titles for autocompletion.
+    fn suggest(&self, query: SuggestQuery) -> impl Future<Output = Result<Vec<NoteSuggestion>, ServiceError>> + Send;
+
     /// Validates and partially updates an existing note.
     fn update(&self, id: i64, request: UpdateNoteRequest) -> impl Future<Output = Result<NoteResponse, ServiceError>> + Send;
 
     /// Deletes a note by its primary key.
     fn delete(&self, id: i64) -> impl Future<Output = Result<(), ServiceError>> + Send;
+
+    /// Creates a copy of a note, with a suffixed title, the same content,
+    /// and the same properties.
+    fn duplicate(&self, id: i64) -> impl Future<Output = Result<NoteResponse, ServiceError>> + Send;
+
+    /// Appends the content of several notes to a target note, then archives
+    /// or deletes them, returning the updated target.
+    fn merge(&self, request: MergeNotesRequest) -> impl Future<Output = Result<NoteResponse, ServiceError>> + Send;
 }
 
-/// Concrete [`NoteService`] backed by a generic [`NoteRepository`].
+/// Concrete [`NoteService`] backed by a generic [`NoteRepository`], which
+/// reads the declared property types from a [`PropertySchemaRepository`].
 #[derive(Clone)]
-pub struct NoteServiceImpl<Repo: NoteRepository> {
+pub struct NoteServiceImpl<Repo: NoteRepository, Schemas: PropertySchemaRepository> {
     /// The repository used for data access.
     repository: Repo,
+    /// The repository holding the declared property types.
+    schemas: Schemas,
+    /// The limits applied when validating requests.
+    limits: NoteLimits,
+    /// How long idempotency keys and their responses are retained.
+    idempotency_ttl: Duration,
 }
 
-impl<Repo: NoteRepository> NoteServiceImpl<Repo> {
-    /// Creates a new [`NoteServiceImpl`] wrapping the given repository.
-    pub fn new(repository: Repo) -> Self {
-        Self { repository }
+impl<Repo: NoteRepository, Schemas: PropertySchemaRepository> NoteServiceImpl<Repo, Schemas> {
+    /// Creates a new [`NoteServiceImpl`] wrapping the given repositories and
+    /// enforcing the given limits.
+    pub fn new(repository: Repo, schemas: Schemas, limits: NoteLimits) -> Self {
+        Self {
+            repository,
+            schemas,
+            limits,
+            idempotency_ttl: DEFAULT_IDEMPOTENCY_TTL,
+        }
     }
-}
 
-/// Internal validation trait implemented by request DTOs and query
-/// parameter types.
-trait Validate {
-    /// Validates (and, where appropriate, normalises) the receiver.
-    ///
-    /// Returns `Ok(())` when the payload is valid, or a
-    /// [`ServiceError::Validation`] describing the first violation found.
-    fn validate(&mut self) -> Result<(), ServiceError>;
+    /// Sets how long idempotency keys and their responses are retained.
+    pub fn idempotency_ttl(mut self, ttl: Duration) -> Self {
+        self.idempotency_ttl = ttl;
+        self
+    }
+
+    /// Checks that every property with a schema has a value of the declared
+    /// type.
+    async fn check_property_types(&self, properties: &Map<String, Value>) -> Result<(), ServiceError> {
+        if properties.is_empty() {
+            return Ok(());
+        }
+
+        for schema in self.schemas.find_all().await? {
+            if let Some(value) = properties.get(&schema.key)
+                && PropertyType::of(value) != Some(schema.kind)
+            {
+                tracing::warn!(key = schema.key.as_str(), kind = %schema.kind, "Validation failed: property of the wrong type");
+                return Err(ServiceError::Validation(format!("Property '{}' must be a {}", schema.key, schema.kind)));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Re-reads the value of every property filter whose key has a schema
+    /// as the declared type.
+    async fn apply_property_schemas(&self, filters: &mut [PropertyFilter]) -> Result<(), ServiceError> {
+        if filters.is_empty() {
+            return Ok(());
+        }
+
+        let schemas = self.schemas.find_all().await?;
+
+        for filter in filters {
+            if let Some(schema) = schemas.iter().find(|schema| schema.key == filter.key) {
+                *filter = typed_property_filter(&filter.key, filter.comparison, &filter.text, Some(schema.kind))?;
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl Validate for CreateNoteRequest {
-    fn validate(&mut self) -> Result<(), ServiceError> {
+    fn validate(&mut self, limits: &NoteLimits) -> Result<(), ServiceError> {
         if self.title.trim().is_empty() {
             tracing::warn!("Validation failed: title is empty");
             return Err(ServiceError::Validation("Field 'title' must not be empty".into()));
         }
 
-        if self.title.len() > MAX_TITLE_LEN {
-            tracing::warn!(length = self.title.len(), max = MAX_TITLE_LEN, "Validation failed: title too long");
+        if self.title.chars().count() > limits.max_title_len {
+            tracing::warn!(
+                length = self.title.chars().count(),
+                max = limits.max_title_len,
+                "Validation failed: title too long"
+            );
             return Err(ServiceError::Validation(format!(
-                "Field 'title' must be at most {MAX_TITLE_LEN} characters"
+                "Field 'title' must be at most {} characters",
+                limits.max_title_len
             )));
         }
 
@@ -201,22 +256,39 @@ impl Validate for CreateNoteRequest {
             return Err(ServiceError::Validation("Field 'content' must not be empty".into()));
         }
 
-        Ok(())
+        if self.content.chars().count() > limits.max_content_len {
+            tracing::warn!(
+                length = self.content.chars().count(),
+                max = limits.max_content_len,
+                "Validation failed: content too long"
+            );
+            return Err(ServiceError::Validation(format!(
+                "Field 'content' must be at most {} characters",
+                limits.max_content_len
+            )));
+        }
+
+        validate_properties(&self.properties)
     }
 }
 
 impl Validate for UpdateNoteRequest {
-    fn validate(&mut self) -> Result<(), ServiceError> {
+    fn validate(&mut self, limits: &NoteLimits) -> Result<(), ServiceError> {
         if let Some(ref title) = self.title {
             if title.trim().is_empty() {
                 tracing::warn!("Validation failed: title is empty");
                 return Err(ServiceError::Validation("Field 'title' must not be empty".into()));
             }
 
-            if title.len() > MAX_TITLE_LEN {
-                tracing::warn!(length = title.len(), max = MAX_TITLE_LEN, "Validation failed: title too long");
+            if title.chars().count() > limits.max_title_len {
+                tracing::warn!(
+                    length = title.chars().count(),
+                    max = limits.max_title_len,
+                    "Validation failed: title too long"
+                );
                 return Err(ServiceError::Validation(format!(
-                    "Field 'title' must be at most {MAX_TITLE_LEN} characters"
+                    "Field 'title' must be at most {} characters",
+                    limits.max_title_len
                 )));
             }
         }
@@ -226,35 +298,198 @@ impl Validate for UpdateNoteRequest {
                 tracing::warn!("Validation failed: content is empty");
                 return Err(ServiceError::Validation("Field 'content' must not be empty".into()));
             }
+
+            if content.chars().count() > limits.max_content_len {
+                tracing::warn!(
+                    length = content.chars().count(),
+                    max = limits.max_content_len,
+                    "Validation failed: content too long"
+                );
+                return Err(ServiceError::Validation(format!(
+                    "Field 'content' must be at most {} characters",
+                    limits.max_content_len
+                )));
+            }
+        }
+
+        if let Some(properties) = &self.properties {
+            validate_properties(properties)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Validates the keys and values of a note's custom properties.
+fn validate_properties(properties: &Map<String, Value>) -> Result<(), ServiceError> {
+    if properties.len() > MAX_PROPERTIES {
+        tracing::warn!(count = properties.len(), max = MAX_PROPERTIES, "Validation failed: too many properties");
+        return Err(ServiceError::Validation(format!(
+            "Field 'properties' must have at most {MAX_PROPERTIES} entries"
+        )));
+    }
+
+    for (key, value) in properties {
+        if !is_property_key(key) {
+            tracing::warn!(key = key.as_str(), "Validation failed: invalid property key");
+            return Err(ServiceError::Validation(format!(
+                "Property key '{key}' is invalid: expected a letter or '_' followed by letters, digits, '_', or '-', at most {MAX_PROPERTY_KEY_LEN} characters"
+            )));
+        }
+
+        if PropertyType::of(value).is_none() {
+            tracing::warn!(key = key.as_str(), "Validation failed: property value is not a scalar");
+            return Err(ServiceError::Validation(format!("Property '{key}' must be a string, number, or boolean")));
+        }
+
+        if let Value::String(text) = value
+            && text.chars().count() > MAX_PROPERTY_VALUE_LEN
+        {
+            tracing::warn!(
+                key = key.as_str(),
+                max = MAX_PROPERTY_VALUE_LEN,
+                "Validation failed: property value too long"
+            );
+            return Err(ServiceError::Validation(format!(
+                "Property '{key}' must be at most {MAX_PROPERTY_VALUE_LEN} characters"
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+impl Validate for MergeNotesRequest {
+    fn validate(&mut self, _limits: &NoteLimits) -> Result<(), ServiceError> {
+        if self.target_id <= 0 {
+            tracing::warn!(target_id = self.target_id, "Validation failed: invalid target ID");
+            return Err(ServiceError::Validation("Field 'targetId' must be a positive integer".into()));
+        }
+
+        if self.source_ids.is_empty() {
+            tracing::warn!("Validation failed: no source IDs");
+            return Err(ServiceError::Validation("Field 'sourceIds' must not be empty".into()));
+        }
+
+        if self.source_ids.len() > MAX_IDS {
+            tracing::warn!(count = self.source_ids.len(), max = MAX_IDS, "Validation failed: too many source IDs");
+            return Err(ServiceError::Validation(format!(
+                "Field 'sourceIds' must not contain more than {MAX_IDS} IDs"
+            )));
+        }
+
+        for (position, &id) in self.source_ids.iter().enumerate() {
+            if id <= 0 {
+                tracing::warn!(id, "Validation failed: invalid source ID");
+                return Err(ServiceError::Validation("Field 'sourceIds' must only contain positive integers".into()));
+            }
+
+            if id == self.target_id {
+                tracing::warn!(id, "Validation failed: target among the sources");
+                return Err(ServiceError::Validation("Field 'sourceIds' must not contain the target note".into()));
+            }
+
+            if self.source_ids[..position].contains(&id) {
+                tracing::warn!(id, "Validation failed: duplicate source ID");
+                return Err(ServiceError::Validation(format!("Field 'sourceIds' contains note {id} more than once")));
+            }
+        }
+
+        if let Some(separator) = &self.separator
+            && separator.chars().count() > MAX_MERGE_SEPARATOR_LEN
+        {
+            tracing::warn!(
+                length = separator.chars().count(),
+                max = MAX_MERGE_SEPARATOR_LEN,
+                "Validation failed: separator too long"
+            );
+            return Err(ServiceError::Validation(format!(
+                "Field 'separator' must be at most {MAX_MERGE_SEPARATOR_LEN} characters"
+            )));
         }
 
         Ok(())
     }
 }
 
-impl Validate for SearchParams {
-    fn validate(&mut self) -> Result<(), ServiceError> {
-        validate_string_filter(&self.title, "title")?;
-        validate_string_filter(&self.content, "content")?;
+/// Builds the title of the `number`-th copy of a note: ` (copy)` for the
+/// first and ` (copy N)` afterwards, shortening `title` so that the result
+/// is at most `max_len` characters long.
+fn copy_title(title: &str, number: u32, max_len: usize) -> String {
+    let suffix = match number {
+        1 => " (copy)".to_owned(),
+        _ => format!(" (copy {number})"),
+    };
+
+    let kept = max_len.saturating_sub(suffix.chars().count());
+    let end = title.char_indices().nth(kept).map_or(title.len(), |(index, _)| index);
+
+    format!("{}{suffix}", title[..end].trim_end())
+}
+
+impl Validate for SuggestQuery {
+    fn validate(&mut self, _limits: &NoteLimits) -> Result<(), ServiceError> {
+        let prefix = self.prefix.as_deref().map(str::trim).unwrap_or_default();
+
+        if prefix.is_empty() {
+            tracing::warn!("Validation failed: suggestion prefix is missing or blank");
+            return Err(ServiceError::Validation("Parameter 'prefix' is required and must not be blank".into()));
+        }
+
+        if prefix.chars().count() > MAX_PREFIX_LEN {
+            tracing::warn!(max = MAX_PREFIX_LEN, "Validation failed: suggestion prefix too long");
+            return Err(ServiceError::Validation(format!(
+                "Parameter 'prefix' must be at most {MAX_PREFIX_LEN} characters"
+            )));
+        }
 
-        self.parsed_page = validate_page(&self.page)?;
-        self.parsed_size = validate_size(&self.size)?;
-        self.sort_fields = validate_order_by(&self.order_by)?.unwrap_or_default();
+        self.prefix = Some(prefix.to_owned());
+        self.parsed_limit = validate_limit(&self.limit, DEFAULT_SUGGESTIONS, MAX_SUGGESTIONS)?;
 
         Ok(())
     }
 }
 
-impl<Repo: NoteRepository> NoteService for NoteServiceImpl<Repo> {
+impl<Repo: NoteRepository, Schemas: PropertySchemaRepository> NoteService for NoteServiceImpl<Repo, Schemas> {
     /// Validates the incoming request and delegates to the repository to
     /// persist the new note.
     #[tracing::instrument(skip_all)]
     async fn create(&self, mut request: CreateNoteRequest) -> Result<NoteResponse, ServiceError> {
-        request.validate()?;
+        request.validate(&self.limits)?;
+        self.check_property_types(&request.properties).await?;
 
         self.repository.create(request).await.map_err(ServiceError::from)
     }
 
+    /// Validates the key and the request, then delegates to the repository.
+    /// A stored response is only replayed if its fingerprint matches the
+    /// current request.
+    #[tracing::instrument(skip_all)]
+    async fn create_idempotent(&self, mut request: CreateNoteRequest, key: String) -> Result<IdempotentOutcome<NoteResponse>, ServiceError> {
+        validate_idempotency_key(&key)?;
+        request.validate(&self.limits)?;
+        self.check_property_types(&request.properties).await?;
+
+        let ttl = chrono::Duration::from_std(self.idempotency_ttl).map_err(|err| ServiceError::Internal(err.to_string()))?;
+        let record = IdempotencyRecord {
+            fingerprint: fingerprint(&request),
+            key,
+            status_code: CREATED_STATUS,
+            expires_at: Utc::now() + ttl,
+        };
+        let fingerprint = record.fingerprint.clone();
+
+        match self.repository.create_idempotent(request, record).await? {
+            IdempotentOutcome::Replayed(stored) if stored.fingerprint != fingerprint => {
+                tracing::warn!("Idempotency key reused with a different request body");
+                Err(ServiceError::Unprocessable(
+                    "Header 'Idempotency-Key' was already used with a different request body".into(),
+                ))
+            },
+            outcome => Ok(outcome),
+        }
+    }
+
     /// Fetches a single note by ID, translating repository errors into
     /// service-layer errors.
     #[tracing::instrument(skip_all)]
@@ -262,20 +497,52 @@ impl<Repo: NoteRepository> NoteService for NoteServiceImpl<Repo> {
         self.repository.find_by_id(id).await.map_err(ServiceError::from)
     }
 
-    /// Validates and normalises search parameters, then delegates to the
-    /// repository.
+    /// Validates and normalises search parameters, delegates to the
+    /// repository, and highlights the matches when requested.
     #[tracing::instrument(skip_all)]
     async fn find_all(&self, mut parameters: SearchParams) -> Result<PaginatedResponse<NoteResponse>, ServiceError> {
-        parameters.validate()?;
+        parameters.validate(&self.limits)?;
+        self.apply_property_schemas(&mut parameters.filters.properties).await?;
+
+        let highlighter = parameters
+            .highlight_options
+            .take()
+            .map(|options| Highlighter::new(&parameters.filters, options));
+        let mut page = self.repository.find_all(parameters).await?;
+
+        if let Some(highlighter) = highlighter {
+            for note in &mut page.notes {
+                note.highlights = Some(highlighter.highlight(note));
+            }
+        }
 
-        self.repository.find_all(parameters).await.map_err(ServiceError::from)
+        Ok(page)
+    }
+
+    /// Delegates the flag change to the repository, translating any
+    /// resulting error.
+    #[tracing::instrument(skip_all)]
+    async fn set_flag(&self, id: i64, flag: NoteFlag, value: bool) -> Result<NoteResponse, ServiceError> {
+        self.repository.set_flag(id, flag, value).await.map_err(ServiceError::from)
+    }
+
+    /// Validates the prefix and limit, then delegates to the repository.
+    #[tracing::instrument(skip_all)]
+    async fn suggest(&self, mut query: SuggestQuery) -> Result<Vec<NoteSuggestion>, ServiceError> {
+        query.validate(&self.limits)?;
+
+        self.repository.suggest(query).await.map_err(ServiceError::from)
     }
 
     /// Validates the incoming request and delegates to the repository to
     /// update the existing note.
     #[tracing::instrument(skip_all)]
     async fn update(&self, id: i64, mut request: UpdateNoteRequest) -> Result<NoteResponse, ServiceError> {
-        request.validate()?;
+        request.validate(&self.limits)?;
+
+        if let Some(properties) = &request.properties {
+            self.check_property_types(properties).await?;
+        }
 
         self.repository.update(id, request).await.map_err(ServiceError::from)
     }
@@ -286,4 +553,82 @@ impl<Repo: NoteRepository> NoteService for NoteServiceImpl<Repo> {
     async fn delete(&self, id: i64) -> Result<(), ServiceError> {
         self.repository.delete(id).await.map_err(ServiceError::from)
     }
+
+    /// Gives the copy the first suffixed title that no note has yet, looking
+    /// up [`COPY_TITLE_BATCH`] candidates at a time. Flags and reminders are
+    /// not copied.
+    #[tracing::instrument(skip_all)]
+    async fn duplicate(&self, id: i64) -> Result<NoteResponse, ServiceError> {
+        let source = self.repository.find_by_id(id).await?;
+
+        let mut first = 1;
+        let title = loop {
+            let candidates: Vec<String> = (first..first + COPY_TITLE_BATCH)
+                .map(|number| copy_title(&source.title, number, self.limits.max_title_len))
+                .collect();
+            let taken = self.repository.find_taken_titles(candidates.clone()).await?;
+
+            if let Some(title) = candidates.into_iter().find(|candidate| !taken.contains(candidate)) {
+                break title;
+            }
+
+            tracing::debug!(id, first, "Copy titles taken, trying the next batch");
+            first += COPY_TITLE_BATCH;
+        };
+
+        let mut request = CreateNoteRequest {
+            title,
+            content: source.content,
+            properties: source.properties,
+        };
+        request.validate(&self.limits)?;
+
+        self.repository.create(request).await.map_err(ServiceError::from)
+    }
+
+    /// Validates the request and delegates to the repository, which fails
+    /// without changing anything if a note is missing or the merged content
+    /// would exceed the content limit.
+    #[tracing::instrument(skip_all)]
+    async fn merge(&self, mut request: MergeNotesRequest) -> Result<NoteResponse, ServiceError> {
+        request.validate(&self.limits)?;
+
+        let separator = request.separator.unwrap_or_else(|| DEFAULT_MERGE_SEPARATOR.to_owned());
+
+        self.repository
+            .merge(
+                request.target_id,
+                request.source_ids,
+                separator,
+                request.source_action,
+                self.limits.max_content_len,
+            )
+            .await
+            .map_err(ServiceError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suffixes_the_first_and_later_copies() {
+        assert_eq!(copy_title("Groceries", 1, 255), "Groceries (copy)");
+        assert_eq!(copy_title("Groceries", 2, 255), "Groceries (copy 2)");
+    }
+
+    #[test]
+    fn shortens_multibyte_titles_at_the_limit_in_characters() {
+        let title = "ä".repeat(20);
+
+        assert_eq!(copy_title(&title, 1, 20), format!("{} (copy)", "ä".repeat(13)));
+        assert_eq!(copy_title(&title, 12, 20).chars().count(), 20);
+        assert_eq!(copy_title(&"😀".repeat(20), 1, 27), format!("{} (copy)", "😀".repeat(20)));
+    }
+
+    #[test]
+    fn trims_whitespace_left_before_the_suffix() {
+        assert_eq!(copy_title("Été à Paris", 1, 13), "Été à (copy)");
+    }
 }
@@ -0,0 +1,70 @@
+//! Property schema service trait and its implementation.
+//!
+//! The [`PropertySchemaService`] trait declares the value types of custom
+//! note properties, whilst [`PropertySchemaServiceImpl`] provides the
+//! concrete implementation backed by a [`PropertySchemaRepository`].
+
+use model::dto::property::{CreatePropertySchemaRequest, MAX_PROPERTY_KEY_LEN, PropertySchemaResponse, is_property_key};
+use repository::property_schema::PropertySchemaRepository;
+use std::future::Future;
+
+use crate::error::ServiceError;
+
+/// Trait abstracting the business operations on property schemas.
+///
+/// Implementations must be [`Send`], [`Sync`], [`Clone`], and `'static` so
+/// that they can be used as Axum shared state.
+pub trait PropertySchemaService: Send + Sync + Clone + 'static {
+    /// Validates the key and declares the value type of a property.
+    fn create(&self, request: CreatePropertySchemaRequest) -> impl Future<Output = Result<PropertySchemaResponse, ServiceError>> + Send;
+
+    /// Returns every property schema.
+    fn find_all(&self) -> impl Future<Output = Result<Vec<PropertySchemaResponse>, ServiceError>> + Send;
+
+    /// Deletes a property schema by its primary key.
+    fn delete(&self, id: i64) -> impl Future<Output = Result<(), ServiceError>> + Send;
+}
+
+/// Concrete [`PropertySchemaService`] backed by a generic
+/// [`PropertySchemaRepository`].
+#[derive(Clone)]
+pub struct PropertySchemaServiceImpl<Repo: PropertySchemaRepository> {
+    /// The repository used for data access.
+    repository: Repo,
+}
+
+impl<Repo: PropertySchemaRepository> PropertySchemaServiceImpl<Repo> {
+    /// Creates a new [`PropertySchemaServiceImpl`] wrapping the given
+    /// repository.
+    pub fn new(repository: Repo) -> Self {
+        Self { repository }
+    }
+}
+
+impl<Repo: PropertySchemaRepository> PropertySchemaService for PropertySchemaServiceImpl<Repo> {
+    /// Validates the key, then delegates to the repository. Existing notes
+    /// are not checked against the new schema.
+    #[tracing::instrument(skip_all)]
+    async fn create(&self, request: CreatePropertySchemaRequest) -> Result<PropertySchemaResponse, ServiceError> {
+        if !is_property_key(&request.key) {
+            tracing::warn!(key = request.key.as_str(), "Validation failed: invalid property key");
+            return Err(ServiceError::Validation(format!(
+                "Field 'key' must be a letter or '_' followed by letters, digits, '_', or '-', at most {MAX_PROPERTY_KEY_LEN} characters"
+            )));
+        }
+
+        self.repository.create(request).await.map_err(ServiceError::from)
+    }
+
+    /// Lists every property schema.
+    #[tracing::instrument(skip_all)]
+    async fn find_all(&self) -> Result<Vec<PropertySchemaResponse>, ServiceError> {
+        self.repository.find_all().await.map_err(ServiceError::from)
+    }
+
+    /// Deletes a property schema by ID.
+    #[tracing::instrument(skip_all)]
+    async fn delete(&self, id: i64) -> Result<(), ServiceError> {
+        self.repository.delete(id).await.map_err(ServiceError::from)
+    }
+}
@@ -0,0 +1,196 @@
+//! Reminder service trait and its implementation.
+//!
+//! The [`ReminderService`] trait sets, lists, and fires note reminders,
+//! whilst [`ReminderServiceImpl`] provides the concrete implementation
+//! backed by a [`ReminderRepository`]. Recurring reminders follow the
+//! calendar of the configured time zone.
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use model::{
+    dto::{
+        note::NoteResponse,
+        reminder::{ReminderEvent, ReminderQuery, ReminderResponse, SetReminderRequest},
+    },
+    rrule::Recurrence,
+};
+use repository::reminder::ReminderRepository;
+use std::future::Future;
+
+use crate::{
+    error::ServiceError,
+    note::NoteLimits,
+    validation::{Validate, validate_limit, validate_timestamp},
+};
+
+/// Maximum number of due reminders fired per scheduler tick; the rest fire
+/// on the following ticks.
+const DUE_REMINDER_BATCH: u64 = 100;
+
+/// Trait abstracting the business operations on note reminders.
+///
+/// Implementations must be [`Send`], [`Sync`], [`Clone`], and `'static` so
+/// that they can be used as Axum shared state.
+pub trait ReminderService: Send + Sync + Clone + 'static {
+    /// Validates the recurrence rule and sets the reminder of a note,
+    /// replacing any previous one.
+    fn set_reminder(&self, id: i64, request: SetReminderRequest) -> impl Future<Output = Result<NoteResponse, ServiceError>> + Send;
+
+    /// Removes the reminder of a note, if any.
+    fn clear_reminder(&self, id: i64) -> impl Future<Output = Result<NoteResponse, ServiceError>> + Send;
+
+    /// Returns pending reminders, soonest first.
+    fn find_reminders(&self, query: ReminderQuery) -> impl Future<Output = Result<Vec<ReminderResponse>, ServiceError>> + Send;
+
+    /// Fires the reminders due before `now` and returns an event for each.
+    ///
+    /// Recurring reminders move on to their first occurrence after `now`,
+    /// so occurrences missed whilst no scheduler was running fire once;
+    /// other reminders are cleared.
+    fn fire_due_reminders(&self, now: DateTime<Utc>) -> impl Future<Output = Result<Vec<ReminderEvent>, ServiceError>> + Send;
+}
+
+/// Concrete [`ReminderService`] backed by a generic [`ReminderRepository`].
+#[derive(Clone)]
+pub struct ReminderServiceImpl<Repo: ReminderRepository> {
+    /// The repository used for data access.
+    repository: Repo,
+    /// The limits applied when validating requests.
+    limits: NoteLimits,
+    /// The time zone whose calendar recurring reminders follow.
+    time_zone: Tz,
+}
+
+impl<Repo: ReminderRepository> ReminderServiceImpl<Repo> {
+    /// Creates a new [`ReminderServiceImpl`] wrapping the given repository
+    /// and enforcing the given limits.
+    pub fn new(repository: Repo, limits: NoteLimits) -> Self {
+        Self {
+            repository,
+            limits,
+            time_zone: Tz::UTC,
+        }
+    }
+
+    /// Sets the time zone whose calendar recurring reminders follow, so that
+    /// they keep their local time of day across daylight saving changes.
+    pub fn time_zone(mut self, time_zone: Tz) -> Self {
+        self.time_zone = time_zone;
+        self
+    }
+}
+
+impl Validate for SetReminderRequest {
+    fn validate(&mut self, _limits: &NoteLimits) -> Result<(), ServiceError> {
+        let Some(raw) = &self.recurrence else {
+            return Ok(());
+        };
+
+        let rule: Recurrence = raw.parse().map_err(|err: String| {
+            tracing::warn!(value = raw.as_str(), "Validation failed: invalid recurrence rule");
+            ServiceError::Validation(format!("Field 'recurrence' is invalid: {err}"))
+        })?;
+
+        if rule.until.is_some_and(|until| until < *self.remind_at) {
+            tracing::warn!("Validation failed: recurrence ends before the reminder");
+            return Err(ServiceError::Validation("Field 'recurrence' must not end before 'remindAt'".into()));
+        }
+
+        self.recurrence = Some(rule.to_string());
+
+        Ok(())
+    }
+}
+
+impl Validate for ReminderQuery {
+    fn validate(&mut self, limits: &NoteLimits) -> Result<(), ServiceError> {
+        self.parsed_before = validate_timestamp(&self.before, "before")?;
+        self.parsed_limit = validate_limit(&self.limit, limits.default_size, limits.max_size)?;
+
+        Ok(())
+    }
+}
+
+/// Returns the first occurrence of `rule` on the calendar of `time_zone`
+/// after both `fired` and `now`, consuming one of its remaining occurrences
+/// per step, or [`None`] once the rule has ended.
+fn next_occurrence(rule: &mut Recurrence, fired: DateTime<Utc>, now: DateTime<Utc>, time_zone: Tz) -> Option<DateTime<Utc>> {
+    let mut occurrence = fired;
+
+    loop {
+        if let Some(count) = &mut rule.count {
+            *count -= 1;
+            if *count == 0 {
+                return None;
+            }
+        }
+
+        occurrence = rule.next_after(occurrence, time_zone)?;
+        if occurrence > now {
+            return Some(occurrence);
+        }
+    }
+}
+
+impl<Repo: ReminderRepository> ReminderService for ReminderServiceImpl<Repo> {
+    /// Validates and normalises the recurrence rule, then delegates to the
+    /// repository.
+    #[tracing::instrument(skip_all)]
+    async fn set_reminder(&self, id: i64, mut request: SetReminderRequest) -> Result<NoteResponse, ServiceError> {
+        request.validate(&self.limits)?;
+
+        self.repository
+            .set_reminder(id, Some(*request.remind_at), request.recurrence)
+            .await
+            .map_err(ServiceError::from)
+    }
+
+    /// Clears both reminder columns through the repository.
+    #[tracing::instrument(skip_all)]
+    async fn clear_reminder(&self, id: i64) -> Result<NoteResponse, ServiceError> {
+        self.repository.set_reminder(id, None, None).await.map_err(ServiceError::from)
+    }
+
+    /// Validates the bound and limit, then delegates to the repository.
+    #[tracing::instrument(skip_all)]
+    async fn find_reminders(&self, mut query: ReminderQuery) -> Result<Vec<ReminderResponse>, ServiceError> {
+        query.validate(&self.limits)?;
+
+        self.repository
+            .find_reminders(query.parsed_before, query.parsed_limit)
+            .await
+            .map_err(ServiceError::from)
+    }
+
+    /// Advances each due reminder through the repository, which skips those
+    /// another scheduler advanced first. A stored rule that no longer parses
+    /// is treated as a one-off reminder.
+    #[tracing::instrument(skip_all)]
+    async fn fire_due_reminders(&self, now: DateTime<Utc>) -> Result<Vec<ReminderEvent>, ServiceError> {
+        let due = self.repository.find_reminders(Some(now), DUE_REMINDER_BATCH).await?;
+        let mut events = Vec::with_capacity(due.len());
+
+        for reminder in due {
+            let fired = *reminder.remind_at;
+            let mut rule = reminder.recurrence.as_deref().and_then(|raw| {
+                raw.parse::<Recurrence>()
+                    .inspect_err(|err| tracing::warn!(id = reminder.note_id, rule = raw, %err, "Ignoring invalid stored recurrence rule"))
+                    .ok()
+            });
+
+            let next = rule.as_mut().and_then(|rule| next_occurrence(rule, fired, now, self.time_zone));
+            let recurrence = rule.filter(|_| next.is_some()).map(|rule| rule.to_string());
+
+            if self.repository.advance_reminder(reminder.note_id, fired, next, recurrence).await? {
+                events.push(ReminderEvent {
+                    note_id: reminder.note_id,
+                    title: reminder.title,
+                    remind_at: fired,
+                    next_remind_at: next,
+                });
+            }
+        }
+
+        Ok(events)
+    }
+}
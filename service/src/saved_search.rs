@@ -0,0 +1,127 @@
+//! Saved search service trait and its implementation.
+//!
+//! The [`SavedSearchService`] trait manages named searches and executes
+//! them, whilst [`SavedSearchServiceImpl`] provides the concrete
+//! implementation backed by a [`SavedSearchRepository`] and running the
+//! stored parameters through a [`NoteService`].
+
+use model::dto::{
+    note::NoteResponse,
+    pagination::PaginatedResponse,
+    saved_search::{CreateSavedSearchRequest, SavedSearchResponse},
+};
+use repository::saved_search::SavedSearchRepository;
+use std::future::Future;
+
+use crate::{
+    error::ServiceError,
+    note::{NoteLimits, NoteService},
+    validation::Validate,
+};
+
+/// Maximum allowed length for a saved search name, in characters.
+const MAX_SAVED_SEARCH_NAME_LEN: usize = 100;
+
+/// Trait abstracting the business operations on saved searches.
+///
+/// Implementations must be [`Send`], [`Sync`], [`Clone`], and `'static` so
+/// that they can be used as Axum shared state.
+pub trait SavedSearchService: Send + Sync + Clone + 'static {
+    /// Validates and persists a named search.
+    fn create(&self, request: CreateSavedSearchRequest) -> impl Future<Output = Result<SavedSearchResponse, ServiceError>> + Send;
+
+    /// Returns every saved search.
+    fn find_all(&self) -> impl Future<Output = Result<Vec<SavedSearchResponse>, ServiceError>> + Send;
+
+    /// Retrieves a single saved search by its primary key.
+    fn find_by_id(&self, id: i64) -> impl Future<Output = Result<SavedSearchResponse, ServiceError>> + Send;
+
+    /// Deletes a saved search by its primary key.
+    fn delete(&self, id: i64) -> impl Future<Output = Result<(), ServiceError>> + Send;
+
+    /// Executes a saved search, optionally on a different page than the
+    /// one stored with it.
+    fn find_notes(&self, id: i64, page: Option<String>) -> impl Future<Output = Result<PaginatedResponse<NoteResponse>, ServiceError>> + Send;
+}
+
+/// Concrete [`SavedSearchService`] backed by a generic
+/// [`SavedSearchRepository`] and executing searches through a
+/// [`NoteService`].
+#[derive(Clone)]
+pub struct SavedSearchServiceImpl<Repo: SavedSearchRepository, Notes: NoteService> {
+    /// The repository used for data access.
+    repository: Repo,
+    /// The service that runs the stored search parameters.
+    notes: Notes,
+    /// The limits applied when validating requests.
+    limits: NoteLimits,
+}
+
+impl<Repo: SavedSearchRepository, Notes: NoteService> SavedSearchServiceImpl<Repo, Notes> {
+    /// Creates a new [`SavedSearchServiceImpl`] wrapping the given repository
+    /// and note service and enforcing the given limits.
+    pub fn new(repository: Repo, notes: Notes, limits: NoteLimits) -> Self {
+        Self { repository, notes, limits }
+    }
+}
+
+impl Validate for CreateSavedSearchRequest {
+    fn validate(&mut self, limits: &NoteLimits) -> Result<(), ServiceError> {
+        if self.name.trim().is_empty() {
+            tracing::warn!("Validation failed: saved search name is empty");
+            return Err(ServiceError::Validation("Field 'name' must not be empty".into()));
+        }
+
+        if self.name.chars().count() > MAX_SAVED_SEARCH_NAME_LEN {
+            tracing::warn!(max = MAX_SAVED_SEARCH_NAME_LEN, "Validation failed: saved search name too long");
+            return Err(ServiceError::Validation(format!(
+                "Field 'name' must be at most {MAX_SAVED_SEARCH_NAME_LEN} characters"
+            )));
+        }
+
+        self.params.clone().validate(limits)
+    }
+}
+
+impl<Repo: SavedSearchRepository, Notes: NoteService> SavedSearchService for SavedSearchServiceImpl<Repo, Notes> {
+    /// Validates the name and the search parameters, then delegates to the
+    /// repository.
+    #[tracing::instrument(skip_all)]
+    async fn create(&self, mut request: CreateSavedSearchRequest) -> Result<SavedSearchResponse, ServiceError> {
+        request.validate(&self.limits)?;
+
+        self.repository.create(request).await.map_err(ServiceError::from)
+    }
+
+    /// Lists every saved search.
+    #[tracing::instrument(skip_all)]
+    async fn find_all(&self) -> Result<Vec<SavedSearchResponse>, ServiceError> {
+        self.repository.find_all().await.map_err(ServiceError::from)
+    }
+
+    /// Fetches a single saved search by ID.
+    #[tracing::instrument(skip_all)]
+    async fn find_by_id(&self, id: i64) -> Result<SavedSearchResponse, ServiceError> {
+        self.repository.find_by_id(id).await.map_err(ServiceError::from)
+    }
+
+    /// Deletes a saved search by ID.
+    #[tracing::instrument(skip_all)]
+    async fn delete(&self, id: i64) -> Result<(), ServiceError> {
+        self.repository.delete(id).await.map_err(ServiceError::from)
+    }
+
+    /// Loads the saved parameters, applies the page override, and runs them
+    /// through [`NoteService::find_all`] so that they are validated
+    /// exactly like an ad-hoc search.
+    #[tracing::instrument(skip_all)]
+    async fn find_notes(&self, id: i64, page: Option<String>) -> Result<PaginatedResponse<NoteResponse>, ServiceError> {
+        let mut parameters = self.repository.find_by_id(id).await?.params;
+
+        if page.is_some() {
+            parameters.page = page;
+        }
+
+        self.notes.find_all(parameters).await
+    }
+}
@@ -0,0 +1,572 @@
+//! Validation of note search parameters.
+//!
+//! [`SearchParams`] are parsed into [`NoteFilters`], the `filter` expression
+//! into a [`FilterExpr`], and the `prop.*` parameters into
+//! [`PropertyFilter`]s, each reporting the first violation found as a
+//! [`ServiceError::Validation`].
+
+use chrono::{DateTime, Utc};
+use model::{
+    dto::{
+        note::NoteFlag,
+        pagination::{
+            Comparison, FilterExpr, HighlightOptions, NoteFilters, PropertyFilter, SearchParams, SortDirection, SortField, SortFieldName, TextField,
+            TextFilter, TextMatch, TimestampField,
+        },
+        property::{MAX_PROPERTY_KEY_LEN, PROPERTY_PREFIX, PropertyType, PropertyValue, is_property_key},
+    },
+    query::{self, Expr, Operator, Term},
+};
+use std::collections::BTreeMap;
+
+use crate::{
+    error::ServiceError,
+    note::NoteLimits,
+    validation::{Validate, parse_timestamp, validate_bool, validate_page, validate_size, validate_timestamp},
+};
+
+/// Maximum number of IDs accepted by the `ids` filter.
+pub(crate) const MAX_IDS: usize = 100;
+
+/// Maximum length of the `filter` expression, in characters.
+const MAX_FILTER_LEN: usize = 1_000;
+
+/// The field names accepted in a `filter` expression.
+const FILTER_FIELDS: &str = "id, title, content, created, updated, pinned, starred, archived";
+
+/// Bounds on the `fragmentSize` parameter, in characters.
+const FRAGMENT_SIZE_RANGE: std::ops::RangeInclusive<usize> = 20..=1_000;
+
+/// Maximum length of the `preTag` and `postTag` markers, in characters.
+const MAX_MARKER_LEN: usize = 32;
+
+/// Maximum number of property filters per search.
+const MAX_PROPERTY_FILTERS: usize = 20;
+
+impl Validate for SearchParams {
+    fn validate(&mut self, limits: &NoteLimits) -> Result<(), ServiceError> {
+        self.filters = validate_filters(self)?;
+        self.highlight_options = validate_highlight(self)?;
+        self.parsed_page = validate_page(&self.page)?;
+        self.parsed_size = validate_size(&self.size, limits)?;
+        self.sort_fields = validate_order_by(&self.order_by)?.unwrap_or_default();
+
+        if validate_bool(&self.pinned_first, "pinnedFirst")?.unwrap_or(false) {
+            if self.sort_fields.is_empty() {
+                self.sort_fields.push(SortField {
+                    name: SortFieldName::Id,
+                    direction: SortDirection::Ascending,
+                });
+            }
+
+            self.sort_fields.retain(|field| field.name != SortFieldName::Pinned);
+            self.sort_fields.insert(
+                0,
+                SortField {
+                    name: SortFieldName::Pinned,
+                    direction: SortDirection::Descending,
+                },
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Validates that a string filter parameter is not blank when present.
+///
+/// Returns `Ok(())` immediately when the parameter is absent. `name` is the
+/// query-string key used verbatim in the error message.
+fn validate_string_filter(raw: &Option<String>, name: &str) -> Result<(), ServiceError> {
+    let Some(value) = raw else {
+        return Ok(());
+    };
+
+    if value.trim().is_empty() {
+        tracing::warn!(parameter = name, "Validation failed: string filter is blank");
+        return Err(ServiceError::Validation(format!("Parameter '{name}' must not be blank")));
+    }
+
+    Ok(())
+}
+
+/// Validates the highlighting parameters.
+///
+/// Returns `Ok(None)` unless `highlight` is `true`. The fragment size and
+/// markers are validated even then, so that a saved search cannot store
+/// values that would only fail once highlighting is switched on.
+fn validate_highlight(params: &SearchParams) -> Result<Option<HighlightOptions>, ServiceError> {
+    let mut options = HighlightOptions::default();
+
+    if let Some(raw) = &params.fragment_size {
+        let trimmed = raw.trim();
+        options.fragment_size = trimmed.parse().ok().filter(|size| FRAGMENT_SIZE_RANGE.contains(size)).ok_or_else(|| {
+            tracing::warn!(value = trimmed, "Validation failed: fragmentSize out of range");
+            ServiceError::Validation(format!(
+                "Parameter 'fragmentSize' must be an integer from {} to {}, got '{trimmed}'",
+                FRAGMENT_SIZE_RANGE.start(),
+                FRAGMENT_SIZE_RANGE.end()
+            ))
+        })?;
+    }
+
+    for (raw, name, marker) in [
+        (&params.pre_tag, "preTag", &mut options.pre_tag),
+        (&params.post_tag, "postTag", &mut options.post_tag),
+    ] {
+        let Some(raw) = raw else {
+            continue;
+        };
+
+        if raw.is_empty() || raw.chars().count() > MAX_MARKER_LEN {
+            tracing::warn!(parameter = name, "Validation failed: highlight marker length");
+            return Err(ServiceError::Validation(format!(
+                "Parameter '{name}' must be 1 to {MAX_MARKER_LEN} characters"
+            )));
+        }
+
+        raw.clone_into(marker);
+    }
+
+    Ok(validate_bool(&params.highlight, "highlight")?.unwrap_or(false).then_some(options))
+}
+
+/// Validates and parses the `ids` query parameter.
+///
+/// Returns an empty list when the parameter is absent. Returns a
+/// [`ServiceError::Validation`] when any element is blank or not a positive
+/// integer, or when more than [`MAX_IDS`] IDs are supplied. Duplicates are
+/// removed.
+fn validate_ids(raw: &Option<String>) -> Result<Vec<i64>, ServiceError> {
+    let Some(raw) = raw else {
+        return Ok(Vec::new());
+    };
+
+    let mut ids = raw
+        .split(',')
+        .map(str::trim)
+        .map(|token| {
+            token.parse::<i64>().ok().filter(|id| *id > 0).ok_or_else(|| {
+                tracing::warn!(value = token, "Validation failed: ids element is not a positive integer");
+                ServiceError::Validation(format!(
+                    "Parameter 'ids' must be a comma-separated list of positive integers, got '{token}'"
+                ))
+            })
+        })
+        .collect::<Result<Vec<i64>, ServiceError>>()?;
+
+    if ids.len() > MAX_IDS {
+        tracing::warn!(count = ids.len(), max = MAX_IDS, "Validation failed: too many ids");
+        return Err(ServiceError::Validation(format!(
+            "Parameter 'ids' must not contain more than {MAX_IDS} IDs"
+        )));
+    }
+
+    ids.sort_unstable();
+    ids.dedup();
+
+    Ok(ids)
+}
+
+/// Validates and parses the `filter` query parameter.
+///
+/// Returns `Ok(None)` when the parameter is absent. Syntax errors, unknown
+/// fields, operators that do not apply to a field, and malformed values are
+/// reported as a [`ServiceError::Validation`] carrying their position.
+fn validate_filter_expression(raw: &Option<String>) -> Result<Option<FilterExpr>, ServiceError> {
+    let Some(raw) = raw else {
+        return Ok(None);
+    };
+
+    if raw.chars().count() > MAX_FILTER_LEN {
+        tracing::warn!(max = MAX_FILTER_LEN, "Validation failed: filter expression too long");
+        return Err(ServiceError::Validation(format!(
+            "Parameter 'filter' must be at most {MAX_FILTER_LEN} characters"
+        )));
+    }
+
+    let expr = query::parse(raw).map_err(|err| {
+        tracing::warn!(position = err.position, "Validation failed: filter expression syntax error");
+        ServiceError::Validation(format!("Parameter 'filter' at position {}: {}", err.position, err.message))
+    })?;
+
+    convert_filter_expression(expr).map(Some)
+}
+
+/// Converts a parsed expression into a [`FilterExpr`], validating each term.
+fn convert_filter_expression(expr: Expr) -> Result<FilterExpr, ServiceError> {
+    match expr {
+        Expr::And(operands) => operands
+            .into_iter()
+            .map(convert_filter_expression)
+            .collect::<Result<_, _>>()
+            .map(FilterExpr::All),
+        Expr::Or(operands) => operands
+            .into_iter()
+            .map(convert_filter_expression)
+            .collect::<Result<_, _>>()
+            .map(FilterExpr::Any),
+        Expr::Not(operand) => convert_filter_expression(*operand).map(|operand| FilterExpr::Not(Box::new(operand))),
+        Expr::Term(term) => convert_filter_term(term),
+    }
+}
+
+/// Validates a single term against the fields it may address.
+fn convert_filter_term(term: Term) -> Result<FilterExpr, ServiceError> {
+    let invalid = |message: String, position: usize| {
+        tracing::warn!(field = term.field, position, "Validation failed: invalid filter term");
+        ServiceError::Validation(format!("Parameter 'filter' at position {position}: {message}"))
+    };
+
+    let text_field = match term.field.as_str() {
+        "title" => Some(TextField::Title),
+        "content" => Some(TextField::Content),
+        _ => None,
+    };
+
+    if let Some(field) = text_field {
+        let matching = match term.operator {
+            Operator::Equals => TextMatch::Exact,
+            Operator::Contains => TextMatch::Contains,
+            Operator::Prefix => TextMatch::Prefix,
+            other => {
+                return Err(invalid(
+                    format!("Operator '{other}' does not apply to text field '{}'", term.field),
+                    term.field_position,
+                ));
+            },
+        };
+
+        if term.value.trim().is_empty() {
+            return Err(invalid(format!("Value for '{}' must not be blank", term.field), term.value_position));
+        }
+
+        return Ok(FilterExpr::Text(TextFilter {
+            field,
+            pattern: term.value,
+            matching,
+            negated: false,
+        }));
+    }
+
+    let flag = match term.field.as_str() {
+        "pinned" => Some(NoteFlag::Pinned),
+        "starred" => Some(NoteFlag::Starred),
+        "archived" => Some(NoteFlag::Archived),
+        _ => None,
+    };
+
+    if let Some(flag) = flag {
+        if term.operator != Operator::Equals {
+            return Err(invalid(
+                format!("Operator '{}' does not apply to flag '{flag}'; use ':'", term.operator),
+                term.field_position,
+            ));
+        }
+
+        return match term.value.as_str() {
+            "true" => Ok(FilterExpr::Flag(flag, true)),
+            "false" => Ok(FilterExpr::Flag(flag, false)),
+            other => Err(invalid(
+                format!("Value for '{flag}' must be 'true' or 'false', got '{other}'"),
+                term.value_position,
+            )),
+        };
+    }
+
+    let timestamp_field = match term.field.as_str() {
+        "created" | "createdAt" => Some(TimestampField::CreatedAt),
+        "updated" | "updatedAt" => Some(TimestampField::UpdatedAt),
+        "id" => None,
+        other => {
+            return Err(invalid(
+                format!("Unknown field '{other}'. Valid fields: {FILTER_FIELDS}"),
+                term.field_position,
+            ));
+        },
+    };
+
+    let comparison = match term.operator {
+        Operator::Equals => Comparison::Equal,
+        Operator::Greater => Comparison::Greater,
+        Operator::GreaterOrEqual => Comparison::GreaterOrEqual,
+        Operator::Less => Comparison::Less,
+        Operator::LessOrEqual => Comparison::LessOrEqual,
+        other => {
+            return Err(invalid(
+                format!("Operator '{other}' does not apply to field '{}'", term.field),
+                term.field_position,
+            ));
+        },
+    };
+
+    let Some(field) = timestamp_field else {
+        let id = term.value.parse::<i64>().ok().filter(|id| *id > 0);
+        return id.map(|id| FilterExpr::Id(comparison, id)).ok_or_else(|| {
+            invalid(
+                format!("Value for 'id' must be a positive integer, got '{}'", term.value),
+                term.value_position,
+            )
+        });
+    };
+
+    // A bare date covers the whole day: `:` matches within it, `>` and `<=`
+    // compare against its end, and `<` and `>=` against its start.
+    match parse_timestamp(&term.value) {
+        Some((start, true)) => {
+            let end = start + chrono::Duration::days(1);
+
+            Ok(match comparison {
+                Comparison::Equal => FilterExpr::All(vec![
+                    FilterExpr::Timestamp(field, Comparison::GreaterOrEqual, start),
+                    FilterExpr::Timestamp(field, Comparison::Less, end),
+                ]),
+                Comparison::Greater => FilterExpr::Timestamp(field, Comparison::GreaterOrEqual, end),
+                Comparison::LessOrEqual => FilterExpr::Timestamp(field, Comparison::Less, end),
+                Comparison::Less | Comparison::GreaterOrEqual => FilterExpr::Timestamp(field, comparison, start),
+            })
+        },
+        Some((instant, false)) => Ok(FilterExpr::Timestamp(field, comparison, instant)),
+        None => Err(invalid(
+            format!(
+                "Value for '{}' must be an RFC 3339 timestamp or a YYYY-MM-DD date, got '{}'",
+                term.field, term.value
+            ),
+            term.value_position,
+        )),
+    }
+}
+
+/// Validates that a lower bound lies strictly before an upper bound when
+/// both are present.
+fn validate_range(after: Option<DateTime<Utc>>, before: Option<DateTime<Utc>>, after_name: &str, before_name: &str) -> Result<(), ServiceError> {
+    if let (Some(after), Some(before)) = (after, before)
+        && after >= before
+    {
+        tracing::warn!(parameter = after_name, "Validation failed: empty date range");
+        return Err(ServiceError::Validation(format!(
+            "Parameter '{after_name}' must be earlier than '{before_name}'"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Validates the raw filter parameters and converts them into
+/// [`NoteFilters`].
+fn validate_filters(params: &SearchParams) -> Result<NoteFilters, ServiceError> {
+    let text_filters = [
+        (&params.title, "title", TextField::Title, TextMatch::Contains, false),
+        (&params.content, "content", TextField::Content, TextMatch::Contains, false),
+        (&params.not_title, "-title", TextField::Title, TextMatch::Contains, true),
+        (&params.not_content, "-content", TextField::Content, TextMatch::Contains, true),
+        (&params.title_prefix, "titlePrefix", TextField::Title, TextMatch::Prefix, false),
+    ];
+
+    let fuzzy = validate_bool(&params.fuzzy, "fuzzy")?.unwrap_or(false);
+    if fuzzy && params.title.is_none() {
+        tracing::warn!("Validation failed: fuzzy without title");
+        return Err(ServiceError::Validation("Parameter 'fuzzy' requires the 'title' parameter".into()));
+    }
+
+    let mut text = Vec::new();
+    for (raw, name, field, matching, negated) in text_filters {
+        validate_string_filter(raw, name)?;
+
+        if fuzzy && name == "title" {
+            continue;
+        }
+
+        if let Some(pattern) = raw {
+            text.push(TextFilter {
+                field,
+                pattern: pattern.clone(),
+                matching,
+                negated,
+            });
+        }
+    }
+
+    let expression = validate_filter_expression(&params.filter)?;
+
+    let mut flags = Vec::new();
+    for (raw, name, flag) in [
+        (&params.pinned, "pinned", NoteFlag::Pinned),
+        (&params.starred, "starred", NoteFlag::Starred),
+    ] {
+        if let Some(value) = validate_bool(raw, name)? {
+            flags.push((flag, value));
+        }
+    }
+
+    let archived = match params.archived.as_deref().map(str::trim) {
+        None => expression
+            .as_ref()
+            .is_none_or(|expression| !expression.mentions(NoteFlag::Archived))
+            .then_some(false),
+        Some("any") => None,
+        Some("true") => Some(true),
+        Some("false") => Some(false),
+        Some(other) => {
+            tracing::warn!(value = other, "Validation failed: archived is not true, false, or any");
+            return Err(ServiceError::Validation(format!(
+                "Parameter 'archived' must be 'true', 'false', or 'any', got '{other}'"
+            )));
+        },
+    };
+    flags.extend(archived.map(|value| (NoteFlag::Archived, value)));
+
+    let filters = NoteFilters {
+        text,
+        case_sensitive: validate_bool(&params.case_sensitive, "caseSensitive")?.unwrap_or(false),
+        ids: validate_ids(&params.ids)?,
+        flags,
+        created_after: validate_timestamp(&params.created_after, "createdAfter")?,
+        created_before: validate_timestamp(&params.created_before, "createdBefore")?,
+        updated_after: validate_timestamp(&params.updated_after, "updatedAfter")?,
+        updated_before: validate_timestamp(&params.updated_before, "updatedBefore")?,
+        properties: validate_property_filters(&params.properties)?,
+        expression,
+        fuzzy_title: params.title.as_ref().filter(|_| fuzzy).map(|title| title.trim().to_owned()),
+    };
+
+    validate_range(filters.created_after, filters.created_before, "createdAfter", "createdBefore")?;
+    validate_range(filters.updated_after, filters.updated_before, "updatedAfter", "updatedBefore")?;
+
+    Ok(filters)
+}
+
+/// Validates and parses the `orderBy` query parameter.
+///
+/// Returns `Ok(None)` immediately when the parameter is absent. Returns a
+/// [`ServiceError::Validation`] when the string is blank or contains only
+/// commas, or when a field name is unrecognised. Each token may be prefixed
+/// with `+` (ascending, default) or `-` (descending).
+fn validate_order_by(raw: &Option<String>) -> Result<Option<Vec<SortField>>, ServiceError> {
+    let Some(raw) = raw else {
+        return Ok(None);
+    };
+
+    let fields: Vec<SortField> = raw
+        .split(',')
+        .map(str::trim)
+        .filter(|string| !string.is_empty())
+        .map(|token| {
+            let (direction, name) = if let Some(rest) = token.strip_prefix('-') {
+                (SortDirection::Descending, rest)
+            } else {
+                (SortDirection::Ascending, token.strip_prefix('+').unwrap_or(token))
+            };
+
+            let name: SortFieldName = name.parse().map_err(|err: String| {
+                tracing::warn!(field = name, "Validation failed: unknown sort field");
+                ServiceError::Validation(err)
+            })?;
+
+            Ok(SortField { name, direction })
+        })
+        .collect::<Result<Vec<SortField>, ServiceError>>()?;
+
+    if fields.is_empty() {
+        tracing::warn!("Validation failed: orderBy is present but contains no fields");
+        return Err(ServiceError::Validation(format!(
+            "Parameter 'orderBy' must contain at least one field. Valid fields: {}",
+            SortFieldName::all_names()
+        )));
+    }
+
+    Ok(Some(fields))
+}
+
+/// Parses the raw `prop.*` parameters into property filters, inferring the
+/// type of each value; the note service later applies the declared property
+/// schemas.
+///
+/// A query string such as `prop.priority>=2` arrives as the key
+/// `prop.priority>` with the value `2`, whilst `prop.priority>2` arrives as
+/// the key `prop.priority>2` with no value.
+fn validate_property_filters(raw: &BTreeMap<String, String>) -> Result<Vec<PropertyFilter>, ServiceError> {
+    if raw.len() > MAX_PROPERTY_FILTERS {
+        tracing::warn!(
+            count = raw.len(),
+            max = MAX_PROPERTY_FILTERS,
+            "Validation failed: too many property filters"
+        );
+        return Err(ServiceError::Validation(format!(
+            "At most {MAX_PROPERTY_FILTERS} property filters are allowed"
+        )));
+    }
+
+    raw.iter()
+        .map(|(name, value)| {
+            let operand = name.strip_prefix(PROPERTY_PREFIX).unwrap_or(name);
+
+            let (key, comparison, text) = match operand.find(['<', '>']) {
+                None => (operand, Comparison::Equal, value.as_str()),
+                Some(at) => {
+                    let (key, rest) = operand.split_at(at);
+                    let greater = rest.starts_with('>');
+
+                    match (&rest[1..], greater) {
+                        ("", true) => (key, Comparison::GreaterOrEqual, value.as_str()),
+                        ("", false) => (key, Comparison::LessOrEqual, value.as_str()),
+                        (text, true) if value.is_empty() => (key, Comparison::Greater, text),
+                        (text, false) if value.is_empty() => (key, Comparison::Less, text),
+                        _ => {
+                            tracing::warn!(parameter = name.as_str(), "Validation failed: malformed property filter");
+                            return Err(ServiceError::Validation(format!(
+                                "Parameter '{name}' is malformed: expected {PROPERTY_PREFIX}<key> followed by =, >, >=, <, or <= and a value"
+                            )));
+                        },
+                    }
+                },
+            };
+
+            if !is_property_key(key) {
+                tracing::warn!(parameter = name.as_str(), "Validation failed: invalid property key");
+                return Err(ServiceError::Validation(format!(
+                    "Parameter '{name}' names an invalid property key: expected a letter or '_' followed by letters, digits, '_', or '-', \
+                     at most {MAX_PROPERTY_KEY_LEN} characters"
+                )));
+            }
+
+            typed_property_filter(key, comparison, text, None)
+        })
+        .collect()
+}
+
+/// Builds a property filter, reading `text` as a value of type `kind`, or
+/// inferring its type if the key has no schema.
+pub(crate) fn typed_property_filter(
+    key: &str,
+    comparison: Comparison,
+    text: &str,
+    kind: Option<PropertyType>,
+) -> Result<PropertyFilter, ServiceError> {
+    let name = format!("{PROPERTY_PREFIX}{key}");
+
+    if text.is_empty() {
+        tracing::warn!(parameter = name.as_str(), "Validation failed: property filter without value");
+        return Err(ServiceError::Validation(format!("Parameter '{name}' must have a value")));
+    }
+
+    let value = match kind {
+        None => PropertyValue::infer(text),
+        Some(kind) => PropertyValue::parse_as(text, kind).ok_or_else(|| {
+            tracing::warn!(parameter = name.as_str(), %kind, "Validation failed: property filter of the wrong type");
+            ServiceError::Validation(format!("Parameter '{name}' must be a {kind}, got '{text}'"))
+        })?,
+    };
+
+    if value.kind() == PropertyType::Boolean && comparison != Comparison::Equal {
+        tracing::warn!(parameter = name.as_str(), "Validation failed: ordered comparison of a boolean property");
+        return Err(ServiceError::Validation(format!("Parameter '{name}' is a boolean and only supports '='")));
+    }
+
+    Ok(PropertyFilter {
+        key: key.to_owned(),
+        comparison,
+        text: text.to_owned(),
+        value,
+    })
+}
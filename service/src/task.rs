@@ -0,0 +1,84 @@
+//! Task service trait and its implementation.
+//!
+//! The [`TaskService`] trait reads and ticks the task list items of notes,
+//! whilst [`TaskServiceImpl`] provides the concrete implementation backed by
+//! a [`TaskRepository`].
+
+use model::dto::task::{NoteTaskResponse, TaskQuery, TaskResponse, UpdateTaskRequest};
+use repository::task::TaskRepository;
+use std::future::Future;
+
+use crate::{
+    error::ServiceError,
+    note::NoteLimits,
+    validation::{Validate, validate_bool, validate_limit},
+};
+
+/// Trait abstracting the business operations on task list items.
+///
+/// Implementations must be [`Send`], [`Sync`], [`Clone`], and `'static` so
+/// that they can be used as Axum shared state.
+pub trait TaskService: Send + Sync + Clone + 'static {
+    /// Returns the task list items of a note, in document order.
+    fn find_note_tasks(&self, id: i64) -> impl Future<Output = Result<Vec<NoteTaskResponse>, ServiceError>> + Send;
+
+    /// Ticks, clears, or toggles a task list item of a note by rewriting its
+    /// content.
+    fn update_task(&self, id: i64, index: usize, request: UpdateTaskRequest) -> impl Future<Output = Result<NoteTaskResponse, ServiceError>> + Send;
+
+    /// Returns task list items across unarchived notes.
+    fn find_tasks(&self, query: TaskQuery) -> impl Future<Output = Result<Vec<TaskResponse>, ServiceError>> + Send;
+}
+
+/// Concrete [`TaskService`] backed by a generic [`TaskRepository`].
+#[derive(Clone)]
+pub struct TaskServiceImpl<Repo: TaskRepository> {
+    /// The repository used for data access.
+    repository: Repo,
+    /// The limits applied when validating requests.
+    limits: NoteLimits,
+}
+
+impl<Repo: TaskRepository> TaskServiceImpl<Repo> {
+    /// Creates a new [`TaskServiceImpl`] wrapping the given repository and
+    /// enforcing the given limits.
+    pub fn new(repository: Repo, limits: NoteLimits) -> Self {
+        Self { repository, limits }
+    }
+}
+
+impl Validate for TaskQuery {
+    fn validate(&mut self, limits: &NoteLimits) -> Result<(), ServiceError> {
+        self.parsed_done = validate_bool(&self.done, "done")?;
+        self.parsed_limit = validate_limit(&self.limit, limits.default_size, limits.max_size)?;
+
+        Ok(())
+    }
+}
+
+impl<Repo: TaskRepository> TaskService for TaskServiceImpl<Repo> {
+    /// Delegates to the repository, which reads the content of the note
+    /// only.
+    #[tracing::instrument(skip_all)]
+    async fn find_note_tasks(&self, id: i64) -> Result<Vec<NoteTaskResponse>, ServiceError> {
+        self.repository.find_note_tasks(id).await.map_err(ServiceError::from)
+    }
+
+    /// Delegates the rewrite to the repository, which performs it in the
+    /// same transaction as the update.
+    #[tracing::instrument(skip_all)]
+    async fn update_task(&self, id: i64, index: usize, request: UpdateTaskRequest) -> Result<NoteTaskResponse, ServiceError> {
+        self.repository.set_task(id, index, request.done).await.map_err(ServiceError::from)
+    }
+
+    /// Validates the filter and limit, then delegates to the repository.
+    #[tracing::instrument(skip_all)]
+    async fn find_tasks(&self, mut query: TaskQuery) -> Result<Vec<TaskResponse>, ServiceError> {
+        query.validate(&self.limits)?;
+
+        self.repository
+            .find_tasks(query.parsed_done, query.parsed_limit)
+            .await
+            .map_err(ServiceError::from)
+    }
+}
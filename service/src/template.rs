@@ -0,0 +1,238 @@
+//! Note template service trait and its implementation.
+//!
+//! The [`TemplateService`] trait manages note templates and renders them
+//! into new notes, whilst [`TemplateServiceImpl`] provides the concrete
+//! implementation backed by a [`TemplateRepository`] and creating the
+//! rendered notes through a [`NoteService`].
+
+use chrono::Utc;
+use model::{
+    dto::{
+        datetime::DateDisplay,
+        note::{CreateNoteRequest, NoteResponse},
+        template::{CreateTemplateRequest, RenderTemplateRequest, TemplateResponse, UpdateTemplateRequest},
+    },
+    template::Pattern,
+};
+use repository::template::TemplateRepository;
+use serde_json::Map;
+use std::{collections::BTreeMap, future::Future};
+
+use crate::{error::ServiceError, note::NoteService};
+
+/// Maximum allowed length for a template name, in characters.
+const MAX_TEMPLATE_NAME_LEN: usize = 100;
+
+/// The template variable holding the current date in the request's time
+/// zone, as `YYYY-MM-DD`.
+const DATE_VARIABLE: &str = "date";
+
+/// The template variable holding the user name supplied with the request.
+const USER_VARIABLE: &str = "user";
+
+/// Trait abstracting the business operations on note templates.
+///
+/// Implementations must be [`Send`], [`Sync`], [`Clone`], and `'static` so
+/// that they can be used as Axum shared state.
+pub trait TemplateService: Send + Sync + Clone + 'static {
+    /// Validates the name and patterns and persists a note template.
+    fn create(&self, request: CreateTemplateRequest) -> impl Future<Output = Result<TemplateResponse, ServiceError>> + Send;
+
+    /// Returns every note template.
+    fn find_all(&self) -> impl Future<Output = Result<Vec<TemplateResponse>, ServiceError>> + Send;
+
+    /// Retrieves a single note template by its primary key.
+    fn find_by_id(&self, id: i64) -> impl Future<Output = Result<TemplateResponse, ServiceError>> + Send;
+
+    /// Validates and partially updates a note template.
+    fn update(&self, id: i64, request: UpdateTemplateRequest) -> impl Future<Output = Result<TemplateResponse, ServiceError>> + Send;
+
+    /// Deletes a note template by its primary key.
+    fn delete(&self, id: i64) -> impl Future<Output = Result<(), ServiceError>> + Send;
+
+    /// Renders a note template with the built-in and supplied variables and
+    /// creates a note from the result, validated like any other new note.
+    fn create_note(&self, id: i64, request: RenderTemplateRequest) -> impl Future<Output = Result<NoteResponse, ServiceError>> + Send;
+}
+
+/// Concrete [`TemplateService`] backed by a generic [`TemplateRepository`]
+/// and creating notes through a [`NoteService`].
+#[derive(Clone)]
+pub struct TemplateServiceImpl<Repo: TemplateRepository, Notes: NoteService> {
+    /// The repository used for data access.
+    repository: Repo,
+    /// The service that creates the rendered notes.
+    notes: Notes,
+}
+
+impl<Repo: TemplateRepository, Notes: NoteService> TemplateServiceImpl<Repo, Notes> {
+    /// Creates a new [`TemplateServiceImpl`] wrapping the given repository
+    /// and note service.
+    pub fn new(repository: Repo, notes: Notes) -> Self {
+        Self { repository, notes }
+    }
+}
+
+/// Validates the name and patterns of a new note template.
+fn validate_new_template(request: &CreateTemplateRequest) -> Result<(), ServiceError> {
+    validate_template_name(&request.name)?;
+    validate_pattern(&request.title, "title")?;
+    validate_pattern(&request.content, "content")?;
+
+    Ok(())
+}
+
+/// Validates the fields present in a note template update.
+fn validate_template_update(request: &UpdateTemplateRequest) -> Result<(), ServiceError> {
+    if let Some(name) = &request.name {
+        validate_template_name(name)?;
+    }
+
+    if let Some(title) = &request.title {
+        validate_pattern(title, "title")?;
+    }
+
+    if let Some(content) = &request.content {
+        validate_pattern(content, "content")?;
+    }
+
+    Ok(())
+}
+
+/// Validates the name of a note template.
+fn validate_template_name(name: &str) -> Result<(), ServiceError> {
+    if name.trim().is_empty() {
+        tracing::warn!("Validation failed: template name is empty");
+        return Err(ServiceError::Validation("Field 'name' must not be empty".into()));
+    }
+
+    if name.chars().count() > MAX_TEMPLATE_NAME_LEN {
+        tracing::warn!(max = MAX_TEMPLATE_NAME_LEN, "Validation failed: template name too long");
+        return Err(ServiceError::Validation(format!(
+            "Field 'name' must be at most {MAX_TEMPLATE_NAME_LEN} characters"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Parses the template pattern held by the field `name`.
+fn validate_pattern(raw: &str, name: &str) -> Result<Pattern, ServiceError> {
+    raw.parse().map_err(|err: String| {
+        tracing::warn!(field = name, "Validation failed: invalid template pattern");
+        ServiceError::Validation(format!("Field '{name}' is not a valid template: {err}"))
+    })
+}
+
+/// Collects the values of a template's variables: the built-in `date`, the
+/// custom ones, which must not redefine it, and `user`, taken from either
+/// the body's `user` field or `variables` but not both.
+fn template_values(request: RenderTemplateRequest) -> Result<BTreeMap<String, String>, ServiceError> {
+    let mut values = request.variables;
+
+    if values.contains_key(DATE_VARIABLE) {
+        tracing::warn!(variable = DATE_VARIABLE, "Validation failed: built-in template variable redefined");
+        return Err(ServiceError::Validation(format!(
+            "Variable '{DATE_VARIABLE}' is built in and must not be set in 'variables'"
+        )));
+    }
+
+    if let Some(user) = request.user {
+        if values.contains_key(USER_VARIABLE) {
+            tracing::warn!(variable = USER_VARIABLE, "Validation failed: template variable given twice");
+            return Err(ServiceError::Validation(format!(
+                "Variable '{USER_VARIABLE}' must be set either in 'user' or in 'variables', not both"
+            )));
+        }
+
+        values.insert(USER_VARIABLE.into(), user);
+    }
+
+    let today = Utc::now().with_timezone(&DateDisplay::current().time_zone).date_naive();
+    values.insert(DATE_VARIABLE.into(), today.format("%Y-%m-%d").to_string());
+
+    Ok(values)
+}
+
+impl<Repo: TemplateRepository, Notes: NoteService> TemplateService for TemplateServiceImpl<Repo, Notes> {
+    /// Validates the name and patterns, then delegates to the repository.
+    #[tracing::instrument(skip_all)]
+    async fn create(&self, request: CreateTemplateRequest) -> Result<TemplateResponse, ServiceError> {
+        validate_new_template(&request)?;
+
+        self.repository.create(request).await.map_err(ServiceError::from)
+    }
+
+    /// Lists every note template.
+    #[tracing::instrument(skip_all)]
+    async fn find_all(&self) -> Result<Vec<TemplateResponse>, ServiceError> {
+        self.repository.find_all().await.map_err(ServiceError::from)
+    }
+
+    /// Fetches a single note template by ID.
+    #[tracing::instrument(skip_all)]
+    async fn find_by_id(&self, id: i64) -> Result<TemplateResponse, ServiceError> {
+        self.repository.find_by_id(id).await.map_err(ServiceError::from)
+    }
+
+    /// Validates the fields present in the request, then delegates to the
+    /// repository.
+    #[tracing::instrument(skip_all)]
+    async fn update(&self, id: i64, request: UpdateTemplateRequest) -> Result<TemplateResponse, ServiceError> {
+        validate_template_update(&request)?;
+
+        self.repository.update(id, request).await.map_err(ServiceError::from)
+    }
+
+    /// Deletes a note template by ID.
+    #[tracing::instrument(skip_all)]
+    async fn delete(&self, id: i64) -> Result<(), ServiceError> {
+        self.repository.delete(id).await.map_err(ServiceError::from)
+    }
+
+    /// Fails with a validation error naming every variable without a value,
+    /// and passes the rendered note through [`NoteService::create`].
+    #[tracing::instrument(skip_all)]
+    async fn create_note(&self, id: i64, request: RenderTemplateRequest) -> Result<NoteResponse, ServiceError> {
+        let template = self.repository.find_by_id(id).await?;
+        let values = template_values(request)?;
+
+        let missing: Vec<&str> = template
+            .variables
+            .iter()
+            .map(String::as_str)
+            .filter(|name| !values.contains_key(*name))
+            .collect();
+        if !missing.is_empty() {
+            tracing::warn!(id, ?missing, "Validation failed: template variables without values");
+            let hint = if missing.contains(&USER_VARIABLE) {
+                format!(" ('{USER_VARIABLE}' is set through the 'user' field or 'variables')")
+            } else {
+                String::new()
+            };
+            return Err(ServiceError::Validation(format!(
+                "Missing values for template variables: {}{hint}",
+                missing.join(", ")
+            )));
+        }
+
+        let render = |stored: &str| {
+            let pattern: Pattern = stored
+                .parse()
+                .map_err(|err| ServiceError::Internal(format!("Invalid stored template pattern: {err}")))?;
+            pattern
+                .render(&values)
+                .map_err(|name| ServiceError::Internal(format!("Template variable '{name}' has no value")))
+        };
+
+        tracing::debug!(id, "Creating note from template");
+
+        self.notes
+            .create(CreateNoteRequest {
+                title: render(&template.title)?,
+                content: render(&template.content)?,
+                properties: Map::new(),
+            })
+            .await
+    }
+}
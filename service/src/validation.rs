@@ -0,0 +1,148 @@
+//! Validation shared by the services.
+//!
+//! Request DTOs and query parameter types implement the internal
+//! [`Validate`] trait; the helpers parse the paging, limit, boolean, and
+//! timestamp parameters common to several of them.
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+
+use crate::{error::ServiceError, note::NoteLimits};
+
+/// Default page number when none is provided by the client.
+const DEFAULT_PAGE: u64 = 1;
+
+/// Internal validation trait implemented by request DTOs and query
+/// parameter types.
+pub(crate) trait Validate {
+    /// Validates (and, where appropriate, normalises) the receiver against
+    /// the given limits.
+    ///
+    /// Returns `Ok(())` when the payload is valid, or a
+    /// [`ServiceError::Validation`] describing the first violation found.
+    fn validate(&mut self, limits: &NoteLimits) -> Result<(), ServiceError>;
+}
+
+/// Validates and parses a boolean query parameter (`true` or `false`).
+///
+/// Returns `Ok(None)` when the parameter is absent.
+pub(crate) fn validate_bool(raw: &Option<String>, name: &str) -> Result<Option<bool>, ServiceError> {
+    let Some(raw) = raw else {
+        return Ok(None);
+    };
+
+    match raw.trim() {
+        "true" => Ok(Some(true)),
+        "false" => Ok(Some(false)),
+        other => {
+            tracing::warn!(parameter = name, value = other, "Validation failed: not a boolean");
+            Err(ServiceError::Validation(format!(
+                "Parameter '{name}' must be 'true' or 'false', got '{other}'"
+            )))
+        },
+    }
+}
+
+/// Validates and parses a timestamp query parameter.
+///
+/// Accepts an RFC 3339 timestamp or a `YYYY-MM-DD` date, which denotes
+/// midnight UTC. Returns `Ok(None)` when the parameter is absent.
+pub(crate) fn validate_timestamp(raw: &Option<String>, name: &str) -> Result<Option<DateTime<Utc>>, ServiceError> {
+    let Some(raw) = raw else {
+        return Ok(None);
+    };
+
+    let trimmed = raw.trim();
+
+    match parse_timestamp(trimmed) {
+        Some((instant, _)) => Ok(Some(instant)),
+        None => {
+            tracing::warn!(parameter = name, value = trimmed, "Validation failed: not a timestamp");
+            Err(ServiceError::Validation(format!(
+                "Parameter '{name}' must be an RFC 3339 timestamp or a YYYY-MM-DD date, got '{trimmed}'"
+            )))
+        },
+    }
+}
+
+/// Parses an RFC 3339 timestamp or a `YYYY-MM-DD` date (midnight UTC),
+/// also reporting whether the value was a bare date.
+pub(crate) fn parse_timestamp(value: &str) -> Option<(DateTime<Utc>, bool)> {
+    if let Ok(date_time) = DateTime::parse_from_rfc3339(value) {
+        return Some((date_time.with_timezone(&Utc), false));
+    }
+
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .map(|date| (date.and_time(NaiveTime::MIN).and_utc(), true))
+}
+
+/// Validates and parses the `page` query parameter.
+///
+/// Returns [`DEFAULT_PAGE`] when the parameter is absent. Returns a
+/// [`ServiceError::Validation`] when the value is blank or not a valid
+/// positive integer. The result is always floored at `1`.
+pub(crate) fn validate_page(raw: &Option<String>) -> Result<u64, ServiceError> {
+    let Some(raw) = raw else {
+        return Ok(DEFAULT_PAGE);
+    };
+
+    let trimmed = raw.trim();
+
+    if trimmed.is_empty() {
+        tracing::warn!("Validation failed: page is blank");
+        return Err(ServiceError::Validation("Parameter 'page' must not be blank".into()));
+    }
+
+    trimmed.parse::<u64>().map(|value| value.max(1)).map_err(|_| {
+        tracing::warn!(value = trimmed, "Validation failed: page is not a valid positive integer");
+        ServiceError::Validation(format!("Parameter 'page' must be a positive integer, got '{trimmed}'"))
+    })
+}
+
+/// Validates and parses the `size` query parameter.
+///
+/// Returns [`NoteLimits::default_size`] when the parameter is absent. Returns
+/// a [`ServiceError::Validation`] when the value is blank, not a valid
+/// positive integer, or exceeds [`NoteLimits::max_size`].
+pub(crate) fn validate_size(raw: &Option<String>, limits: &NoteLimits) -> Result<u64, ServiceError> {
+    let Some(raw) = raw else {
+        return Ok(limits.default_size);
+    };
+
+    let trimmed = raw.trim();
+
+    if trimmed.is_empty() {
+        tracing::warn!("Validation failed: size is blank");
+        return Err(ServiceError::Validation("Parameter 'size' must not be blank".into()));
+    }
+
+    let value = trimmed.parse::<u64>().map_err(|_| {
+        tracing::warn!(value = trimmed, "Validation failed: size is not a valid positive integer");
+        ServiceError::Validation(format!("Parameter 'size' must be a positive integer, got '{trimmed}'"))
+    })?;
+
+    if value > limits.max_size {
+        tracing::warn!(size = value, max = limits.max_size, "Validation failed: page size too large");
+        return Err(ServiceError::Validation(format!("Parameter 'size' must not exceed {}", limits.max_size)));
+    }
+
+    Ok(value)
+}
+
+/// Validates and parses a `limit` query parameter.
+///
+/// Returns `default` when the parameter is absent. Returns a
+/// [`ServiceError::Validation`] when the value is not an integer from `1` to
+/// `max`.
+pub(crate) fn validate_limit(raw: &Option<String>, default: u64, max: u64) -> Result<u64, ServiceError> {
+    let Some(raw) = raw else {
+        return Ok(default);
+    };
+
+    let trimmed = raw.trim();
+
+    trimmed.parse().ok().filter(|limit| (1..=max).contains(limit)).ok_or_else(|| {
+        tracing::warn!(value = trimmed, max, "Validation failed: limit out of range");
+        ServiceError::Validation(format!("Parameter 'limit' must be an integer from 1 to {max}, got '{trimmed}'"))
+    })
+}